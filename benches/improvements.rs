@@ -0,0 +1,60 @@
+//! Times the bookkeeping of improvements: a best position replaced by cloning the position into a
+//! new allocation against one copied into the allocation it already has, which is how `Optimizer`
+//! records them, and a whole `Optimizer` run on an objective the turtles keep improving on, to put
+//! that bookkeeping beside everything else an iteration does.
+//!
+//! `cargo bench --bench improvements`
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use ndarray::Array1;
+use turtle_swarm_optimizer::{CubicBoundary, Optimizer};
+
+const TURTLES: usize = 200;
+const DIMENSIONS: usize = 1000;
+const ITERATIONS: usize = 50;
+
+// Centred off the origin, so the swarm is a while getting there and improves all the way.
+fn parabola(x: &Array1<f64>) -> f64 {
+    x.iter().map(|x| (x - 0.5) * (x - 0.5)).sum()
+}
+
+// The fastest of a few runs, as the least disturbed by whatever else the machine is doing.
+fn time(name: &str, mut run: impl FnMut()) {
+    let fastest = (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            run();
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or(Duration::ZERO);
+    println!("{name:<12} {fastest:>10.2?}");
+}
+
+fn main() {
+    let position = Array1::from_elem(DIMENSIONS, 0.5);
+    let mut best = Array1::zeros(DIMENSIONS);
+    let replacements = TURTLES * ITERATIONS;
+    println!("{replacements} best positions of {DIMENSIONS} dimensions replaced");
+    time("clone", || {
+        for _ in 0..replacements {
+            best = black_box(&position).clone();
+        }
+    });
+    time("clone_from", || {
+        for _ in 0..replacements {
+            best.clone_from(black_box(&position));
+        }
+    });
+    black_box(&best);
+
+    let boundaries = CubicBoundary::new(DIMENSIONS, -1., 1.);
+    println!("{TURTLES} turtles in {DIMENSIONS} dimensions for {ITERATIONS} iterations");
+    time("optimizer", || {
+        let mut optimizer = Optimizer::new_seeded(TURTLES, boundaries, &parabola, -1., 3);
+        optimizer.max_iterations = Some(ITERATIONS);
+        black_box(optimizer.optimize());
+    });
+}
@@ -0,0 +1,51 @@
+//! Times the per-turtle `Optimizer` against the `MatrixOptimizer` on a large swarm in high
+//! dimensions, with an objective cheap enough that the update dominates.
+//!
+//! `cargo bench --bench updates`
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use ndarray::Array1;
+use turtle_swarm_optimizer::matrix::MatrixOptimizer;
+use turtle_swarm_optimizer::{CubicBoundary, Optimizer};
+
+const TURTLES: usize = 1000;
+const DIMENSIONS: usize = 100;
+const ITERATIONS: usize = 50;
+
+fn parabola(x: &Array1<f64>) -> f64 {
+    x.iter().map(|x| x * x).sum()
+}
+
+// The fastest of a few runs, as the least disturbed by whatever else the machine is doing.
+fn time(name: &str, mut run: impl FnMut() -> f64) {
+    let fastest = (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(run());
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or(Duration::ZERO);
+    println!(
+        "{name:<10} {:>10.2?} for {ITERATIONS} iterations, {:>8.2?} per iteration",
+        fastest,
+        fastest / ITERATIONS as u32
+    );
+}
+
+fn main() {
+    let boundaries = CubicBoundary::new(DIMENSIONS, -1., 1.);
+    println!("{TURTLES} turtles in {DIMENSIONS} dimensions");
+    time("optimizer", || {
+        let mut optimizer = Optimizer::new_seeded(TURTLES, boundaries, &parabola, -1., 3);
+        optimizer.max_iterations = Some(ITERATIONS);
+        optimizer.optimize().best_score
+    });
+    time("matrix", || {
+        let mut matrix = MatrixOptimizer::new_seeded(TURTLES, boundaries, &parabola, -1., 3);
+        matrix.max_iterations = Some(ITERATIONS);
+        matrix.optimize().best_score
+    });
+}
@@ -0,0 +1,3 @@
+//! Re-exports the main crate, whose `ffi`, `python` and `wasm` modules carry the exported symbols.
+
+pub use turtle_swarm_optimizer::*;
@@ -0,0 +1,111 @@
+#ifndef TURTLE_SWARM_OPTIMIZER_H
+#define TURTLE_SWARM_OPTIMIZER_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// An opaque optimizer handle for C callers.
+typedef struct TsoOptimizer TsoOptimizer;
+
+// An objective function implemented in C. It receives the position and its length along with the
+// `user_data` pointer given to `tso_optimizer_set_objective`.
+typedef double (*TsoObjective)(const double *position, uintptr_t dimensions, void *user_data);
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Creates an optimizer searching the cube `[lower, upper]^dimensions`. Free it with
+// `tso_optimizer_destroy`.
+struct TsoOptimizer *tso_optimizer_new(uintptr_t turtles,
+                                       uintptr_t dimensions,
+                                       double lower,
+                                       double upper,
+                                       double goal);
+
+// Like `tso_optimizer_new`, but the turtles are placed reproducibly from `seed`.
+struct TsoOptimizer *tso_optimizer_new_seeded(uintptr_t turtles,
+                                              uintptr_t dimensions,
+                                              double lower,
+                                              double upper,
+                                              double goal,
+                                              uint64_t seed);
+
+// Registers the objective function. `user_data` is handed back to every call.
+//
+// # Safety
+// `optimizer` must be null or a live pointer returned by one of the constructors.
+void tso_optimizer_set_objective(struct TsoOptimizer *optimizer,
+                                 TsoObjective objective,
+                                 void *user_data);
+
+// Limits `tso_optimizer_optimize` to `max_iterations` iterations in total. Zero lifts the limit.
+//
+// # Safety
+// `optimizer` must be null or a live pointer returned by one of the constructors.
+void tso_optimizer_set_max_iterations(struct TsoOptimizer *optimizer, uintptr_t max_iterations);
+
+// Limits `tso_optimizer_optimize` to `max_evaluations` objective evaluations in total. Zero lifts
+// the limit.
+//
+// # Safety
+// `optimizer` must be null or a live pointer returned by one of the constructors.
+void tso_optimizer_set_max_evaluations(struct TsoOptimizer *optimizer, uintptr_t max_evaluations);
+
+// Performs a single iteration of the algorithm. Returns whether the goal has been reached.
+//
+// # Safety
+// `optimizer` must be null or a live pointer returned by one of the constructors.
+bool tso_optimizer_step(struct TsoOptimizer *optimizer);
+
+// Iterates until the goal is reached, or until a limit set with `tso_optimizer_set_max_iterations`
+// or `tso_optimizer_set_max_evaluations` is spent.
+//
+// # Safety
+// `optimizer` must be null or a live pointer returned by one of the constructors.
+void tso_optimizer_optimize(struct TsoOptimizer *optimizer);
+
+// The best score observed so far, or NaN for a null optimizer.
+//
+// # Safety
+// `optimizer` must be null or a live pointer returned by one of the constructors.
+double tso_optimizer_best_score(const struct TsoOptimizer *optimizer);
+
+// Copies up to `capacity` coordinates of the best position into `out` and returns the number of
+// dimensions, so callers may pass a null `out` to size their buffer first.
+//
+// # Safety
+// `optimizer` must be null or a live pointer returned by one of the constructors, and `out` must
+// be null or valid for `capacity` writes.
+uintptr_t tso_optimizer_best_position(const struct TsoOptimizer *optimizer,
+                                      double *out,
+                                      uintptr_t capacity);
+
+// The number of iterations performed so far.
+//
+// # Safety
+// `optimizer` must be null or a live pointer returned by one of the constructors.
+uintptr_t tso_optimizer_iterations(const struct TsoOptimizer *optimizer);
+
+// The number of objective evaluations performed so far.
+//
+// # Safety
+// `optimizer` must be null or a live pointer returned by one of the constructors.
+uintptr_t tso_optimizer_evaluations(const struct TsoOptimizer *optimizer);
+
+// Frees an optimizer. Passing null is a no-op.
+//
+// # Safety
+// `optimizer` must be null or a pointer returned by one of the constructors that has not been
+// destroyed yet.
+void tso_optimizer_destroy(struct TsoOptimizer *optimizer);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* TURTLE_SWARM_OPTIMIZER_H */
@@ -0,0 +1,119 @@
+//! Ways of summarizing several scores of one position into a single score.
+//!
+//! Besides the perturbation sets of `robust`, aggregation extends to noisy objectives through
+//! `Replicated`, which evaluates every position several times. Because the optimizer only ever
+//! sees the aggregated score, personal and global bests are compared on it consistently, so a
+//! risk-averse user can ask for a high quantile or the CVaR instead of the mean.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ndarray::Array1;
+
+/// How a set of scores is reduced to one. Lower is better throughout, so the worst case is the
+/// largest score and high quantiles are pessimistic.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Aggregation {
+    Mean,
+    Median,
+    /// The largest score.
+    Worst,
+    /// The `q` quantile, interpolated linearly between the ranked scores. `q` is clamped to
+    /// `[0, 1]`.
+    Quantile(f64),
+    /// The conditional value at risk: the mean of the scores at or above the `alpha` quantile,
+    /// so `CVaR(0.9)` averages the worst tenth. `alpha` is clamped to `[0, 1]`.
+    CVaR(f64),
+}
+
+impl Aggregation {
+    /// Reduces `scores`, which are reordered in the process. Nothing to aggregate is infinitely
+    /// bad.
+    pub fn apply(&self, scores: &mut [f64]) -> f64 {
+        if scores.is_empty() {
+            return f64::INFINITY;
+        }
+        match *self {
+            Aggregation::Mean => scores.iter().sum::<f64>() / scores.len() as f64,
+            Aggregation::Median => Aggregation::Quantile(0.5).apply(scores),
+            Aggregation::Worst => scores.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Aggregation::Quantile(q) => {
+                scores.sort_unstable_by(f64::total_cmp);
+                let rank = q.clamp(0., 1.) * (scores.len() - 1) as f64;
+                let below = rank as usize;
+                let above = (below + 1).min(scores.len() - 1);
+                let share = rank - below as f64;
+                scores[below] + share * (scores[above] - scores[below])
+            }
+            Aggregation::CVaR(alpha) => {
+                scores.sort_unstable_by(f64::total_cmp);
+                let tail = scores.len() - (alpha.clamp(0., 1.) * scores.len() as f64) as usize;
+                let tail = &scores[scores.len() - tail.max(1)..];
+                tail.iter().sum::<f64>() / tail.len() as f64
+            }
+        }
+    }
+}
+
+/// A noisy objective evaluated `replicates` times per position, with the scores aggregated.
+pub struct Replicated<'a> {
+    objective_function: &'a (dyn Fn(&Array1<f64>) -> f64 + Sync),
+    pub replicates: usize,
+    pub aggregation: Aggregation,
+}
+
+impl<'a> Replicated<'a> {
+    /// Averages `replicates` evaluations.
+    pub fn new(
+        objective_function: &'a (dyn Fn(&Array1<f64>) -> f64 + Sync),
+        replicates: usize,
+    ) -> Self {
+        Self {
+            objective_function,
+            replicates,
+            aggregation: Aggregation::Mean,
+        }
+    }
+
+    pub fn evaluate(&self, position: &Array1<f64>) -> f64 {
+        let mut scores: Vec<f64> = (0..self.replicates.max(1))
+            .map(|_| (self.objective_function)(position))
+            .collect();
+        self.aggregation.apply(&mut scores)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quantiles_interpolate_between_ranks() {
+        let mut scores = [4., 1., 3., 2., 5.];
+        assert_eq!(Aggregation::Worst.apply(&mut scores), 5.);
+        assert_eq!(Aggregation::Quantile(0.5).apply(&mut scores), 3.);
+        assert_eq!(Aggregation::Quantile(0.875).apply(&mut scores), 4.5);
+        assert_eq!(Aggregation::Quantile(2.).apply(&mut scores), 5.);
+        assert_eq!(Aggregation::Worst.apply(&mut []), f64::INFINITY);
+    }
+
+    #[test]
+    fn replicates_are_aggregated_with_the_chosen_risk_measure() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+        // Deterministic "noise" cycling through 0, 1, ..., 9 on top of the true value.
+        let calls = AtomicUsize::new(0);
+        let noisy = |x: &Array1<f64>| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            x[0] + (calls.load(Ordering::Relaxed) % 10) as f64
+        };
+        let mut replicated = Replicated::new(&noisy, 10);
+        let position = Array1::zeros(1);
+        assert_eq!(replicated.evaluate(&position), 4.5);
+        replicated.aggregation = Aggregation::Median;
+        assert_eq!(replicated.evaluate(&position), 4.5);
+        replicated.aggregation = Aggregation::CVaR(0.8);
+        assert_eq!(replicated.evaluate(&position), 8.5);
+        replicated.aggregation = Aggregation::CVaR(1.);
+        assert_eq!(replicated.evaluate(&position), 9.);
+        assert_eq!(calls.load(Ordering::Relaxed), 40);
+    }
+}
@@ -0,0 +1,270 @@
+//! Several swarms on islands of their own, trading their best turtles now and then.
+//!
+//! Every island is an ordinary `Optimizer` searching on its own, so the islands easily settle in
+//! different basins. Every `interval` iterations each island sends copies of its `migrants` best
+//! turtles to its neighbours, as given by the `Migration` topology, where they replace the worst
+//! turtles. Good basins spread through the archipelago without every island collapsing onto the
+//! first one found, which makes the whole more robust on multimodal objectives.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ndarray::Array1;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::boundary::Boundary;
+use crate::objective::ObjectiveFunction;
+use crate::{CubicBoundary, OptimizationResult, Optimizer, Termination, Turtle};
+
+/// Which islands an island sends its migrants to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Migration {
+    /// The next island, with the last one sending to the first.
+    #[default]
+    Ring,
+    /// Every other island.
+    Full,
+    /// Another island drawn at random every migration.
+    Random,
+}
+
+pub struct ArchipelagoOptimizer<'a, B = CubicBoundary> {
+    pub islands: Vec<Optimizer<'a, B>>,
+    /// Iterations every island runs between migrations.
+    pub interval: usize,
+    /// How many of its best turtles an island sends to each neighbour.
+    pub migrants: usize,
+    pub migration: Migration,
+    /// How many migrations have taken place.
+    pub migrations: usize,
+    rng: ChaCha12Rng,
+}
+
+/// What the islands found.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArchipelagoResult {
+    pub best_score: f64,
+    pub best_position: Array1<f64>,
+    /// The island that found the best.
+    pub best_island: usize,
+    pub migrations: usize,
+    /// Each island's own result, in island order.
+    pub islands: Vec<OptimizationResult>,
+}
+
+impl<'a, B: Boundary + Clone> ArchipelagoOptimizer<'a, B> {
+    /// `islands` islands of `turtles` turtles each, placed reproducibly from `seed`. Migrations
+    /// go around a ring every 10 iterations, two turtles at a time.
+    pub fn new_seeded(
+        islands: usize,
+        turtles: usize,
+        boundaries: B,
+        objective_function: impl Into<ObjectiveFunction<'a>>,
+        goal: f64,
+        seed: u64,
+    ) -> Self {
+        let objective_function = objective_function.into();
+        let islands = (0..islands as u64)
+            .map(|island| {
+                Optimizer::new_seeded(
+                    turtles,
+                    boundaries.clone(),
+                    objective_function.clone(),
+                    goal,
+                    seed.wrapping_add(island),
+                )
+            })
+            .collect();
+        let mut archipelago = Self::from_islands(islands);
+        archipelago.rng = ChaCha12Rng::seed_from_u64(seed);
+        archipelago
+    }
+}
+
+impl<'a, B: Boundary> ArchipelagoOptimizer<'a, B> {
+    /// An archipelago of optimizers set up however you like, each of which keeps its own goal,
+    /// limits and settings.
+    pub fn from_islands(islands: Vec<Optimizer<'a, B>>) -> Self {
+        Self {
+            islands,
+            interval: 10,
+            migrants: 2,
+            migration: Migration::Ring,
+            migrations: 0,
+            rng: ChaCha12Rng::seed_from_u64(0),
+        }
+    }
+
+    /// Runs the islands, migrating between them every `interval` iterations, until one of them
+    /// reaches its goal or every one of them has stopped for another reason. Islands cut short
+    /// by another island's goal report `Termination::Criterion`.
+    pub fn optimize(&mut self) -> ArchipelagoResult {
+        let mut stopped: Vec<Option<Termination>> = self.islands.iter().map(|_| None).collect();
+        'epochs: while stopped.iter().any(Option::is_none) {
+            for (island, stop) in self.islands.iter_mut().zip(stopped.iter_mut()) {
+                for _ in 0..self.interval.max(1) {
+                    if stop.is_some() {
+                        break;
+                    }
+                    *stop = island.termination().or_else(|| island.step());
+                }
+                if *stop == Some(Termination::GoalReached) {
+                    break 'epochs;
+                }
+            }
+            if stopped.iter().any(Option::is_none) {
+                self.migrate();
+            }
+        }
+
+        let islands: Vec<OptimizationResult> = self
+            .islands
+            .iter()
+            .zip(stopped.iter())
+            .map(|(island, stop)| island.result(stop.unwrap_or(Termination::Criterion)))
+            .collect();
+        let best_island = (0..self.islands.len())
+            .reduce(|best, island| {
+                let direction = self.islands[island].direction();
+                if direction.is_better(
+                    self.islands[island].best_score,
+                    self.islands[best].best_score,
+                ) {
+                    island
+                } else {
+                    best
+                }
+            })
+            .unwrap_or(0);
+        ArchipelagoResult {
+            best_score: islands
+                .get(best_island)
+                .map_or(f64::NAN, |best| best.best_score),
+            best_position: islands
+                .get(best_island)
+                .map_or_else(|| Array1::zeros(0), |best| best.best_position.clone()),
+            best_island,
+            migrations: self.migrations,
+            islands,
+        }
+    }
+
+    /// Sends copies of every island's best turtles to its neighbours, where they replace the
+    /// worst turtles and may become the island's best.
+    pub fn migrate(&mut self) {
+        let count = self.islands.len();
+        if count < 2 || self.migrants == 0 {
+            return;
+        }
+        let emigrants: Vec<Vec<Turtle>> = self
+            .islands
+            .iter()
+            .map(|island| {
+                let direction = island.direction();
+                let mut ranked: Vec<&Turtle> = island.turtles.iter().collect();
+                ranked.sort_by(|a, b| {
+                    if direction.is_better(a.best_score, b.best_score) {
+                        core::cmp::Ordering::Less
+                    } else if direction.is_better(b.best_score, a.best_score) {
+                        core::cmp::Ordering::Greater
+                    } else {
+                        core::cmp::Ordering::Equal
+                    }
+                });
+                ranked.into_iter().take(self.migrants).cloned().collect()
+            })
+            .collect();
+
+        for (source, emigrants) in emigrants.iter().enumerate() {
+            let targets: Vec<usize> = match self.migration {
+                Migration::Ring => Vec::from([(source + 1) % count]),
+                Migration::Full => (0..count).filter(|target| *target != source).collect(),
+                Migration::Random => {
+                    let target = self.rng.gen_range(0..count - 1);
+                    Vec::from([if target >= source { target + 1 } else { target }])
+                }
+            };
+            for target in targets {
+                self.islands[target].welcome(emigrants);
+            }
+        }
+        self.migrations += 1;
+    }
+}
+
+impl<B: Boundary> Optimizer<'_, B> {
+    // Puts copies of `immigrants` in place of the turtles with the worst personal bests.
+    fn welcome(&mut self, immigrants: &[Turtle]) {
+        let direction = self.direction();
+        for immigrant in immigrants {
+            let worst = (0..self.turtles.len()).reduce(|worst, turtle| {
+                if direction.is_better(
+                    self.turtles[worst].best_score,
+                    self.turtles[turtle].best_score,
+                ) {
+                    turtle
+                } else {
+                    worst
+                }
+            });
+            let Some(worst) = worst else {
+                return;
+            };
+            if !direction.is_better(immigrant.best_score, self.turtles[worst].best_score) {
+                continue;
+            }
+            self.turtles[worst] = immigrant.clone();
+            if direction.is_better(immigrant.best_score, self.best_score) {
+                self.best_score = immigrant.best_score;
+                self.best_position = immigrant.best_position.clone();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Two basins per dimension, the one around -1 slightly deeper.
+    fn double_well(x: &Array1<f64>) -> f64 {
+        x.iter()
+            .map(|x| (x * x - 1.) * (x * x - 1.) + 0.1 * x)
+            .sum()
+    }
+
+    #[test]
+    fn islands_share_their_best_turtles() {
+        let boundaries = CubicBoundary::new(2, -3., 3.);
+        let mut archipelago =
+            ArchipelagoOptimizer::new_seeded(3, 6, boundaries, &double_well, -10., 5);
+        for island in archipelago.islands.iter_mut() {
+            island.iterate();
+        }
+        let before: Vec<f64> = archipelago
+            .islands
+            .iter()
+            .map(|island| island.best_score)
+            .collect();
+        archipelago.migrate();
+        for (island, best) in before.iter().enumerate() {
+            assert!(archipelago.islands[(island + 1) % 3].best_score <= *best);
+        }
+
+        for island in archipelago.islands.iter_mut() {
+            island.max_iterations = Some(40);
+        }
+        archipelago.interval = 5;
+        let result = archipelago.optimize();
+        assert_eq!(result.islands.len(), 3);
+        assert_eq!(
+            result.best_score,
+            result.islands[result.best_island].best_score
+        );
+        assert!(result
+            .islands
+            .iter()
+            .all(|island| result.best_score <= island.best_score));
+        assert_eq!(result.migrations, 1 + 7);
+    }
+}
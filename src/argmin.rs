@@ -0,0 +1,173 @@
+//! Integration with the [argmin](https://argmin-rs.org) optimization framework.
+//!
+//! [`TurtleSwarm`] implements argmin's `Solver` so the TSO can be driven by an argmin `Executor`
+//! together with its observers, checkpoints and termination handling. In the other direction,
+//! [`Problem`] implements `CostFunction`, so a problem defined for this crate can be handed to any
+//! argmin solver as-is.
+
+use ::argmin::core::{CostFunction, Error, IterState, Problem as ArgminProblem, Solver, KV};
+use ndarray::Array1;
+use rand::{thread_rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::{external_objective, CubicBoundary, Optimizer, OptimizerParams, Problem};
+
+impl CostFunction for Problem<'_> {
+    type Param = Array1<f64>;
+    type Output = f64;
+
+    fn cost(&self, param: &Self::Param) -> Result<Self::Output, Error> {
+        Ok((self.objective_function)(param))
+    }
+}
+
+/// The argmin state used by [`TurtleSwarm`]. It holds the swarm's best position and score.
+pub type TurtleState = IterState<Array1<f64>, (), (), (), (), f64>;
+
+/// The turtle swarm optimizer as an argmin solver.
+pub struct TurtleSwarm {
+    turtles: usize,
+    boundaries: CubicBoundary,
+    seed: Option<u64>,
+    params: OptimizerParams,
+    // Built once the solver is initialized, from the seed when there is one, and kept across
+    // iterations so the bests, the velocities and every random choice carry over.
+    optimizer: Option<Optimizer<'static>>,
+}
+
+impl TurtleSwarm {
+    pub fn new(turtles: usize, boundaries: CubicBoundary) -> Self {
+        Self {
+            turtles,
+            boundaries,
+            seed: None,
+            params: OptimizerParams::default(),
+            optimizer: None,
+        }
+    }
+
+    /// Sets the coefficients of the velocity update.
+    pub fn with_params(mut self, params: OptimizerParams) -> Self {
+        self.params = params;
+        self
+    }
+
+    /// Places the turtles reproducibly from `seed` when the solver is initialized.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+}
+
+impl<O> Solver<O, TurtleState> for TurtleSwarm
+where
+    O: CostFunction<Param = Array1<f64>, Output = f64>,
+{
+    fn name(&self) -> &str {
+        "Turtle Swarm Optimization"
+    }
+
+    fn init(
+        &mut self,
+        _problem: &mut ArgminProblem<O>,
+        state: TurtleState,
+    ) -> Result<(TurtleState, Option<KV>), Error> {
+        let rng = match self.seed {
+            Some(seed) => ChaCha12Rng::seed_from_u64(seed),
+            None => ChaCha12Rng::from_rng(thread_rng()).expect("the thread rng does not fail"),
+        };
+        let mut optimizer = Optimizer::with_rng(
+            self.turtles,
+            self.boundaries,
+            &external_objective,
+            f64::NEG_INFINITY,
+            rng,
+        );
+        optimizer.params = self.params;
+        self.optimizer = Some(optimizer);
+        Ok((state, None))
+    }
+
+    fn next_iter(
+        &mut self,
+        problem: &mut ArgminProblem<O>,
+        state: TurtleState,
+    ) -> Result<(TurtleState, Option<KV>), Error> {
+        // argmin counts evaluations through its own `Problem`, which is not `Sync`, so the swarm is
+        // scored through ask and tell for one iteration. A failure leaves the swarm where it was.
+        let optimizer = self
+            .optimizer
+            .as_mut()
+            .ok_or_else(|| Error::msg("the solver has not been initialized"))?;
+        let scores = optimizer
+            .ask()
+            .iter()
+            .map(|position| problem.cost(position))
+            .collect::<Result<Vec<f64>, Error>>()?;
+        optimizer.tell(&scores);
+        Ok((
+            state
+                .param(optimizer.best_position.clone())
+                .cost(optimizer.best_score),
+            None,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::argmin::core::{Executor, State};
+
+    #[test]
+    fn executor_drives_the_swarm() {
+        fn parabola(x: &Array1<f64>) -> f64 {
+            (x * x).sum()
+        }
+        let problem = Problem {
+            objective_function: &parabola,
+            boundaries: CubicBoundary::new(2, -1., 1.),
+        };
+        let solver = TurtleSwarm::new(10, problem.boundaries).with_seed(7);
+
+        let result = Executor::new(problem, solver)
+            .configure(|state| state.max_iters(5))
+            .run()
+            .unwrap();
+
+        assert_eq!(result.state().get_iter(), 5);
+        assert!(result.state().get_best_cost() < 2.);
+        assert!(result.state().get_best_param().is_some());
+    }
+
+    #[test]
+    fn the_swarm_keeps_its_bests_between_iterations() {
+        fn parabola(x: &Array1<f64>) -> f64 {
+            (x * x).sum()
+        }
+        let problem = Problem {
+            objective_function: &parabola,
+            boundaries: CubicBoundary::new(2, -1., 1.),
+        };
+        let params = OptimizerParams {
+            inertia: 0.7,
+            cognitive: 1.5,
+            social: 1.5,
+        };
+        let solver = TurtleSwarm::new(10, problem.boundaries)
+            .with_seed(7)
+            .with_params(params);
+
+        let mut optimizer = Optimizer::new_seeded(10, problem.boundaries, &parabola, -1., 7);
+        optimizer.params = params;
+        for _ in 0..20 {
+            optimizer.iterate();
+        }
+        let result = Executor::new(problem, solver)
+            .configure(|state| state.max_iters(20))
+            .run()
+            .unwrap();
+
+        assert_eq!(result.state().get_best_cost(), optimizer.best_score);
+    }
+}
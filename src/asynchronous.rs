@@ -0,0 +1,120 @@
+//! Asynchronous objectives, enabled with the `async` feature.
+//!
+//! An objective that is a request to a scoring service spends its time waiting, and the swarm's
+//! positions can all be waited on at once. An `AsyncEvaluator` calls an objective returning a
+//! future for every position the optimizer asks about, keeps up to `concurrency` of them in
+//! flight, and tells the optimizer the scores in order. The optimizer itself is driven through
+//! ask and tell, so it is made with `external_objective`:
+//!
+//! `AsyncEvaluator::new(|x| score_remotely(x.to_vec()), 8).optimize(&mut optimizer).await`
+//!
+//! The futures are polled by whatever executor awaits `optimize`; nothing here spawns tasks. A
+//! future cannot borrow the position it scores, so it takes a copy of what it needs.
+
+use core::future::Future;
+
+use futures::stream::{self, StreamExt};
+use ndarray::Array1;
+
+use crate::boundary::Boundary;
+use crate::{OptimizationResult, Optimizer};
+
+pub struct AsyncEvaluator<F> {
+    objective_function: F,
+    /// How many evaluations may be in flight at once, at least one.
+    pub concurrency: usize,
+}
+
+impl<F, Fut> AsyncEvaluator<F>
+where
+    F: Fn(&Array1<f64>) -> Fut,
+    Fut: Future<Output = f64>,
+{
+    pub fn new(objective_function: F, concurrency: usize) -> Self {
+        Self {
+            objective_function,
+            concurrency,
+        }
+    }
+
+    /// Scores every position, with up to `concurrency` evaluations in flight, in order.
+    pub async fn evaluate(&self, positions: &[Array1<f64>]) -> Vec<f64> {
+        stream::iter(
+            positions
+                .iter()
+                .map(|position| (self.objective_function)(position)),
+        )
+        .buffered(self.concurrency.max(1))
+        .collect()
+        .await
+    }
+
+    /// Drives `optimizer` through ask and tell until `optimize` would stop.
+    pub async fn optimize<B: Boundary>(
+        &self,
+        optimizer: &mut Optimizer<'_, B>,
+    ) -> OptimizationResult {
+        let mut termination = optimizer.termination();
+        loop {
+            if let Some(termination) = termination {
+                return optimizer.result(termination);
+            }
+            let scores = self.evaluate(&optimizer.ask()).await;
+            termination = optimizer.tell(&scores);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{external_objective, CubicBoundary, Termination};
+    use core::pin::Pin;
+    use core::task::{Context, Poll};
+    use std::cell::Cell;
+
+    // Pending on its first poll, like a request waiting on the network.
+    struct Later(bool);
+
+    impl Future for Later {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<()> {
+            if self.0 {
+                return Poll::Ready(());
+            }
+            self.0 = true;
+            context.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+
+    #[test]
+    fn evaluations_run_concurrently_up_to_the_limit() {
+        let (in_flight, most) = (Cell::new(0), Cell::new(0));
+        let remote = |x: &Array1<f64>| {
+            let score = x.mapv(|x| x * x).sum();
+            let (in_flight, most) = (&in_flight, &most);
+            async move {
+                in_flight.set(in_flight.get() + 1);
+                most.set(most.get().max(in_flight.get()));
+                Later(false).await;
+                in_flight.set(in_flight.get() - 1);
+                score
+            }
+        };
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &external_objective, -1., 3);
+        optimizer.max_iterations = Some(20);
+        let evaluator = AsyncEvaluator::new(remote, 4);
+        let result = futures::executor::block_on(evaluator.optimize(&mut optimizer));
+
+        assert_eq!(result.termination, Termination::IterationLimit);
+        assert_eq!(result.evaluations, 200);
+        assert_eq!(most.get(), 4);
+        assert_eq!(
+            result.best_score,
+            result.best_position.mapv(|x| x * x).sum()
+        );
+    }
+}
@@ -0,0 +1,174 @@
+//! Basin hopping for landscapes whose funnels lie far apart.
+//!
+//! The swarm alternates between converging locally and jumping as a whole by a large random
+//! displacement. After every jump the new local minimum is compared with the one the swarm jumped
+//! from and accepted Metropolis style: always when it is better, and with probability
+//! `exp(-worsening / temperature)` when it is not. Rejected hops put the swarm back where it was.
+//! Better and worse follow the optimizer's `Direction`.
+
+use ndarray::Array1;
+use ndarray_rand::rand::Rng;
+
+use crate::{Direction, Optimizer};
+
+#[derive(Clone, Copy, Debug)]
+pub struct BasinHopping {
+    pub hops: usize,
+    /// The largest jump along each dimension, as a fraction of the search space's width.
+    pub step: f64,
+    pub temperature: f64,
+    /// Iterations of local convergence after each jump.
+    pub local_iterations: usize,
+}
+
+impl Default for BasinHopping {
+    fn default() -> Self {
+        Self {
+            hops: 50,
+            step: 0.5,
+            temperature: 1.,
+            local_iterations: 20,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BasinHoppingResult {
+    pub best_score: f64,
+    pub best_position: Array1<f64>,
+    pub hops: usize,
+    pub accepted: usize,
+}
+
+impl BasinHopping {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hops `optimizer`'s swarm around. Afterwards the swarm sits in the last accepted basin and
+    /// the optimizer's best is the best seen over every hop.
+    pub fn run(&self, optimizer: &mut Optimizer) -> BasinHoppingResult {
+        let shape = optimizer.boundaries.shape;
+        let (lower, upper) = (optimizer.boundaries.lower, optimizer.boundaries.upper);
+        let step = self.step * (upper - lower);
+        let direction = optimizer.direction();
+        // How much worse `candidate` is than `current`, in either direction.
+        let worsening = |candidate: f64, current: f64| match direction {
+            Direction::Minimize => candidate - current,
+            Direction::Maximize => current - candidate,
+        };
+
+        if !optimizer.best_score.is_finite() {
+            self.converge(optimizer);
+        }
+        let mut best_score = optimizer.best_score;
+        let mut best_position = optimizer.best_position.clone();
+        let mut current_score = optimizer.best_score;
+        let mut accepted = 0;
+
+        for _ in 0..self.hops {
+            let saved_turtles = optimizer.turtles.clone();
+            let saved_best = (optimizer.best_score, optimizer.best_position.clone());
+
+            let jump: Array1<f64> = (0..shape)
+                .map(|_| optimizer.rng.gen_range(-1.0..=1.0) * step)
+                .collect();
+            for turtle in optimizer.turtles.iter_mut() {
+                turtle.position = (&turtle.position + &jump).mapv(|x| x.clamp(lower, upper));
+                turtle.velocity = f64::EPSILON * Array1::ones(shape);
+                turtle.best_score = direction.worst();
+            }
+            optimizer.best_score = direction.worst();
+            self.converge(optimizer);
+
+            let candidate = optimizer.best_score;
+            if direction.is_better(candidate, best_score) {
+                best_score = candidate;
+                best_position = optimizer.best_position.clone();
+            }
+            let accept = !direction.is_better(current_score, candidate)
+                || optimizer.rng.gen::<f64>()
+                    < (-worsening(candidate, current_score) / self.temperature).exp();
+            if accept {
+                current_score = candidate;
+                accepted += 1;
+            } else {
+                optimizer.turtles = saved_turtles;
+                (optimizer.best_score, optimizer.best_position) = saved_best;
+            }
+        }
+
+        optimizer.best_score = best_score;
+        optimizer.best_position = best_position.clone();
+        BasinHoppingResult {
+            best_score,
+            best_position,
+            hops: self.hops,
+            accepted,
+        }
+    }
+
+    fn converge(&self, optimizer: &mut Optimizer) {
+        for _ in 0..self.local_iterations.max(1) {
+            optimizer.iterate();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CubicBoundary;
+
+    #[test]
+    fn hops_never_lose_the_best_basin() {
+        let funnels = |x: &Array1<f64>| {
+            x.iter()
+                .map(|x| ((x - 3.).powi(2)).min((x + 3.).powi(2) - 1.))
+                .sum()
+        };
+        let boundaries = CubicBoundary::new(2, -5., 5.);
+        let mut optimizer = Optimizer::new_seeded(6, boundaries, &funnels, -2., 3);
+        let hopping = BasinHopping {
+            hops: 20,
+            local_iterations: 2,
+            ..BasinHopping::new()
+        };
+        let result = hopping.run(&mut optimizer);
+
+        assert_eq!(result.hops, 20);
+        assert!(result.accepted <= result.hops);
+        assert_eq!(optimizer.best_score, result.best_score);
+        assert_eq!(funnels(&result.best_position), result.best_score);
+        assert!(optimizer
+            .turtles
+            .iter()
+            .all(|turtle| turtle.best_score >= result.best_score));
+    }
+
+    #[test]
+    fn maximizing_hops_keep_the_highest_basin() {
+        let funnels = |x: &Array1<f64>| {
+            -x.iter()
+                .map(|x| ((x - 3.).powi(2)).min((x + 3.).powi(2) - 1.))
+                .sum::<f64>()
+        };
+        let boundaries = CubicBoundary::new(2, -5., 5.);
+        let mut optimizer = Optimizer::new_seeded(6, boundaries, &funnels, 2., 3);
+        optimizer.set_direction(Direction::Maximize);
+        let hopping = BasinHopping {
+            hops: 20,
+            local_iterations: 2,
+            ..BasinHopping::new()
+        };
+        let result = hopping.run(&mut optimizer);
+
+        assert!(result.best_score.is_finite());
+        assert_eq!(optimizer.best_score, result.best_score);
+        assert_eq!(funnels(&result.best_position), result.best_score);
+        assert!(optimizer
+            .turtles
+            .iter()
+            .all(|turtle| turtle.best_score <= result.best_score));
+    }
+}
@@ -0,0 +1,185 @@
+//! The standard test objectives for validating optimizers and comparing their settings.
+//!
+//! Each benchmark knows its recommended search domain, where its global minimum lies and what
+//! it scores, for any number of dimensions.
+//!
+//! | benchmark  | domain            | minimum at       |
+//! |------------|-------------------|------------------|
+//! | Sphere     | [-5.12, 5.12]     | 0                |
+//! | Rosenbrock | [-5, 10]          | 1                |
+//! | Rastrigin  | [-5.12, 5.12]     | 0                |
+//! | Ackley     | [-32.768, 32.768] | 0                |
+//! | Griewank   | [-600, 600]       | 0                |
+//! | Schwefel   | [-500, 500]       | 420.9687...      |
+
+use std::f64::consts::{E, PI};
+
+use ndarray::Array1;
+
+use crate::{CubicBoundary, Problem};
+
+// Where each coordinate of Schwefel's minimum lies, and the value that shifts the minimum to 0.
+const SCHWEFEL_OPTIMUM: f64 = 420.968_746_359_982;
+const SCHWEFEL_OFFSET: f64 = 418.982_887_272_433_8;
+
+pub fn sphere(x: &Array1<f64>) -> f64 {
+    x.mapv(|x| x * x).sum()
+}
+
+pub fn rosenbrock(x: &Array1<f64>) -> f64 {
+    x.windows(2)
+        .into_iter()
+        .map(|pair| 100. * (pair[1] - pair[0] * pair[0]).powi(2) + (1. - pair[0]).powi(2))
+        .sum()
+}
+
+pub fn rastrigin(x: &Array1<f64>) -> f64 {
+    10. * x.len() as f64 + x.mapv(|x| x * x - 10. * (2. * PI * x).cos()).sum()
+}
+
+pub fn ackley(x: &Array1<f64>) -> f64 {
+    let n = x.len().max(1) as f64;
+    let squares = x.mapv(|x| x * x).sum() / n;
+    let cosines = x.mapv(|x| (2. * PI * x).cos()).sum() / n;
+    -20. * (-0.2 * squares.sqrt()).exp() - cosines.exp() + 20. + E
+}
+
+pub fn griewank(x: &Array1<f64>) -> f64 {
+    let squares = x.mapv(|x| x * x).sum() / 4000.;
+    let cosines: f64 = x
+        .iter()
+        .enumerate()
+        .map(|(i, x)| (x / ((i + 1) as f64).sqrt()).cos())
+        .product();
+    squares - cosines + 1.
+}
+
+pub fn schwefel(x: &Array1<f64>) -> f64 {
+    SCHWEFEL_OFFSET * x.len() as f64 - x.mapv(|x| x * x.abs().sqrt().sin()).sum()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Benchmark {
+    Sphere,
+    Rosenbrock,
+    Rastrigin,
+    Ackley,
+    Griewank,
+    Schwefel,
+}
+
+impl Benchmark {
+    pub const ALL: [Benchmark; 6] = [
+        Benchmark::Sphere,
+        Benchmark::Rosenbrock,
+        Benchmark::Rastrigin,
+        Benchmark::Ackley,
+        Benchmark::Griewank,
+        Benchmark::Schwefel,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Benchmark::Sphere => "sphere",
+            Benchmark::Rosenbrock => "rosenbrock",
+            Benchmark::Rastrigin => "rastrigin",
+            Benchmark::Ackley => "ackley",
+            Benchmark::Griewank => "griewank",
+            Benchmark::Schwefel => "schwefel",
+        }
+    }
+
+    pub fn function(self) -> fn(&Array1<f64>) -> f64 {
+        match self {
+            Benchmark::Sphere => sphere,
+            Benchmark::Rosenbrock => rosenbrock,
+            Benchmark::Rastrigin => rastrigin,
+            Benchmark::Ackley => ackley,
+            Benchmark::Griewank => griewank,
+            Benchmark::Schwefel => schwefel,
+        }
+    }
+
+    pub fn evaluate(self, x: &Array1<f64>) -> f64 {
+        self.function()(x)
+    }
+
+    /// The recommended lower and upper bound of every dimension.
+    pub fn bounds(self) -> (f64, f64) {
+        match self {
+            Benchmark::Sphere | Benchmark::Rastrigin => (-5.12, 5.12),
+            Benchmark::Rosenbrock => (-5., 10.),
+            Benchmark::Ackley => (-32.768, 32.768),
+            Benchmark::Griewank => (-600., 600.),
+            Benchmark::Schwefel => (-500., 500.),
+        }
+    }
+
+    pub fn boundaries(self, dimensions: usize) -> CubicBoundary {
+        let (lower, upper) = self.bounds();
+        CubicBoundary::new(dimensions, lower, upper)
+    }
+
+    pub fn optimum_position(self, dimensions: usize) -> Array1<f64> {
+        match self {
+            Benchmark::Rosenbrock => Array1::ones(dimensions),
+            Benchmark::Schwefel => Array1::from_elem(dimensions, SCHWEFEL_OPTIMUM),
+            _ => Array1::zeros(dimensions),
+        }
+    }
+
+    /// The score at the global minimum, zero for every benchmark here.
+    pub fn optimum_value(self) -> f64 {
+        0.
+    }
+
+    /// The benchmark over its recommended domain.
+    pub fn problem(self, dimensions: usize) -> Problem<'static> {
+        let objective_function: &'static (dyn Fn(&Array1<f64>) -> f64 + Sync) = match self {
+            Benchmark::Sphere => &sphere,
+            Benchmark::Rosenbrock => &rosenbrock,
+            Benchmark::Rastrigin => &rastrigin,
+            Benchmark::Ackley => &ackley,
+            Benchmark::Griewank => &griewank,
+            Benchmark::Schwefel => &schwefel,
+        };
+        Problem {
+            objective_function,
+            boundaries: self.boundaries(dimensions),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Optimizer, OptimizerParams};
+
+    #[test]
+    fn benchmarks_bottom_out_at_their_known_optima() {
+        for benchmark in Benchmark::ALL {
+            let optimum = benchmark.optimum_position(5);
+            let problem = benchmark.problem(5);
+            let (lower, upper) = benchmark.bounds();
+            assert!(optimum.iter().all(|x| *x >= lower && *x <= upper));
+            assert!(
+                (benchmark.evaluate(&optimum) - benchmark.optimum_value()).abs() < 1e-9,
+                "{} at its optimum",
+                benchmark.name()
+            );
+            let nearby = &optimum + 0.1;
+            assert!((problem.objective_function)(&nearby) > benchmark.optimum_value());
+        }
+
+        let problem = Benchmark::Sphere.problem(3);
+        let mut optimizer =
+            Optimizer::new_seeded(20, problem.boundaries, problem.objective_function, 1e-6, 4);
+        optimizer.params = OptimizerParams {
+            inertia: 0.6,
+            cognitive: 0.7,
+            social: 0.7,
+        };
+        optimizer.max_iterations = Some(500);
+        assert!(optimizer.optimize().best_score <= 1e-6);
+    }
+}
@@ -0,0 +1,10 @@
+fn main() {
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:8080".to_string());
+    println!("turtles are listening on http://{}", address);
+    if let Err(error) = turtle_swarm_optimizer::server::serve(&address) {
+        eprintln!("tso-server: {}", error);
+        std::process::exit(1);
+    }
+}
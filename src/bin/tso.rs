@@ -0,0 +1,3 @@
+fn main() {
+    std::process::exit(turtle_swarm_optimizer::cli::main());
+}
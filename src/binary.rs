@@ -0,0 +1,359 @@
+//! Binary turtles for bitstring problems such as feature selection and knapsacks.
+//!
+//! This follows Kennedy and Eberhart's binary particle swarm: every bit has a real valued velocity,
+//! a sigmoid turns that velocity into the probability of the bit being set, and positions are
+//! sampled bit by bit from those probabilities.
+//!
+//! Runs end the way the continuous optimizer's do: at the goal, at `max_iterations`, or when a
+//! `stopping` criterion or one of the `observers` says so, and `optimize` returns the same
+//! `OptimizationResult`, with the best bitstrings as positions of zeros and ones.
+
+use ndarray::Array1;
+use rand::{thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::observer::Observer;
+use crate::stopping::{Progress, StoppingCriterion};
+use crate::{Direction, OptimizationResult, Termination};
+
+/// A turtle carrying a bitstring instead of a point.
+#[derive(Debug)]
+pub struct BinaryTurtle {
+    pub bits: Vec<bool>,
+    velocity: Array1<f64>,
+    pub best_score: f64,
+    pub best_bits: Vec<bool>,
+}
+
+impl BinaryTurtle {
+    fn new<R: Rng + ?Sized>(dimensions: usize, rng: &mut R) -> Self {
+        Self {
+            bits: (0..dimensions).map(|_| rng.gen_bool(0.5)).collect(),
+            velocity: Array1::zeros(dimensions),
+            best_score: f64::INFINITY,
+            best_bits: vec![false; dimensions],
+        }
+    }
+}
+
+fn as_array(bits: &[bool]) -> Array1<f64> {
+    bits.iter().map(|bit| if *bit { 1. } else { 0. }).collect()
+}
+
+fn sigmoid(velocity: f64) -> f64 {
+    1. / (1. + (-velocity).exp())
+}
+
+/// Minimizes an objective over bitstrings of a fixed length.
+pub struct BinaryOptimizer<'a> {
+    pub turtles: Vec<BinaryTurtle>,
+    pub dimensions: usize,
+    pub iterations: usize,
+    /// How many times the objective has been evaluated.
+    pub evaluations: usize,
+    pub best_score: f64,
+    pub best_bits: Vec<bool>,
+    pub objective_function: &'a (dyn Fn(&[bool]) -> f64 + Sync),
+    pub goal: f64,
+    /// The most iterations `optimize` may spend in total before giving up on the goal.
+    pub max_iterations: Option<usize>,
+    /// Decides when `optimize` stops in place of the goal and `max_iterations`, see `stopping`.
+    pub stopping: Option<Box<dyn StoppingCriterion<BinaryOptimizer<'a>> + Send + 'a>>,
+    /// Callbacks with read access at the end of every iteration, see `observer`.
+    pub observers: Vec<Box<dyn Observer<BinaryOptimizer<'a>> + Send + 'a>>,
+    // Whether an observer has asked the run to stop.
+    observer_stop: bool,
+    /// How strongly turtles are pulled towards the personal and swarm bests. Binary turtles flip
+    /// coins rather than crawl, so they are not bound by the turtle velocity.
+    pub acceleration: f64,
+    /// Velocities are clamped to `[-v_max, v_max]` so no bit becomes certain.
+    pub v_max: f64,
+    rng: ChaCha12Rng,
+}
+
+impl<'a> BinaryOptimizer<'a> {
+    pub fn new(
+        turtles: usize,
+        dimensions: usize,
+        objective_function: &'a (dyn Fn(&[bool]) -> f64 + Sync),
+        goal: f64,
+    ) -> Self {
+        let rng = ChaCha12Rng::from_rng(thread_rng()).expect("the thread rng does not fail");
+        Self::new_using(turtles, dimensions, objective_function, goal, rng)
+    }
+
+    /// Like `new`, but the run is reproducible from `seed`.
+    pub fn new_seeded(
+        turtles: usize,
+        dimensions: usize,
+        objective_function: &'a (dyn Fn(&[bool]) -> f64 + Sync),
+        goal: f64,
+        seed: u64,
+    ) -> Self {
+        let rng = ChaCha12Rng::seed_from_u64(seed);
+        Self::new_using(turtles, dimensions, objective_function, goal, rng)
+    }
+
+    fn new_using(
+        turtles: usize,
+        dimensions: usize,
+        objective_function: &'a (dyn Fn(&[bool]) -> f64 + Sync),
+        goal: f64,
+        mut rng: ChaCha12Rng,
+    ) -> Self {
+        Self {
+            turtles: (0..turtles)
+                .map(|_| BinaryTurtle::new(dimensions, &mut rng))
+                .collect(),
+            dimensions,
+            iterations: 0,
+            evaluations: 0,
+            best_score: f64::INFINITY,
+            best_bits: vec![false; dimensions],
+            objective_function,
+            goal,
+            max_iterations: None,
+            stopping: None,
+            observers: Vec::new(),
+            observer_stop: false,
+            acceleration: 2.,
+            v_max: 4.,
+            rng,
+        }
+    }
+
+    fn evaluate(&mut self) {
+        for turtle in self.turtles.iter_mut() {
+            let score = (self.objective_function)(&turtle.bits);
+            self.evaluations += 1;
+            if score < turtle.best_score {
+                turtle.best_score = score;
+                turtle.best_bits = turtle.bits.clone();
+                if score < self.best_score {
+                    self.best_score = score;
+                    self.best_bits = turtle.bits.clone();
+                }
+            }
+        }
+    }
+
+    fn update(&mut self) {
+        let best = as_array(&self.best_bits);
+        for turtle in self.turtles.iter_mut() {
+            let bits = as_array(&turtle.bits);
+            let personal = &as_array(&turtle.best_bits) - &bits;
+            let social = &best - &bits;
+            for dimension in 0..self.dimensions {
+                let velocity = turtle.velocity[dimension]
+                    + self.acceleration * self.rng.gen::<f64>() * personal[dimension]
+                    + self.acceleration * self.rng.gen::<f64>() * social[dimension];
+                turtle.velocity[dimension] = velocity.clamp(-self.v_max, self.v_max);
+                turtle.bits[dimension] =
+                    self.rng.gen::<f64>() < sigmoid(turtle.velocity[dimension]);
+            }
+        }
+    }
+
+    /// Iterates until the best observed score matches the goal, or the run is stopped otherwise.
+    pub fn optimize(&mut self) -> OptimizationResult {
+        loop {
+            if let Some(termination) = self.termination() {
+                return self.result(termination);
+            }
+            self.iterate();
+        }
+    }
+
+    /// Moves the turtles once. Returns why `optimize` would stop here, if it would.
+    pub fn step(&mut self) -> Option<Termination> {
+        self.iterate();
+        self.termination()
+    }
+
+    fn result(&self, termination: Termination) -> OptimizationResult {
+        OptimizationResult {
+            best_position: as_array(&self.best_bits),
+            best_score: self.best_score,
+            iterations: self.iterations,
+            evaluations: self.evaluations,
+            turtle_bests: self
+                .turtles
+                .iter()
+                .map(|turtle| (turtle.best_score, as_array(&turtle.best_bits)))
+                .collect(),
+            termination,
+        }
+    }
+
+    // Why the run should stop now, if it should, in the same order as the continuous optimizer.
+    fn termination(&mut self) -> Option<Termination> {
+        if core::mem::take(&mut self.observer_stop) {
+            return Some(Termination::Observer);
+        }
+        if let Some(mut criterion) = self.stopping.take() {
+            let reason = criterion.check(self);
+            self.stopping = Some(criterion);
+            return reason;
+        }
+        if Direction::Minimize.reaches(self.best_score, self.goal) {
+            Some(Termination::GoalReached)
+        } else if self
+            .max_iterations
+            .is_some_and(|limit| self.iterations >= limit)
+        {
+            Some(Termination::IterationLimit)
+        } else {
+            None
+        }
+    }
+
+    fn notify_observers(&mut self) {
+        let mut observers = core::mem::take(&mut self.observers);
+        for observer in observers.iter_mut() {
+            if observer.observe(self).is_break() {
+                self.observer_stop = true;
+            }
+        }
+        observers.append(&mut self.observers);
+        self.observers = observers;
+    }
+
+    pub(crate) fn iterate(&mut self) {
+        self.evaluate();
+        self.update();
+        self.iterations += 1;
+        self.notify_observers();
+    }
+
+    /// Reports the results of a completed optimization to stdout.
+    pub fn report(&self) {
+        self.report_to(std::io::stdout().lock())
+            .expect("the report is printed to stdout");
+    }
+
+    /// Writes the report `report` prints to `writer` instead, such as a file or a buffer.
+    pub fn report_to<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        let render = |bits: &[bool]| -> String {
+            bits.iter()
+                .map(|bit| if *bit { '1' } else { '0' })
+                .collect()
+        };
+        writeln!(
+            writer,
+            "{} turtles performed {} optimizer iterations for you.",
+            self.turtles.len(),
+            self.iterations
+        )?;
+        writeln!(
+            writer,
+            "The objective was evaluated {} times.",
+            self.evaluations
+        )?;
+        writeln!(
+            writer,
+            "The best score: {} was observed at bits: {}",
+            self.best_score,
+            render(&self.best_bits)
+        )?;
+        writeln!(
+            writer,
+            "Below is a complete run down of the best bitstrings: "
+        )?;
+
+        for (turtle_number, turtle) in self.turtles.iter().enumerate() {
+            writeln!(
+                writer,
+                "\t Turtle #{}'s best score {}, was observed at {} ",
+                turtle_number,
+                turtle.best_score,
+                render(&turtle.best_bits)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Progress for BinaryOptimizer<'_> {
+    fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    fn best_score(&self) -> f64 {
+        self.best_score
+    }
+
+    fn goal(&self) -> f64 {
+        self.goal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::ops::ControlFlow;
+
+    #[test]
+    fn knapsacks_are_packed() {
+        // Weights and values of six items, and a knapsack holding 10. The best load is items
+        // 1, 2 and 4, worth 17.
+        let weights = [5., 4., 3., 6., 3., 2.];
+        let values = [4., 6., 5., 7., 6., 2.];
+        let knapsack = |bits: &[bool]| {
+            let (weight, value) = bits
+                .iter()
+                .zip(weights.iter().zip(values.iter()))
+                .filter(|(bit, _)| **bit)
+                .fold((0., 0.), |(w, v), (_, (weight, value))| {
+                    (w + weight, v + value)
+                });
+            if weight > 10. {
+                f64::INFINITY
+            } else {
+                -value
+            }
+        };
+        let mut optimizer = BinaryOptimizer::new_seeded(12, 6, &knapsack, -17., 4);
+        let result = optimizer.optimize();
+        assert_eq!(result.termination, Termination::GoalReached);
+        assert_eq!(optimizer.best_score, -17.);
+        assert_eq!(
+            optimizer.best_bits,
+            vec![false, true, true, false, true, false]
+        );
+        assert_eq!(result.best_position.to_vec(), vec![0., 1., 1., 0., 1., 0.]);
+
+        // The continuous optimizer's criteria and observers stop binary runs too.
+        let mut optimizer = BinaryOptimizer::new_seeded(12, 6, &knapsack, -100., 4);
+        optimizer.stopping = Some(Box::new(
+            crate::stopping::Any::new()
+                .or(crate::stopping::Goal)
+                .or(crate::stopping::MaxIterations(30)),
+        ));
+        assert_eq!(
+            optimizer.optimize().termination,
+            Termination::IterationLimit
+        );
+        optimizer.stopping = None;
+        optimizer
+            .observers
+            .push(Box::new(|optimizer: &BinaryOptimizer| {
+                if optimizer.iterations >= 35 {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            }));
+        let result = optimizer.optimize();
+        assert_eq!(result.termination, Termination::Observer);
+        assert_eq!(result.iterations, 35);
+    }
+
+    #[test]
+    fn binary_runs_move_between_threads() {
+        let ones = |bits: &[bool]| -(bits.iter().filter(|bit| **bit).count() as f64);
+        let mut optimizer = BinaryOptimizer::new_seeded(8, 5, &ones, -5., 2);
+        optimizer.max_iterations = Some(100);
+        let result = std::thread::scope(|scope| scope.spawn(|| optimizer.optimize()).join());
+        assert_eq!(result.unwrap().termination, Termination::GoalReached);
+    }
+}
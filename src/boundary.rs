@@ -0,0 +1,422 @@
+//! The geometry of the region the turtles search.
+//!
+//! Optimizers place their turtles with `sample`, pull them back in after every move with
+//! `project`, and redraw them with `sample` when they are sent elsewhere, so any feasible region
+//! that implements `Boundary` is used consistently. `CubicBoundary` is the default;
+//! `RectangularBoundary` gives every dimension bounds of its own and `BoxUnion` searches several
+//! disjoint boxes at once.
+//!
+//! How a turtle that crawled out is brought back is up to the optimizer's `BoundaryPolicy`.
+//! Clamping onto the wall is the default; reflecting, wrapping around and redrawing the turtle
+//! keep more of the swarm away from the walls.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ndarray::Array1;
+use rand::{distributions::Uniform, Rng, RngCore};
+
+use crate::CubicBoundary;
+
+pub trait Boundary {
+    /// The number of coordinates of a position.
+    fn dimensions(&self) -> usize;
+
+    /// A position drawn from inside the region.
+    fn sample(&self, rng: &mut dyn RngCore) -> Array1<f64>;
+
+    fn contains(&self, position: &Array1<f64>) -> bool;
+
+    /// Moves `position` into the region, leaving positions already inside alone.
+    fn project(&self, position: &mut Array1<f64>);
+
+    /// The lower and upper corner of the smallest box holding the region.
+    fn bounding_box(&self) -> (Array1<f64>, Array1<f64>);
+}
+
+impl Boundary for CubicBoundary {
+    fn dimensions(&self) -> usize {
+        self.shape
+    }
+
+    fn sample(&self, rng: &mut dyn RngCore) -> Array1<f64> {
+        let uniform = Uniform::new(self.lower, self.upper);
+        (0..self.shape).map(|_| rng.sample(uniform)).collect()
+    }
+
+    fn contains(&self, position: &Array1<f64>) -> bool {
+        position
+            .iter()
+            .all(|x| *x >= self.lower && *x <= self.upper)
+    }
+
+    fn project(&self, position: &mut Array1<f64>) {
+        for dimension in position.iter_mut() {
+            if *dimension > self.upper {
+                *dimension = self.upper;
+            } else if *dimension < self.lower {
+                *dimension = self.lower;
+            }
+        }
+    }
+
+    fn bounding_box(&self) -> (Array1<f64>, Array1<f64>) {
+        (
+            Array1::from_elem(self.shape, self.lower),
+            Array1::from_elem(self.shape, self.upper),
+        )
+    }
+}
+
+/// What happens to a turtle that moved out of the boundary.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BoundaryPolicy {
+    /// Stops the turtle at the wall with `project`.
+    #[default]
+    Clamp,
+    /// Bounces the turtle off the wall it crossed, reversing that part of its velocity.
+    Reflect,
+    /// Brings the turtle in through the opposite wall, as if the bounds were periodic.
+    Wrap,
+    /// Redraws the turtle anywhere in the boundary.
+    Reinitialize,
+}
+
+impl BoundaryPolicy {
+    /// Brings `position`, which moved with `velocity`, back into `boundaries`. Reflecting and
+    /// wrapping work within the bounding box, and whatever is still outside after that is
+    /// projected.
+    pub(crate) fn apply<B: Boundary + ?Sized>(
+        &self,
+        boundaries: &B,
+        position: &mut Array1<f64>,
+        velocity: &mut Array1<f64>,
+        rng: &mut dyn RngCore,
+    ) {
+        if boundaries.contains(position) {
+            return;
+        }
+        match self {
+            BoundaryPolicy::Clamp => {}
+            BoundaryPolicy::Reinitialize => *position = boundaries.sample(rng),
+            BoundaryPolicy::Reflect | BoundaryPolicy::Wrap => {
+                let (lower, upper) = boundaries.bounding_box();
+                ndarray::Zip::from(position.view_mut())
+                    .and(velocity)
+                    .and(&lower)
+                    .and(&upper)
+                    .for_each(|x, v, low, high| {
+                        let width = high - low;
+                        if *x >= *low && *x <= *high {
+                            return;
+                        }
+                        if width <= 0. {
+                            *x = *low;
+                        } else if *self == BoundaryPolicy::Wrap {
+                            *x = low + ((*x - low) % width + width) % width;
+                        } else {
+                            let period = 2. * width;
+                            let offset = ((*x - low) % period + period) % period;
+                            *x = low
+                                + if offset > width {
+                                    period - offset
+                                } else {
+                                    offset
+                                };
+                            *v = -*v;
+                        }
+                    });
+            }
+        }
+        boundaries.project(position);
+    }
+}
+
+/// A box with its own lower and upper bound for every dimension.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RectangularBoundary {
+    pub lower: Array1<f64>,
+    pub upper: Array1<f64>,
+}
+
+impl RectangularBoundary {
+    /// Panics when `lower` and `upper` differ in length.
+    pub fn new(lower: Array1<f64>, upper: Array1<f64>) -> Self {
+        assert_eq!(
+            lower.len(),
+            upper.len(),
+            "a rectangular boundary needs as many upper as lower bounds"
+        );
+        // A dimension given the wrong way round is swapped rather than rejected.
+        let low = ndarray::Zip::from(&lower)
+            .and(&upper)
+            .map_collect(|a, b| a.min(*b));
+        let high = ndarray::Zip::from(&lower)
+            .and(&upper)
+            .map_collect(|a, b| a.max(*b));
+        Self {
+            lower: low,
+            upper: high,
+        }
+    }
+
+    /// One `(lower, upper)` pair per dimension.
+    pub fn from_bounds(bounds: &[(f64, f64)]) -> Self {
+        Self::new(
+            bounds.iter().map(|(lower, _)| *lower).collect(),
+            bounds.iter().map(|(_, upper)| *upper).collect(),
+        )
+    }
+}
+
+impl From<CubicBoundary> for RectangularBoundary {
+    fn from(cube: CubicBoundary) -> Self {
+        Self::new(
+            Array1::from_elem(cube.shape, cube.lower),
+            Array1::from_elem(cube.shape, cube.upper),
+        )
+    }
+}
+
+impl Boundary for RectangularBoundary {
+    fn dimensions(&self) -> usize {
+        self.lower.len()
+    }
+
+    fn sample(&self, rng: &mut dyn RngCore) -> Array1<f64> {
+        self.lower
+            .iter()
+            .zip(self.upper.iter())
+            .map(|(low, high)| {
+                if low < high {
+                    rng.gen_range(*low..*high)
+                } else {
+                    *low
+                }
+            })
+            .collect()
+    }
+
+    fn contains(&self, position: &Array1<f64>) -> bool {
+        position
+            .iter()
+            .zip(self.lower.iter().zip(self.upper.iter()))
+            .all(|(x, (low, high))| x >= low && x <= high)
+    }
+
+    fn project(&self, position: &mut Array1<f64>) {
+        ndarray::Zip::from(position)
+            .and(&self.lower)
+            .and(&self.upper)
+            .for_each(|x, low, high| *x = x.max(*low).min(*high));
+    }
+
+    fn bounding_box(&self) -> (Array1<f64>, Array1<f64>) {
+        (self.lower.clone(), self.upper.clone())
+    }
+}
+
+/// The union of axis-aligned boxes, each given by its lower and upper corner. Positions are
+/// sampled from the boxes in proportion to their volume, so overlaps are sampled more densely,
+/// and projected into the nearest box.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BoxUnion {
+    pub boxes: Vec<(Array1<f64>, Array1<f64>)>,
+}
+
+impl BoxUnion {
+    /// Panics without any boxes or when the corners disagree on the number of dimensions.
+    pub fn new(boxes: Vec<(Array1<f64>, Array1<f64>)>) -> Self {
+        assert!(!boxes.is_empty(), "a box union needs at least one box");
+        let dimensions = boxes[0].0.len();
+        let boxes = boxes
+            .into_iter()
+            .map(|(lower, upper)| {
+                assert!(
+                    lower.len() == dimensions && upper.len() == dimensions,
+                    "every corner of a box union needs the same number of dimensions"
+                );
+                // Corners given in any order describe the same box.
+                let low = ndarray::Zip::from(&lower)
+                    .and(&upper)
+                    .map_collect(|a, b| a.min(*b));
+                let high = ndarray::Zip::from(&lower)
+                    .and(&upper)
+                    .map_collect(|a, b| a.max(*b));
+                (low, high)
+            })
+            .collect();
+        Self { boxes }
+    }
+
+    fn volume((lower, upper): &(Array1<f64>, Array1<f64>)) -> f64 {
+        (upper - lower).product()
+    }
+}
+
+impl Boundary for BoxUnion {
+    fn dimensions(&self) -> usize {
+        self.boxes[0].0.len()
+    }
+
+    fn sample(&self, rng: &mut dyn RngCore) -> Array1<f64> {
+        let total: f64 = self.boxes.iter().map(Self::volume).sum();
+        let chosen = if total > 0. {
+            let mut target = rng.gen_range(0. ..total);
+            self.boxes
+                .iter()
+                .position(|bounds| {
+                    target -= Self::volume(bounds);
+                    target < 0.
+                })
+                .unwrap_or(self.boxes.len() - 1)
+        } else {
+            rng.gen_range(0..self.boxes.len())
+        };
+        let (lower, upper) = &self.boxes[chosen];
+        lower
+            .iter()
+            .zip(upper.iter())
+            .map(|(low, high)| {
+                if low < high {
+                    rng.gen_range(*low..*high)
+                } else {
+                    *low
+                }
+            })
+            .collect()
+    }
+
+    fn contains(&self, position: &Array1<f64>) -> bool {
+        self.boxes.iter().any(|(lower, upper)| {
+            position
+                .iter()
+                .zip(lower.iter().zip(upper.iter()))
+                .all(|(x, (low, high))| x >= low && x <= high)
+        })
+    }
+
+    fn project(&self, position: &mut Array1<f64>) {
+        if self.contains(position) {
+            return;
+        }
+        let mut nearest: Option<(f64, Array1<f64>)> = None;
+        for (lower, upper) in self.boxes.iter() {
+            let clamped = ndarray::Zip::from(&*position)
+                .and(lower)
+                .and(upper)
+                .map_collect(|x, low, high| x.max(*low).min(*high));
+            let distance = (&clamped - &*position).mapv(|x| x * x).sum();
+            if nearest.as_ref().is_none_or(|(best, _)| distance < *best) {
+                nearest = Some((distance, clamped));
+            }
+        }
+        if let Some((_, clamped)) = nearest {
+            *position = clamped;
+        }
+    }
+
+    fn bounding_box(&self) -> (Array1<f64>, Array1<f64>) {
+        let (mut lower, mut upper) = self.boxes[0].clone();
+        for (low, high) in self.boxes.iter().skip(1) {
+            ndarray::Zip::from(&mut lower)
+                .and(low)
+                .for_each(|x, low| *x = x.min(*low));
+            ndarray::Zip::from(&mut upper)
+                .and(high)
+                .for_each(|x, high| *x = x.max(*high));
+        }
+        (lower, upper)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Optimizer;
+    use ndarray::array;
+
+    #[test]
+    fn every_dimension_keeps_to_its_own_bounds() {
+        let bounds = RectangularBoundary::from_bounds(&[(0., 1.), (100., -100.), (5., 5.)]);
+        assert_eq!(bounds.lower, array![0., -100., 5.]);
+        assert_eq!(bounds.upper, array![1., 100., 5.]);
+        let mut far = array![-3., 300., 0.];
+        bounds.project(&mut far);
+        assert_eq!(far, array![0., 100., 5.]);
+
+        let sloped = |x: &Array1<f64>| x.sum();
+        let mut optimizer = Optimizer::new_seeded(20, bounds.clone(), &sloped, -1e9, 6);
+        for _ in 0..10 {
+            optimizer.iterate();
+        }
+        assert!(optimizer
+            .turtles
+            .iter()
+            .all(|turtle| bounds.contains(&turtle.position)));
+        assert!(optimizer
+            .turtles
+            .iter()
+            .any(|turtle| turtle.position[1] < -1.));
+    }
+
+    #[test]
+    fn policies_bring_turtles_back_their_own_way() {
+        let cube = CubicBoundary::new(2, -1., 1.);
+        let mut rng = rand::rngs::mock::StepRng::new(0, 1 << 60);
+        let bring_back = |policy: BoundaryPolicy, rng: &mut dyn RngCore| {
+            let mut position = array![1.3, -0.5];
+            let mut velocity = array![0.6, 0.1];
+            policy.apply(&cube, &mut position, &mut velocity, rng);
+            (position, velocity)
+        };
+        let (clamped, _) = bring_back(BoundaryPolicy::Clamp, &mut rng);
+        assert_eq!(clamped, array![1., -0.5]);
+        let (reflected, velocity) = bring_back(BoundaryPolicy::Reflect, &mut rng);
+        assert!((reflected[0] - 0.7).abs() < 1e-12 && reflected[1] == -0.5);
+        assert_eq!(velocity, array![-0.6, 0.1]);
+        let (wrapped, velocity) = bring_back(BoundaryPolicy::Wrap, &mut rng);
+        assert!((wrapped[0] + 0.7).abs() < 1e-12 && wrapped[1] == -0.5);
+        assert_eq!(velocity, array![0.6, 0.1]);
+        let (redrawn, _) = bring_back(BoundaryPolicy::Reinitialize, &mut rng);
+        assert!(cube.contains(&redrawn));
+
+        let sloped = |x: &Array1<f64>| x.sum();
+        let mut optimizer = Optimizer::new_seeded(20, cube, &sloped, -1e9, 6);
+        optimizer.params.cognitive = 0.9;
+        optimizer.params.social = 0.9;
+        optimizer.boundary_policy = BoundaryPolicy::Reflect;
+        for _ in 0..10 {
+            optimizer.iterate();
+            assert!(optimizer
+                .turtles
+                .iter()
+                .all(|turtle| cube.contains(&turtle.position)));
+        }
+    }
+
+    #[test]
+    fn turtles_stay_inside_a_union_of_boxes() {
+        let union = BoxUnion::new(vec![
+            (array![-2., -2.], array![-1., -1.]),
+            (array![2., 1.], array![1., 2.]),
+        ]);
+        let mut outside = array![0.2, 0.];
+        union.project(&mut outside);
+        assert_eq!(outside, array![1., 1.]);
+
+        let shifted = |x: &Array1<f64>| x.mapv(|x| (x - 1.5).powi(2)).sum();
+        let mut optimizer = Optimizer::new_seeded(30, union.clone(), &shifted, -1., 5);
+        for _ in 0..5 {
+            optimizer.iterate();
+        }
+        optimizer.restart(0.2);
+
+        let positions = optimizer.turtles.iter().map(|turtle| &turtle.position);
+        assert!(positions.clone().all(|position| union.contains(position)));
+        assert!(positions.clone().any(|position| position[0] < 0.));
+        assert!(positions.clone().any(|position| position[0] > 0.));
+    }
+}
@@ -0,0 +1,364 @@
+//! Step by step construction of an `Optimizer`.
+//!
+//! `Optimizer::new` only takes what every run needs. Everything else is set through the
+//! builder, which checks the settings fit together before any turtle is placed:
+//!
+//! `OptimizerBuilder::new(&objective).turtles(50).boundary(b).goal(1e-3).build()`
+
+use core::fmt;
+
+use ndarray::Array2;
+
+use crate::boundary::{Boundary, BoundaryPolicy};
+use crate::inertia::InertiaSchedule;
+use crate::initialization::InitStrategy;
+use crate::integer::IntegerDimensions;
+use crate::nan::NanPolicy;
+use crate::objective::ObjectiveFunction;
+use crate::topology::Topology;
+use crate::{
+    CubicBoundary, Direction, Optimizer, OptimizerParams, UpdateMode, VelocityLimit, VelocityUpdate,
+};
+
+/// Why a builder could not make an optimizer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuildError {
+    NoTurtles,
+    /// No boundary was given for the turtles to search.
+    MissingBoundary,
+    /// The boundary has no dimensions.
+    NoDimensions,
+    /// The goal is not a number.
+    InvalidGoal,
+    /// A coefficient in the params is not finite.
+    InvalidParams,
+    /// The goal can never be reached and there is no iteration, evaluation or time limit, so the
+    /// run would not end.
+    Unbounded,
+    /// There is no thread rng without the `std` feature, so runs need a seed.
+    MissingSeed,
+    /// The initial positions do not have one column per dimension of the boundary.
+    MismatchedPositions,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            BuildError::NoTurtles => "an optimizer needs at least one turtle",
+            BuildError::MissingBoundary => "an optimizer needs a boundary",
+            BuildError::NoDimensions => "the boundary has no dimensions",
+            BuildError::InvalidGoal => "the goal is not a number",
+            BuildError::InvalidParams => {
+                "the inertia, cognitive and social coefficients must be finite"
+            }
+            BuildError::Unbounded => {
+                "without a goal an optimizer needs an iteration, evaluation or time limit"
+            }
+            BuildError::MissingSeed => "without the std feature an optimizer needs a seed",
+            BuildError::MismatchedPositions => {
+                "the initial positions need one column per dimension"
+            }
+        };
+        f.write_str(message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BuildError {}
+
+pub struct OptimizerBuilder<'a, B = CubicBoundary> {
+    objective_function: ObjectiveFunction<'a>,
+    turtles: usize,
+    boundaries: Option<B>,
+    goal: Option<f64>,
+    max_iterations: Option<usize>,
+    max_evaluations: Option<usize>,
+    #[cfg(feature = "std")]
+    max_duration: Option<std::time::Duration>,
+    params: OptimizerParams,
+    inertia_schedule: InertiaSchedule,
+    velocity_update: VelocityUpdate,
+    v_max: Option<VelocityLimit>,
+    boundary_policy: BoundaryPolicy,
+    update_mode: UpdateMode,
+    direction: Direction,
+    nan_policy: NanPolicy,
+    topology: Topology,
+    integer_dimensions: Option<IntegerDimensions>,
+    init: InitStrategy,
+    initial_positions: Option<Array2<f64>>,
+    seed: Option<u64>,
+}
+
+impl<'a> OptimizerBuilder<'a> {
+    /// Starts with 30 turtles, minimizing without a goal, which leaves a boundary and either a goal
+    /// or an iteration limit to be set.
+    pub fn new(objective_function: impl Into<ObjectiveFunction<'a>>) -> Self {
+        Self {
+            objective_function: objective_function.into(),
+            turtles: 30,
+            boundaries: None,
+            goal: None,
+            max_iterations: None,
+            max_evaluations: None,
+            #[cfg(feature = "std")]
+            max_duration: None,
+            params: OptimizerParams::default(),
+            inertia_schedule: InertiaSchedule::Constant,
+            velocity_update: VelocityUpdate::Inertia,
+            v_max: None,
+            boundary_policy: BoundaryPolicy::Clamp,
+            update_mode: UpdateMode::All,
+            direction: Direction::Minimize,
+            nan_policy: NanPolicy::Worst,
+            topology: Topology::Star,
+            integer_dimensions: None,
+            init: InitStrategy::Uniform,
+            initial_positions: None,
+            seed: None,
+        }
+    }
+}
+
+impl<'a, B: Boundary> OptimizerBuilder<'a, B> {
+    pub fn turtles(mut self, turtles: usize) -> Self {
+        self.turtles = turtles;
+        self
+    }
+
+    /// The region to search, which may be any `Boundary`.
+    pub fn boundary<C: Boundary>(self, boundaries: C) -> OptimizerBuilder<'a, C> {
+        OptimizerBuilder {
+            objective_function: self.objective_function,
+            turtles: self.turtles,
+            boundaries: Some(boundaries),
+            goal: self.goal,
+            max_iterations: self.max_iterations,
+            max_evaluations: self.max_evaluations,
+            #[cfg(feature = "std")]
+            max_duration: self.max_duration,
+            params: self.params,
+            inertia_schedule: self.inertia_schedule,
+            velocity_update: self.velocity_update,
+            v_max: self.v_max,
+            boundary_policy: self.boundary_policy,
+            update_mode: self.update_mode,
+            direction: self.direction,
+            nan_policy: self.nan_policy,
+            topology: self.topology,
+            integer_dimensions: self.integer_dimensions,
+            init: self.init,
+            initial_positions: self.initial_positions,
+            seed: self.seed,
+        }
+    }
+
+    pub fn goal(mut self, goal: f64) -> Self {
+        self.goal = Some(goal);
+        self
+    }
+
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = Some(max_iterations);
+        self
+    }
+
+    pub fn max_evaluations(mut self, max_evaluations: usize) -> Self {
+        self.max_evaluations = Some(max_evaluations);
+        self
+    }
+
+    #[cfg(feature = "std")]
+    pub fn max_duration(mut self, max_duration: std::time::Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    pub fn params(mut self, params: OptimizerParams) -> Self {
+        self.params = params;
+        self
+    }
+
+    pub fn inertia_schedule(mut self, inertia_schedule: InertiaSchedule) -> Self {
+        self.inertia_schedule = inertia_schedule;
+        self
+    }
+
+    pub fn velocity_update(mut self, velocity_update: VelocityUpdate) -> Self {
+        self.velocity_update = velocity_update;
+        self
+    }
+
+    pub fn v_max(mut self, v_max: VelocityLimit) -> Self {
+        self.v_max = Some(v_max);
+        self
+    }
+
+    pub fn boundary_policy(mut self, boundary_policy: BoundaryPolicy) -> Self {
+        self.boundary_policy = boundary_policy;
+        self
+    }
+
+    pub fn update_mode(mut self, update_mode: UpdateMode) -> Self {
+        self.update_mode = update_mode;
+        self
+    }
+
+    pub fn direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn nan_policy(mut self, nan_policy: NanPolicy) -> Self {
+        self.nan_policy = nan_policy;
+        self
+    }
+
+    pub fn topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    pub fn integer_dimensions(mut self, integer_dimensions: IntegerDimensions) -> Self {
+        self.integer_dimensions = Some(integer_dimensions);
+        self
+    }
+
+    /// How the turtles are spread over the boundary, uniformly unless set.
+    pub fn init(mut self, init: InitStrategy) -> Self {
+        self.init = init;
+        self
+    }
+
+    /// Starts one turtle at every row of `positions`, in place of `turtles` and `init`.
+    pub fn initial_positions(mut self, positions: Array2<f64>) -> Self {
+        self.initial_positions = Some(positions);
+        self
+    }
+
+    /// Places the turtles, and makes every later random choice, reproducibly from `seed`.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn build(self) -> Result<Optimizer<'a, B>, BuildError> {
+        let boundaries = self.boundaries.ok_or(BuildError::MissingBoundary)?;
+        // Turtles given their positions are placed after the optimizer is made.
+        let (turtles, placed) = match &self.initial_positions {
+            Some(positions) => (0, positions.nrows()),
+            None => (self.turtles, self.turtles),
+        };
+        if placed == 0 {
+            return Err(BuildError::NoTurtles);
+        }
+        if boundaries.dimensions() == 0 {
+            return Err(BuildError::NoDimensions);
+        }
+        if self
+            .initial_positions
+            .as_ref()
+            .is_some_and(|positions| positions.ncols() != boundaries.dimensions())
+        {
+            return Err(BuildError::MismatchedPositions);
+        }
+        // Without a goal the run aims for the impossible, the best score beyond every real one.
+        let goal = self.goal.unwrap_or(-self.direction.worst());
+        if goal.is_nan() {
+            return Err(BuildError::InvalidGoal);
+        }
+        let params = self.params;
+        if !(params.inertia.is_finite()
+            && params.cognitive.is_finite()
+            && params.social.is_finite())
+        {
+            return Err(BuildError::InvalidParams);
+        }
+        #[cfg(feature = "std")]
+        let limited = self.max_iterations.is_some()
+            || self.max_evaluations.is_some()
+            || self.max_duration.is_some();
+        #[cfg(not(feature = "std"))]
+        let limited = self.max_iterations.is_some() || self.max_evaluations.is_some();
+        if goal == -self.direction.worst() && !limited {
+            return Err(BuildError::Unbounded);
+        }
+        let mut optimizer = match self.seed {
+            Some(seed) => {
+                Optimizer::new_seeded(turtles, boundaries, self.objective_function, goal, seed)
+            }
+            #[cfg(feature = "std")]
+            None => Optimizer::new(turtles, boundaries, self.objective_function, goal),
+            #[cfg(not(feature = "std"))]
+            None => return Err(BuildError::MissingSeed),
+        };
+        if let Some(positions) = &self.initial_positions {
+            optimizer.place_turtles(positions);
+        } else if self.init != InitStrategy::Uniform {
+            optimizer.initialize(self.init);
+        }
+        optimizer.max_iterations = self.max_iterations;
+        optimizer.max_evaluations = self.max_evaluations;
+        #[cfg(feature = "std")]
+        {
+            optimizer.max_duration = self.max_duration;
+        }
+        optimizer.params = params;
+        optimizer.inertia_schedule = self.inertia_schedule;
+        optimizer.velocity_update = self.velocity_update;
+        optimizer.v_max = self.v_max;
+        optimizer.boundary_policy = self.boundary_policy;
+        optimizer.update_mode = self.update_mode;
+        optimizer.topology = self.topology;
+        optimizer.set_direction(self.direction);
+        optimizer.nan_policy = self.nan_policy;
+        optimizer.set_integer_dimensions(self.integer_dimensions);
+        Ok(optimizer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::RectangularBoundary;
+    use crate::Termination;
+    use ndarray::Array1;
+
+    #[test]
+    fn builders_check_their_settings() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let builder = OptimizerBuilder::new(&parabola);
+        assert_eq!(builder.build().err(), Some(BuildError::MissingBoundary));
+
+        let cube = CubicBoundary::new(2, -1., 1.);
+        let unbounded = OptimizerBuilder::new(&parabola).boundary(cube).build();
+        assert_eq!(unbounded.err(), Some(BuildError::Unbounded));
+        let idle = OptimizerBuilder::new(&parabola)
+            .boundary(cube)
+            .turtles(0)
+            .goal(1e-3);
+        assert_eq!(idle.build().err(), Some(BuildError::NoTurtles));
+        let misplaced = OptimizerBuilder::new(&parabola)
+            .boundary(cube)
+            .initial_positions(Array2::zeros((4, 3)))
+            .goal(1e-3);
+        assert_eq!(
+            misplaced.build().err(),
+            Some(BuildError::MismatchedPositions)
+        );
+
+        let mut optimizer = OptimizerBuilder::new(&parabola)
+            .turtles(12)
+            .boundary(RectangularBoundary::from_bounds(&[(-1., 1.), (-2., 2.)]))
+            .max_iterations(10)
+            .seed(4)
+            .build()
+            .unwrap();
+        assert_eq!(optimizer.turtles.len(), 12);
+        assert_eq!(
+            optimizer.optimize().termination,
+            Termination::IterationLimit
+        );
+        assert_eq!(optimizer.iterations, 10);
+    }
+}
@@ -0,0 +1,433 @@
+//! Snapshots of a running optimizer.
+//!
+//! A checkpoint holds the swarm, the bests, the options that shape the trajectory along with the
+//! state they have built up, such as a stagnation streak or learned step scales, and the exact
+//! state of the random number generator, so a resumed run continues with the very draws the
+//! original would have made. A time limit resumes with the time already spent deducted. With the
+//! `serde` feature checkpoints can be serialized, and `save_checkpoint` and
+//! `resume_from_checkpoint` keep them in JSON files so a run survives a restart of the process.
+//!
+//! Hooks cannot be saved: `stopping`, `middleware`, `observers`, `history` and watchers are left
+//! out and have to be set again on the resumed optimizer.
+//!
+//! JSON has no infinities, and a turtle that has not been scored yet has the worst score there
+//! is, so scores and the goal are serialized as numbers when finite and as `"inf"`, `"-inf"` or
+//! `"nan"` otherwise.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ndarray::Array1;
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::boundary::BoundaryPolicy;
+use crate::duplicates::Deduplication;
+use crate::inertia::InertiaSchedule;
+use crate::integer::IntegerDimensions;
+use crate::nan::NanPolicy;
+use crate::noise::NoiseHandling;
+use crate::stagnation::StagnationRestart;
+use crate::step_scaling::StepScaling;
+use crate::tabu::TabuArchive;
+use crate::topology::Topology;
+use crate::{
+    CubicBoundary, Direction, Optimizer, OptimizerParams, Turtle, TurtleStats, UpdateMode,
+    VelocityLimit, VelocityUpdate,
+};
+
+/// The position of a ChaCha12 generator in its stream.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RngState {
+    pub seed: [u8; 32],
+    pub stream: u64,
+    pub word_pos: u128,
+}
+
+impl RngState {
+    pub(crate) fn capture(rng: &ChaCha12Rng) -> Self {
+        Self {
+            seed: rng.get_seed(),
+            stream: rng.get_stream(),
+            word_pos: rng.get_word_pos(),
+        }
+    }
+
+    pub(crate) fn restore(&self) -> ChaCha12Rng {
+        let mut rng = ChaCha12Rng::from_seed(self.seed);
+        rng.set_stream(self.stream);
+        rng.set_word_pos(self.word_pos);
+        rng
+    }
+}
+
+/// Serializes an `f64` that may not be finite, see the module documentation.
+#[cfg(feature = "serde")]
+pub(crate) mod score {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Score {
+        Finite(f64),
+        Named(String),
+    }
+
+    pub fn serialize<S: Serializer>(score: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        if score.is_finite() {
+            serializer.serialize_f64(*score)
+        } else if score.is_nan() {
+            serializer.serialize_str("nan")
+        } else if *score > 0. {
+            serializer.serialize_str("inf")
+        } else {
+            serializer.serialize_str("-inf")
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        match Score::deserialize(deserializer)? {
+            Score::Finite(score) => Ok(score),
+            Score::Named(name) => match name.as_str() {
+                "inf" => Ok(f64::INFINITY),
+                "-inf" => Ok(f64::NEG_INFINITY),
+                "nan" => Ok(f64::NAN),
+                other => Err(serde::de::Error::custom(format_args!(
+                    "expected a number, \"inf\", \"-inf\" or \"nan\", found \"{other}\""
+                ))),
+            },
+        }
+    }
+
+    /// The same for an `f64` that may be missing.
+    pub mod option {
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        #[derive(serde::Serialize, Deserialize)]
+        struct Wrapped(#[serde(with = "super")] f64);
+
+        pub fn serialize<S: Serializer>(
+            score: &Option<f64>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serde::Serialize::serialize(&score.map(Wrapped), serializer)
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<f64>, D::Error> {
+            Ok(Option::<Wrapped>::deserialize(deserializer)?.map(|Wrapped(score)| score))
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TurtleState {
+    pub position: Vec<f64>,
+    pub velocity: Vec<f64>,
+    #[cfg_attr(feature = "serde", serde(with = "score"))]
+    pub best_score: f64,
+    pub best_position: Vec<f64>,
+    pub stats: TurtleStats,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TabuState {
+    pub center: Vec<f64>,
+    pub radius: f64,
+    #[cfg_attr(feature = "serde", serde(with = "score"))]
+    pub score: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Checkpoint {
+    pub turtles: Vec<TurtleState>,
+    pub lower: f64,
+    pub upper: f64,
+    pub shape: usize,
+    pub iterations: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub evaluations: usize,
+    #[cfg_attr(feature = "serde", serde(with = "score"))]
+    pub best_score: f64,
+    pub best_position: Vec<f64>,
+    #[cfg_attr(feature = "serde", serde(with = "score"))]
+    pub goal: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_iterations: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_evaluations: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub params: OptimizerParams,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub inertia_schedule: InertiaSchedule,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub velocity_update: VelocityUpdate,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub v_max: Option<VelocityLimit>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub boundary_policy: BoundaryPolicy,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub direction: Direction,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub nan_policy: NanPolicy,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub topology: Topology,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub integer_dimensions: Option<IntegerDimensions>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub step_scaling: Option<StepScaling>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub deduplication: Option<Deduplication>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub noise: Option<NoiseHandling>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub stagnation: Option<StagnationRestart>,
+    #[cfg(feature = "std")]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_duration: Option<std::time::Duration>,
+    /// The time spent against `max_duration`, once its clock has started.
+    #[cfg(feature = "std")]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub elapsed: Option<std::time::Duration>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tabu: Vec<TabuState>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub update_mode: UpdateMode,
+    pub rng: RngState,
+}
+
+impl<'a> Optimizer<'a> {
+    /// Captures everything needed to continue this run later.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            turtles: self
+                .turtles
+                .iter()
+                .map(|turtle| TurtleState {
+                    position: turtle.position.to_vec(),
+                    velocity: turtle.velocity.to_vec(),
+                    best_score: turtle.best_score,
+                    best_position: turtle.best_position.to_vec(),
+                    stats: turtle.stats,
+                })
+                .collect(),
+            lower: self.boundaries.lower,
+            upper: self.boundaries.upper,
+            shape: self.boundaries.shape,
+            iterations: self.iterations,
+            evaluations: self.evaluations,
+            best_score: self.best_score,
+            best_position: self.best_position.to_vec(),
+            goal: self.goal,
+            max_iterations: self.max_iterations,
+            max_evaluations: self.max_evaluations,
+            params: self.params,
+            inertia_schedule: self.inertia_schedule,
+            velocity_update: self.velocity_update,
+            v_max: self.v_max,
+            boundary_policy: self.boundary_policy,
+            direction: self.direction,
+            nan_policy: self.nan_policy,
+            topology: self.topology,
+            integer_dimensions: self.integer_dimensions().cloned(),
+            step_scaling: self.step_scaling.clone(),
+            deduplication: self.deduplication,
+            noise: self.noise,
+            stagnation: self.stagnation,
+            #[cfg(feature = "std")]
+            max_duration: self.max_duration,
+            #[cfg(feature = "std")]
+            elapsed: self.started.map(|started| started.elapsed()),
+            tabu: self
+                .tabu
+                .regions
+                .iter()
+                .map(|region| TabuState {
+                    center: region.center.to_vec(),
+                    radius: region.radius,
+                    score: region.score,
+                })
+                .collect(),
+            update_mode: self.update_mode,
+            rng: RngState::capture(&self.rng),
+        }
+    }
+
+    /// Continues the run saved in `checkpoint`. Objective functions cannot be saved, so the same
+    /// one has to be handed back.
+    pub fn resume(
+        checkpoint: &Checkpoint,
+        objective_function: &'a (dyn Fn(&Array1<f64>) -> f64 + Sync),
+    ) -> Self {
+        let boundaries = CubicBoundary::new(checkpoint.shape, checkpoint.lower, checkpoint.upper);
+        let mut optimizer = Self::with_rng(
+            0,
+            boundaries,
+            objective_function,
+            checkpoint.goal,
+            checkpoint.rng.restore(),
+        );
+        optimizer.turtles = checkpoint
+            .turtles
+            .iter()
+            .map(|turtle| Turtle {
+                position: Array1::from(turtle.position.clone()),
+                velocity: Array1::from(turtle.velocity.clone()),
+                best_score: turtle.best_score,
+                best_position: Array1::from(turtle.best_position.clone()),
+                stats: turtle.stats,
+            })
+            .collect();
+        optimizer.iterations = checkpoint.iterations;
+        optimizer.evaluations = checkpoint.evaluations;
+        optimizer.best_score = checkpoint.best_score;
+        optimizer.best_position = Array1::from(checkpoint.best_position.clone());
+        optimizer.max_iterations = checkpoint.max_iterations;
+        optimizer.max_evaluations = checkpoint.max_evaluations;
+        optimizer.params = checkpoint.params;
+        optimizer.inertia_schedule = checkpoint.inertia_schedule;
+        optimizer.velocity_update = checkpoint.velocity_update;
+        optimizer.v_max = checkpoint.v_max;
+        optimizer.boundary_policy = checkpoint.boundary_policy;
+        optimizer.direction = checkpoint.direction;
+        optimizer.nan_policy = checkpoint.nan_policy;
+        optimizer.topology = checkpoint.topology;
+        optimizer.update_mode = checkpoint.update_mode;
+        optimizer.set_integer_dimensions(checkpoint.integer_dimensions.clone());
+        optimizer.step_scaling = checkpoint.step_scaling.clone();
+        optimizer.deduplication = checkpoint.deduplication;
+        optimizer.noise = checkpoint.noise;
+        optimizer.stagnation = checkpoint.stagnation;
+        #[cfg(feature = "std")]
+        {
+            optimizer.max_duration = checkpoint.max_duration;
+            optimizer.started = checkpoint
+                .elapsed
+                .and_then(|elapsed| std::time::Instant::now().checked_sub(elapsed));
+        }
+        let mut tabu = TabuArchive::new();
+        for region in checkpoint.tabu.iter() {
+            tabu.add(
+                Array1::from(region.center.clone()),
+                region.radius,
+                region.score,
+            );
+        }
+        optimizer.tabu = tabu;
+        optimizer
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'a> Optimizer<'a> {
+    /// Writes a checkpoint of this run to `path` as JSON, replacing the file if there is one.
+    pub fn save_checkpoint(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        serde_json::to_writer(file, &self.checkpoint())?;
+        Ok(())
+    }
+
+    /// Continues the run saved at `path` by `save_checkpoint`.
+    pub fn resume_from_checkpoint(
+        path: impl AsRef<std::path::Path>,
+        objective_function: &'a (dyn Fn(&Array1<f64>) -> f64 + Sync),
+    ) -> std::io::Result<Self> {
+        let file = std::io::BufReader::new(std::fs::File::open(path)?);
+        let checkpoint: Checkpoint = serde_json::from_reader(file)?;
+        Ok(Self::resume(&checkpoint, objective_function))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resumed_runs_follow_the_original_trajectory() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(4, -1., 1.);
+        let mut original = Optimizer::new_seeded(5, boundaries, &parabola, -1., 12);
+        original.update_mode = UpdateMode::Random { block: 2 };
+        for _ in 0..3 {
+            original.iterate();
+        }
+        original.restart(0.3);
+
+        let checkpoint = original.checkpoint();
+        let mut resumed = Optimizer::resume(&checkpoint, &parabola);
+        for _ in 0..4 {
+            original.iterate();
+            resumed.iterate();
+        }
+        original.restart(0.3);
+        resumed.restart(0.3);
+
+        assert_eq!(original.checkpoint(), resumed.checkpoint());
+    }
+
+    #[test]
+    fn resumed_runs_keep_the_state_of_their_options() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(3, -1., 1.);
+        let mut original = Optimizer::new_seeded(6, boundaries, &parabola, -1., 8);
+        original.step_scaling = Some(StepScaling::new(3));
+        original.deduplication = Some(Deduplication::default());
+        original.noise = Some(NoiseHandling::new(2));
+        original.stagnation = Some(StagnationRestart::new(2, 0.5));
+        for _ in 0..5 {
+            original.iterate();
+        }
+
+        let mut resumed = Optimizer::resume(&original.checkpoint(), &parabola);
+        for _ in 0..5 {
+            original.iterate();
+            resumed.iterate();
+        }
+        assert_eq!(original.checkpoint(), resumed.checkpoint());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn checkpoint_files_survive_a_restart() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(3, -1., 1.);
+        let mut original = Optimizer::new_seeded(6, boundaries, &parabola, -1., 21);
+        original.step_scaling = Some(StepScaling::new(3));
+        original.noise = Some(NoiseHandling::new(2));
+        original.stagnation = Some(StagnationRestart::new(2, 0.5));
+        original.iterate();
+        let path = std::env::temp_dir().join(format!("tso-checkpoint-{}.json", std::process::id()));
+        original.save_checkpoint(&path).unwrap();
+
+        let mut resumed = Optimizer::resume_from_checkpoint(&path, &parabola).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        original.iterate();
+        resumed.iterate();
+        assert_eq!(original.checkpoint(), resumed.checkpoint());
+        assert!(Optimizer::resume_from_checkpoint(&path, &parabola).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn unscored_checkpoints_survive_a_restart() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(3, -1., 1.);
+        let mut original = Optimizer::new_seeded(6, boundaries, &parabola, f64::NEG_INFINITY, 21);
+        let path = std::env::temp_dir().join(format!("tso-unscored-{}.json", std::process::id()));
+        original.save_checkpoint(&path).unwrap();
+
+        let mut resumed = Optimizer::resume_from_checkpoint(&path, &parabola).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(resumed.best_score, f64::INFINITY);
+        assert_eq!(resumed.goal, f64::NEG_INFINITY);
+        original.iterate();
+        resumed.iterate();
+        assert_eq!(original.checkpoint(), resumed.checkpoint());
+    }
+}
@@ -0,0 +1,285 @@
+//! The `tso` command line tool, enabled with the `cli` feature.
+//!
+//! A run is described by a TOML file:
+//!
+//! ```toml
+//! turtles = 32
+//! dimensions = 2
+//! lower = -5.0
+//! upper = 5.0
+//! goal = 1e-3
+//! max_iterations = 500 # optional, the turtles work until the goal is reached without it
+//! seed = 7             # optional
+//!
+//! [objective]
+//! expression = "x0^2 + x1^2"
+//! # or an external program, called with the position appended as arguments, that prints a score
+//! # command = ["python3", "score.py"]
+//! # or, with the `script` feature, a Rhai script defining `fn objective(x)`
+//! # script = "objective.rhai"
+//!
+//! [output]
+//! format = "json" # or "csv"
+//! path = "results.json" # optional, stdout without it
+//! ```
+
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::Command;
+
+use evalexpr::{
+    build_operator_tree, ContextWithMutableVariables, DefaultNumericTypes, HashMapContext, Value,
+};
+use ndarray::Array1;
+use serde::Deserialize;
+
+use crate::{external_objective, CubicBoundary, Optimizer};
+
+#[derive(Debug, Deserialize)]
+pub struct Config {
+    #[serde(default = "default_turtles")]
+    pub turtles: usize,
+    pub dimensions: usize,
+    pub lower: f64,
+    pub upper: f64,
+    pub goal: f64,
+    pub max_iterations: Option<usize>,
+    pub seed: Option<u64>,
+    pub objective: ObjectiveConfig,
+    #[serde(default)]
+    pub output: OutputConfig,
+}
+
+fn default_turtles() -> usize {
+    32
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ObjectiveConfig {
+    /// A math expression over the variables `x0`, `x1`, ...
+    Expression(String),
+    /// A program and its leading arguments. The position is appended and a score is read back.
+    Command(Vec<String>),
+    /// The path of a Rhai script defining `fn objective(x)`.
+    #[cfg(feature = "script")]
+    Script(PathBuf),
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct OutputConfig {
+    #[serde(default)]
+    pub format: Format,
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    #[default]
+    Json,
+    Csv,
+}
+
+type Objective<'a> = Box<dyn Fn(&Array1<f64>) -> Result<f64, String> + 'a>;
+
+impl ObjectiveConfig {
+    /// Turns the configured objective into a fallible objective function.
+    pub fn build(&self, dimensions: usize) -> Result<Objective<'_>, String> {
+        match self {
+            ObjectiveConfig::Expression(expression) => {
+                let tree = build_operator_tree::<DefaultNumericTypes>(expression)
+                    .map_err(|error| format!("invalid expression `{}`: {}", expression, error))?;
+                let names: Vec<String> = (0..dimensions).map(|i| format!("x{}", i)).collect();
+                let context = RefCell::new(HashMapContext::<DefaultNumericTypes>::new());
+                Ok(Box::new(move |position: &Array1<f64>| {
+                    let mut context = context.borrow_mut();
+                    for (name, value) in names.iter().zip(position.iter()) {
+                        context
+                            .set_value(name.clone(), Value::from_float(*value))
+                            .map_err(|error| error.to_string())?;
+                    }
+                    tree.eval_number_with_context(&*context)
+                        .map_err(|error| format!("evaluating `{}`: {}", expression, error))
+                }))
+            }
+            ObjectiveConfig::Command(command) => {
+                let (program, arguments) = command
+                    .split_first()
+                    .ok_or_else(|| "the objective command is empty".to_string())?;
+                Ok(Box::new(move |position: &Array1<f64>| {
+                    let output = Command::new(program)
+                        .args(arguments)
+                        .args(position.iter().map(|x| x.to_string()))
+                        .output()
+                        .map_err(|error| format!("running `{}`: {}", program, error))?;
+                    if !output.status.success() {
+                        return Err(format!("`{}` exited with {}", program, output.status));
+                    }
+                    let stdout = String::from_utf8_lossy(&output.stdout);
+                    stdout
+                        .split_whitespace()
+                        .next()
+                        .and_then(|score| score.parse::<f64>().ok())
+                        .ok_or_else(|| format!("`{}` did not print a score", program))
+                }))
+            }
+            #[cfg(feature = "script")]
+            ObjectiveConfig::Script(path) => {
+                let source = fs::read_to_string(path)
+                    .map_err(|error| format!("reading `{}`: {}", path.display(), error))?;
+                let script = crate::script::Script::compile(&source)?;
+                Ok(Box::new(move |position: &Array1<f64>| {
+                    script.evaluate(position)
+                }))
+            }
+        }
+    }
+}
+
+/// Runs the optimization described by `config` and renders the results in its output format.
+pub fn run(config: &Config) -> Result<String, Box<dyn Error>> {
+    let objective = config.objective.build(config.dimensions)?;
+    let boundaries = CubicBoundary::new(config.dimensions, config.lower, config.upper);
+    let mut optimizer = match config.seed {
+        Some(seed) => Optimizer::new_seeded(
+            config.turtles,
+            boundaries,
+            &external_objective,
+            config.goal,
+            seed,
+        ),
+        None => Optimizer::new(config.turtles, boundaries, &external_objective, config.goal),
+    };
+    optimizer.max_iterations = config.max_iterations;
+    // The first failing evaluation ends the run; the swarm cannot be trusted after it. Scripted
+    // objectives are tied to their thread, so the positions are scored through ask and tell.
+    optimizer.optimize_with(|position| objective(position))?;
+
+    Ok(match config.output.format {
+        Format::Json => to_json(&optimizer),
+        Format::Csv => to_csv(&optimizer),
+    })
+}
+
+fn to_json(optimizer: &Optimizer) -> String {
+    format!("{:#}\n", optimizer.report_json())
+}
+
+fn to_csv(optimizer: &Optimizer) -> String {
+    let mut csv = String::from("turtle,best_score");
+    for dimension in 0..optimizer.boundaries.shape {
+        let _ = write!(csv, ",x{}", dimension);
+    }
+    csv.push('\n');
+
+    let mut row = |label: &str, score: f64, position: &Array1<f64>| {
+        let _ = write!(csv, "{},{}", label, score);
+        for value in position.iter() {
+            let _ = write!(csv, ",{}", value);
+        }
+        csv.push('\n');
+    };
+    row("best", optimizer.best_score, &optimizer.best_position);
+    for (turtle_number, turtle) in optimizer.turtles.iter().enumerate() {
+        row(
+            &turtle_number.to_string(),
+            turtle.best_score,
+            &turtle.best_position,
+        );
+    }
+    csv
+}
+
+const USAGE: &str = "usage: tso <config.toml> [--format json|csv] [--output <path>]";
+
+/// Entry point of the `tso` binary. Returns the process exit code.
+pub fn main() -> i32 {
+    match run_from_args(std::env::args().skip(1)) {
+        Ok(()) => 0,
+        Err(error) => {
+            eprintln!("tso: {}", error);
+            1
+        }
+    }
+}
+
+fn run_from_args(mut args: impl Iterator<Item = String>) -> Result<(), Box<dyn Error>> {
+    let mut config_path = None;
+    let mut format = None;
+    let mut output = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => match args.next().as_deref() {
+                Some("json") => format = Some(Format::Json),
+                Some("csv") => format = Some(Format::Csv),
+                _ => return Err(USAGE.into()),
+            },
+            "--output" => output = Some(PathBuf::from(args.next().ok_or(USAGE)?)),
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ if config_path.is_none() => config_path = Some(PathBuf::from(arg)),
+            _ => return Err(USAGE.into()),
+        }
+    }
+    let config_path = config_path.ok_or(USAGE)?;
+    let mut config: Config = toml::from_str(&fs::read_to_string(&config_path)?)?;
+    if let Some(format) = format {
+        config.output.format = format;
+    }
+    if output.is_some() {
+        config.output.path = output;
+    }
+
+    let rendered = run(&config)?;
+    match &config.output.path {
+        Some(path) => fs::write(path, rendered)?,
+        None => io::stdout().write_all(rendered.as_bytes())?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expression_configs_run_to_csv() {
+        let config: Config = toml::from_str(
+            r#"
+            turtles = 5
+            dimensions = 2
+            lower = -1.0
+            upper = 1.0
+            goal = -1.0
+            max_iterations = 3
+            seed = 11
+
+            [objective]
+            expression = "x0^2 + math::abs(x1)"
+
+            [output]
+            format = "csv"
+            "#,
+        )
+        .unwrap();
+
+        let csv = run(&config).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "turtle,best_score,x0,x1");
+        assert!(lines[1].starts_with("best,"));
+        assert_eq!(lines.len(), 2 + 5);
+    }
+
+    #[test]
+    fn broken_expressions_are_reported() {
+        let objective = ObjectiveConfig::Expression("(x0 + 1".to_string());
+        assert!(objective.build(1).is_err());
+    }
+}
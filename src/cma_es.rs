@@ -0,0 +1,384 @@
+//! CMA-ES, the covariance matrix adaptation evolution strategy, for smooth continuous problems.
+//!
+//! Every generation samples `population` candidates from a multivariate normal distribution,
+//! moves its mean towards the weighted best half of them and adapts the covariance matrix and
+//! the step size `sigma` from the paths the mean has taken, following Hansen's tutorial. Where
+//! the swarm crawls through a narrow valley for thousands of iterations, the distribution learns
+//! the valley's shape within a few dozen generations.
+//!
+//! A run that stagnates, with the distribution collapsed or the scores flat, restarts from a new
+//! random mean with twice the population (IPOP), keeping the best found so far. Candidates are
+//! projected into the boundary before they are scored. Runs stop like an `Optimizer`'s and
+//! finish with the same `OptimizationResult`.
+
+use ndarray::{Array1, Array2, Axis};
+use ndarray_rand::rand::{thread_rng, SeedableRng};
+use ndarray_rand::rand_distr::StandardNormal;
+use ndarray_rand::RandomExt;
+use rand_chacha::ChaCha12Rng;
+
+use crate::boundary::Boundary;
+use crate::objective::ObjectiveFunction;
+use crate::{CubicBoundary, Direction, OptimizationResult, Termination};
+
+// The search distribution and the evolution paths that adapt it.
+struct Distribution {
+    mean: Array1<f64>,
+    sigma: f64,
+    covariance: Array2<f64>,
+    // The eigenvectors and the square roots of the eigenvalues of the covariance.
+    basis: Array2<f64>,
+    scales: Array1<f64>,
+    sigma_path: Array1<f64>,
+    covariance_path: Array1<f64>,
+    generations: usize,
+    // The best score of recent generations, for noticing stagnation.
+    recent: Vec<f64>,
+}
+
+impl Distribution {
+    fn new(mean: Array1<f64>, sigma: f64) -> Self {
+        let n = mean.len();
+        Self {
+            mean,
+            sigma,
+            covariance: Array2::eye(n),
+            basis: Array2::eye(n),
+            scales: Array1::ones(n),
+            sigma_path: Array1::zeros(n),
+            covariance_path: Array1::zeros(n),
+            generations: 0,
+            recent: Vec::new(),
+        }
+    }
+}
+
+pub struct CmaEs<'a, B = CubicBoundary> {
+    pub boundaries: B,
+    /// Generations spent over all restarts.
+    pub iterations: usize,
+    /// How many times the objective has been evaluated.
+    pub evaluations: usize,
+    pub best_score: f64,
+    pub best_position: Array1<f64>,
+    pub objective_function: ObjectiveFunction<'a>,
+    pub goal: f64,
+    /// The most generations `optimize` may spend before giving up on the goal.
+    pub max_iterations: Option<usize>,
+    pub direction: Direction,
+    /// The candidates per generation, `4 + 3 ln n` to start with and doubled by every restart.
+    pub population: usize,
+    /// The initial step size, as a fraction of the mean width of the boundary.
+    pub initial_sigma: f64,
+    /// How many times the run may restart with a bigger population.
+    pub max_restarts: usize,
+    pub restarts: usize,
+    /// Stagnation thresholds on the spread of the distribution and of the recent scores.
+    pub tolerance: f64,
+    // The candidates of the last generation and their scores.
+    candidates: Vec<(f64, Array1<f64>)>,
+    distribution: Option<Distribution>,
+    rng: ChaCha12Rng,
+}
+
+impl<'a, B: Boundary> CmaEs<'a, B> {
+    pub fn new(
+        boundaries: B,
+        objective_function: impl Into<ObjectiveFunction<'a>>,
+        goal: f64,
+    ) -> Self {
+        let rng = ChaCha12Rng::from_rng(thread_rng()).expect("the rng provides a seed");
+        Self::with_rng(boundaries, objective_function.into(), goal, rng)
+    }
+
+    /// Like `new`, but every sample is drawn reproducibly from `seed`.
+    pub fn new_seeded(
+        boundaries: B,
+        objective_function: impl Into<ObjectiveFunction<'a>>,
+        goal: f64,
+        seed: u64,
+    ) -> Self {
+        let rng = ChaCha12Rng::seed_from_u64(seed);
+        Self::with_rng(boundaries, objective_function.into(), goal, rng)
+    }
+
+    fn with_rng(
+        boundaries: B,
+        objective_function: ObjectiveFunction<'a>,
+        goal: f64,
+        rng: ChaCha12Rng,
+    ) -> Self {
+        let n = boundaries.dimensions();
+        Self {
+            best_position: Array1::zeros(n),
+            boundaries,
+            iterations: 0,
+            evaluations: 0,
+            best_score: f64::INFINITY,
+            objective_function,
+            goal,
+            max_iterations: None,
+            direction: Direction::Minimize,
+            population: 4 + (3. * (n.max(1) as f64).ln()) as usize,
+            initial_sigma: 0.3,
+            max_restarts: 9,
+            restarts: 0,
+            tolerance: 1e-12,
+            candidates: Vec::new(),
+            distribution: None,
+            rng,
+        }
+    }
+
+    /// The current step size, once the first generation has been sampled.
+    pub fn sigma(&self) -> Option<f64> {
+        self.distribution
+            .as_ref()
+            .map(|distribution| distribution.sigma)
+    }
+
+    /// Evolves generations until the best score reaches the goal or `max_iterations` is spent.
+    pub fn optimize(&mut self) -> OptimizationResult {
+        loop {
+            if let Some(termination) = self.termination() {
+                return self.result(termination);
+            }
+            self.iterate();
+        }
+    }
+
+    /// Evolves a single generation. Returns why `optimize` would stop here, if it would.
+    pub fn step(&mut self) -> Option<Termination> {
+        self.iterate();
+        self.termination()
+    }
+
+    fn termination(&self) -> Option<Termination> {
+        if self.candidates.is_empty() {
+            return None;
+        }
+        if self.direction.reaches(self.best_score, self.goal) {
+            return Some(Termination::GoalReached);
+        }
+        self.max_iterations
+            .filter(|limit| self.iterations >= *limit)
+            .map(|_| Termination::IterationLimit)
+    }
+
+    fn result(&self, termination: Termination) -> OptimizationResult {
+        OptimizationResult {
+            best_position: self.best_position.clone(),
+            best_score: self.best_score,
+            iterations: self.iterations,
+            evaluations: self.evaluations,
+            turtle_bests: self.candidates.clone(),
+            termination,
+        }
+    }
+
+    fn start(&mut self) -> Distribution {
+        let (lower, upper) = self.boundaries.bounding_box();
+        let width = (&upper - &lower).mean().unwrap_or(1.);
+        let sigma = self.initial_sigma * if width > 0. { width } else { 1. };
+        Distribution::new(self.boundaries.sample(&mut self.rng), sigma)
+    }
+
+    // Whether the distribution has collapsed or the recent scores stopped changing.
+    fn stagnated(&self, distribution: &Distribution) -> bool {
+        let n = distribution.mean.len().max(1) as f64;
+        let spread = distribution
+            .covariance
+            .diag()
+            .iter()
+            .fold(0f64, |widest, variance| widest.max(variance.sqrt()));
+        let window = 10 + (30. * n / self.population.max(1) as f64) as usize;
+        let flat = distribution.recent.len() >= window && {
+            let recent = &distribution.recent[distribution.recent.len() - window..];
+            let high = recent.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            let low = recent.iter().copied().fold(f64::INFINITY, f64::min);
+            high - low <= self.tolerance
+        };
+        let (smallest, largest) = distribution
+            .scales
+            .iter()
+            .fold((f64::INFINITY, 0f64), |(low, high), scale| {
+                (low.min(*scale), high.max(*scale))
+            });
+        flat || distribution.sigma * spread <= self.tolerance
+            || !distribution.sigma.is_finite()
+            || largest > 1e7 * smallest
+    }
+
+    pub(crate) fn iterate(&mut self) {
+        if self.candidates.is_empty() && self.distribution.is_none() {
+            self.best_score = self.direction.worst();
+        }
+        let mut distribution = match self.distribution.take() {
+            Some(distribution) => distribution,
+            None => self.start(),
+        };
+        let n = distribution.mean.len();
+        let lambda = self.population.max(2);
+        let mu = lambda / 2;
+        let mut weights: Array1<f64> = (0..mu)
+            .map(|i| (mu as f64 + 0.5).ln() - ((i + 1) as f64).ln())
+            .collect();
+        weights /= weights.sum();
+        let mu_eff = 1. / weights.mapv(|w| w * w).sum();
+        let nf = n.max(1) as f64;
+        let c_sigma = (mu_eff + 2.) / (nf + mu_eff + 5.);
+        let d_sigma = 1. + 2. * (((mu_eff - 1.) / (nf + 1.)).sqrt() - 1.).max(0.) + c_sigma;
+        let c_c = (4. + mu_eff / nf) / (nf + 4. + 2. * mu_eff / nf);
+        let c_1 = 2. / ((nf + 1.3).powi(2) + mu_eff);
+        let c_mu = (1. - c_1).min(2. * (mu_eff - 2. + 1. / mu_eff) / ((nf + 2.).powi(2) + mu_eff));
+        let chi_n = nf.sqrt() * (1. - 1. / (4. * nf) + 1. / (21. * nf * nf));
+
+        // Sample, repair into the boundary and score the generation.
+        let mut offspring: Vec<(f64, Array1<f64>, Array1<f64>)> = (0..lambda)
+            .map(|_| {
+                let z = Array1::<f64>::random_using(n, StandardNormal, &mut self.rng);
+                let mut x = &distribution.mean
+                    + distribution.sigma * distribution.basis.dot(&(&distribution.scales * &z));
+                self.boundaries.project(&mut x);
+                let y = (&x - &distribution.mean) / distribution.sigma;
+                let score = (self.objective_function)(&x);
+                self.evaluations += 1;
+                (score, x, y)
+            })
+            .collect();
+        let direction = self.direction;
+        offspring.sort_by(|a, b| {
+            let (a, b) = (a.0, b.0);
+            if direction.is_better(a, b) || (b.is_nan() && !a.is_nan()) {
+                core::cmp::Ordering::Less
+            } else if direction.is_better(b, a) || (a.is_nan() && !b.is_nan()) {
+                core::cmp::Ordering::Greater
+            } else {
+                core::cmp::Ordering::Equal
+            }
+        });
+        if direction.is_better(offspring[0].0, self.best_score) {
+            self.best_score = offspring[0].0;
+            self.best_position = offspring[0].1.clone();
+        }
+        distribution.recent.push(offspring[0].0);
+
+        // Move the mean towards the best half.
+        let mut y_w = Array1::zeros(n);
+        for (weight, (_, _, y)) in weights.iter().zip(offspring.iter()) {
+            y_w.scaled_add(*weight, y);
+        }
+        distribution.mean = &distribution.mean + distribution.sigma * &y_w;
+
+        // Update the evolution paths.
+        let inverse_root = distribution
+            .basis
+            .dot(&Array2::from_diag(&distribution.scales.mapv(|d| 1. / d)))
+            .dot(&distribution.basis.t());
+        distribution.sigma_path = (1. - c_sigma) * &distribution.sigma_path
+            + (c_sigma * (2. - c_sigma) * mu_eff).sqrt() * inverse_root.dot(&y_w);
+        distribution.generations += 1;
+        let path_length = distribution.sigma_path.mapv(|x| x * x).sum().sqrt();
+        let h_sigma = path_length
+            / (1. - (1. - c_sigma).powi(2 * distribution.generations as i32)).sqrt()
+            < (1.4 + 2. / (nf + 1.)) * chi_n;
+        let h_sigma = if h_sigma { 1. } else { 0. };
+        distribution.covariance_path = (1. - c_c) * &distribution.covariance_path
+            + h_sigma * (c_c * (2. - c_c) * mu_eff).sqrt() * &y_w;
+
+        // Adapt the covariance and the step size.
+        let path = distribution.covariance_path.view().insert_axis(Axis(1));
+        let rank_one = path.dot(&path.t());
+        let mut rank_mu = Array2::zeros((n, n));
+        for (weight, (_, _, y)) in weights.iter().zip(offspring.iter()) {
+            let y = y.view().insert_axis(Axis(1));
+            rank_mu.scaled_add(*weight, &y.dot(&y.t()));
+        }
+        distribution.covariance = (1. - c_1 - c_mu) * &distribution.covariance
+            + c_1 * (rank_one + (1. - h_sigma) * c_c * (2. - c_c) * &distribution.covariance)
+            + c_mu * rank_mu;
+        distribution.sigma *= ((c_sigma / d_sigma) * (path_length / chi_n - 1.)).exp();
+        let (eigenvalues, basis) = symmetric_eigen(&distribution.covariance);
+        distribution.scales = eigenvalues.mapv(|value| value.max(1e-300).sqrt());
+        distribution.basis = basis;
+
+        self.candidates = offspring
+            .into_iter()
+            .map(|(score, position, _)| (score, position))
+            .collect();
+        self.iterations += 1;
+
+        if self.stagnated(&distribution) && self.restarts < self.max_restarts {
+            self.restarts += 1;
+            self.population *= 2;
+        } else {
+            self.distribution = Some(distribution);
+        }
+    }
+}
+
+// The eigenvalues and eigenvectors, as columns, of a symmetric matrix by cyclic Jacobi rotations.
+fn symmetric_eigen(matrix: &Array2<f64>) -> (Array1<f64>, Array2<f64>) {
+    let n = matrix.nrows();
+    let mut a = matrix.clone();
+    let mut vectors = Array2::eye(n);
+    for _ in 0..64 {
+        let off: f64 = (0..n)
+            .flat_map(|i| (0..n).filter(move |j| *j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[[i, j]] * a[[i, j]])
+            .sum();
+        if off <= 1e-30 * a.diag().mapv(|x| x * x).sum().max(1e-300) {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[[p, q]] == 0. {
+                    continue;
+                }
+                let theta = (a[[q, q]] - a[[p, p]]) / (2. * a[[p, q]]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.).sqrt());
+                let c = 1. / (t * t + 1.).sqrt();
+                let s = t * c;
+                for k in 0..n {
+                    let (akp, akq) = (a[[k, p]], a[[k, q]]);
+                    a[[k, p]] = c * akp - s * akq;
+                    a[[k, q]] = s * akp + c * akq;
+                }
+                for k in 0..n {
+                    let (apk, aqk) = (a[[p, k]], a[[q, k]]);
+                    a[[p, k]] = c * apk - s * aqk;
+                    a[[q, k]] = s * apk + c * aqk;
+                }
+                for k in 0..n {
+                    let (vkp, vkq) = (vectors[[k, p]], vectors[[k, q]]);
+                    vectors[[k, p]] = c * vkp - s * vkq;
+                    vectors[[k, q]] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+    (a.diag().to_owned(), vectors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benchmarks::rosenbrock;
+
+    #[test]
+    fn covariance_adapts_to_narrow_valleys() {
+        let boundaries = CubicBoundary::new(4, -2., 2.);
+        let mut cma = CmaEs::new_seeded(boundaries, &rosenbrock, 1e-8, 3);
+        cma.max_iterations = Some(3000);
+        let result = cma.optimize();
+
+        assert_eq!(result.termination, Termination::GoalReached);
+        assert!(result.best_position.iter().all(|x| (x - 1.).abs() < 1e-3));
+        assert_eq!(result.turtle_bests.len(), cma.population);
+
+        let matrix = ndarray::array![[2., 1.], [1., 2.]];
+        let (values, vectors) = symmetric_eigen(&matrix);
+        let rebuilt = vectors.dot(&Array2::from_diag(&values)).dot(&vectors.t());
+        assert!((rebuilt - matrix).iter().all(|x| x.abs() < 1e-12));
+    }
+}
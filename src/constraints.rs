@@ -0,0 +1,317 @@
+//! Inequality constraints handled with penalties.
+//!
+//! A `Constrained` objective adds `coefficient * Σ max(0, g(x))²` to the objective for every
+//! registered constraint `g(x) <= 0`, so infeasible turtles score worse the further out they are.
+//! Picking the coefficient by hand is fiddly; pushing an `AdaptivePenalty` onto an optimizer's
+//! middleware raises it while the best solution stays infeasible and relaxes it while the whole
+//! swarm is feasible. `Constrained::optimize` runs an optimizer on the penalized objective and
+//! reports the feasibility of what it found alongside the result.
+//!
+//! The coefficient changes while an optimizer holds the objective, so it is kept in an atomic
+//! where the target has 64 bit atomics, which makes a `Constrained` objective `Sync`. Elsewhere
+//! it is kept in a `Cell`, and the objective can only be scored through `optimize_with`.
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+#[cfg(not(target_has_atomic = "64"))]
+use core::cell::Cell;
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::{AtomicU64, Ordering};
+use ndarray::Array1;
+
+use crate::boundary::Boundary;
+use crate::middleware::Middleware;
+use crate::{OptimizationResult, Optimizer};
+
+/// A constraint `g(x) <= 0`. Positive values measure the violation.
+pub type ConstraintFunction<'a> = Box<dyn Fn(&Array1<f64>) -> f64 + Send + Sync + 'a>;
+
+#[cfg(target_has_atomic = "64")]
+struct Coefficient(AtomicU64);
+
+#[cfg(target_has_atomic = "64")]
+impl Coefficient {
+    fn new(coefficient: f64) -> Self {
+        Self(AtomicU64::new(coefficient.to_bits()))
+    }
+
+    fn get(&self) -> f64 {
+        f64::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    fn set(&self, coefficient: f64) {
+        self.0.store(coefficient.to_bits(), Ordering::Relaxed);
+    }
+}
+
+#[cfg(not(target_has_atomic = "64"))]
+type Coefficient = Cell<f64>;
+
+pub struct Constrained<'a> {
+    objective_function: &'a (dyn Fn(&Array1<f64>) -> f64 + Sync),
+    pub constraints: Vec<ConstraintFunction<'a>>,
+    coefficient: Coefficient,
+}
+
+impl<'a> Constrained<'a> {
+    pub fn new(
+        objective_function: &'a (dyn Fn(&Array1<f64>) -> f64 + Sync),
+        coefficient: f64,
+    ) -> Self {
+        Self {
+            objective_function,
+            constraints: Vec::new(),
+            coefficient: Coefficient::new(coefficient),
+        }
+    }
+
+    /// Registers the constraint `constraint(x) <= 0`.
+    pub fn add(&mut self, constraint: impl Fn(&Array1<f64>) -> f64 + Send + Sync + 'a) {
+        self.constraints.push(Box::new(constraint));
+    }
+
+    pub fn coefficient(&self) -> f64 {
+        self.coefficient.get()
+    }
+
+    pub fn set_coefficient(&self, coefficient: f64) {
+        self.coefficient.set(coefficient);
+    }
+
+    /// How far `position` violates each constraint, zero where it is satisfied.
+    pub fn violations(&self, position: &Array1<f64>) -> Vec<f64> {
+        self.constraints
+            .iter()
+            .map(|constraint| constraint(position).max(0.))
+            .collect()
+    }
+
+    pub fn is_feasible(&self, position: &Array1<f64>) -> bool {
+        self.constraints
+            .iter()
+            .all(|constraint| constraint(position) <= 0.)
+    }
+
+    /// The objective plus the penalty.
+    pub fn evaluate(&self, position: &Array1<f64>) -> f64 {
+        let penalty: f64 = self
+            .violations(position)
+            .iter()
+            .map(|violation| violation * violation)
+            .sum();
+        if penalty == 0. {
+            (self.objective_function)(position)
+        } else {
+            (self.objective_function)(position) + self.coefficient.get() * penalty
+        }
+    }
+}
+
+/// How the best solution of a run stands against each constraint.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Feasibility {
+    /// The best position's violation of each constraint, in registration order.
+    pub violations: Vec<f64>,
+    /// How many turtles currently sit at feasible positions.
+    pub feasible_turtles: usize,
+    pub turtles: usize,
+}
+
+impl Feasibility {
+    pub fn is_feasible(&self) -> bool {
+        self.violations.iter().all(|violation| *violation == 0.)
+    }
+
+    /// The most violated constraint, if any constraint is violated at all.
+    pub fn binding(&self) -> Option<usize> {
+        self.violations
+            .iter()
+            .enumerate()
+            .filter(|(_, violation)| **violation > 0.)
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(constraint, _)| constraint)
+    }
+}
+
+/// What a constrained run found.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConstrainedResult {
+    pub result: OptimizationResult,
+    pub feasibility: Feasibility,
+}
+
+impl Constrained<'_> {
+    /// Runs `optimizer`, which should be scoring this constrained objective, with `optimize`.
+    pub fn optimize<B: Boundary>(&self, optimizer: &mut Optimizer<'_, B>) -> ConstrainedResult {
+        let result = optimizer.optimize();
+        ConstrainedResult {
+            result,
+            feasibility: self.feasibility(optimizer),
+        }
+    }
+
+    pub fn feasibility<B>(&self, optimizer: &Optimizer<'_, B>) -> Feasibility {
+        Feasibility {
+            violations: self.violations(&optimizer.best_position),
+            feasible_turtles: optimizer
+                .turtles
+                .iter()
+                .filter(|turtle| self.is_feasible(&turtle.position))
+                .count(),
+            turtles: optimizer.turtles.len(),
+        }
+    }
+}
+
+impl<B: Boundary> Optimizer<'_, B> {
+    /// Like `report`, followed by the best position's violation of every constraint.
+    #[cfg(feature = "std")]
+    pub fn report_constrained(&self, constrained: &Constrained) {
+        self.report_constrained_to(constrained, std::io::stdout().lock())
+            .expect("the report is printed to stdout");
+    }
+
+    /// Writes the report `report_constrained` prints to `writer` instead.
+    #[cfg(feature = "std")]
+    pub fn report_constrained_to<W: std::io::Write>(
+        &self,
+        constrained: &Constrained,
+        mut writer: W,
+    ) -> std::io::Result<()> {
+        self.report_to(&mut writer)?;
+        let feasibility = constrained.feasibility(self);
+        writeln!(
+            writer,
+            "{} of {} turtles are feasible.",
+            feasibility.feasible_turtles, feasibility.turtles
+        )?;
+        for (constraint, violation) in feasibility.violations.iter().enumerate() {
+            writeln!(
+                writer,
+                "\t Constraint #{}'s violation is {}",
+                constraint, violation
+            )?;
+        }
+        if let Some(constraint) = feasibility.binding() {
+            writeln!(writer, "Constraint #{} is violated the most.", constraint)?;
+        }
+        Ok(())
+    }
+}
+
+/// Middleware adapting a `Constrained` objective's coefficient once per iteration. When the
+/// coefficient changes the personal bests are rescored, so they stay comparable.
+pub struct AdaptivePenalty<'c, 'a> {
+    constrained: &'c Constrained<'a>,
+    /// Consecutive iterations of an infeasible best, or an entirely feasible swarm, before the
+    /// coefficient is changed.
+    pub patience: usize,
+    pub increase: f64,
+    pub decrease: f64,
+    pub min: f64,
+    pub max: f64,
+    infeasible: usize,
+    feasible: usize,
+}
+
+impl<'c, 'a> AdaptivePenalty<'c, 'a> {
+    pub fn new(constrained: &'c Constrained<'a>) -> Self {
+        Self {
+            constrained,
+            patience: 5,
+            increase: 2.,
+            decrease: 0.5,
+            min: 1e-6,
+            max: 1e12,
+            infeasible: 0,
+            feasible: 0,
+        }
+    }
+}
+
+impl<B: Boundary> Middleware<B> for AdaptivePenalty<'_, '_> {
+    fn post_evaluate(&mut self, optimizer: &mut Optimizer<'_, B>) {
+        if self.constrained.is_feasible(&optimizer.best_position) {
+            self.infeasible = 0;
+        } else {
+            self.infeasible += 1;
+        }
+        if optimizer
+            .turtles
+            .iter()
+            .all(|turtle| self.constrained.is_feasible(&turtle.position))
+        {
+            self.feasible += 1;
+        } else {
+            self.feasible = 0;
+        }
+
+        let coefficient = self.constrained.coefficient();
+        let adapted = if self.infeasible >= self.patience.max(1) {
+            self.infeasible = 0;
+            (coefficient * self.increase).min(self.max)
+        } else if self.feasible >= self.patience.max(1) {
+            self.feasible = 0;
+            (coefficient * self.decrease).max(self.min)
+        } else {
+            coefficient
+        };
+        if adapted != coefficient {
+            self.constrained.set_coefficient(adapted);
+            optimizer.reevaluate_bests();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CubicBoundary;
+
+    #[test]
+    fn penalties_grow_until_the_best_is_feasible() {
+        let sum = |x: &Array1<f64>| x.sum();
+        let mut constrained = Constrained::new(&sum, 1e-6);
+        constrained.add(|x: &Array1<f64>| 1. - x.sum());
+        let objective = |x: &Array1<f64>| constrained.evaluate(x);
+
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(50, boundaries, &objective, -10., 4);
+        let mut adaptive = AdaptivePenalty::new(&constrained);
+        adaptive.patience = 1;
+        adaptive.increase = 10.;
+        optimizer.middleware.push(Box::new(adaptive));
+        optimizer.iterate();
+        assert!(!constrained.is_feasible(&optimizer.best_position));
+        optimizer.max_iterations = Some(21);
+        let outcome = constrained.optimize(&mut optimizer);
+
+        assert!(constrained.coefficient() > 1e-6);
+        assert!(outcome.feasibility.is_feasible());
+        assert_eq!(outcome.result.best_position, optimizer.best_position);
+        assert_eq!(constrained.violations(&Array1::zeros(2)), [1.]);
+    }
+
+    #[test]
+    fn the_most_violated_constraint_is_binding() {
+        let sum = |x: &Array1<f64>| x.sum();
+        let mut constrained = Constrained::new(&sum, 1.);
+        constrained.add(|x: &Array1<f64>| x[0] - 0.5);
+        constrained.add(|x: &Array1<f64>| x[1] + 2.);
+        constrained.add(|x: &Array1<f64>| -x[0] - 1.);
+
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &sum, -10., 2);
+        optimizer.iterate();
+        let feasibility = constrained.feasibility(&optimizer);
+
+        assert_eq!(feasibility.turtles, 10);
+        assert_eq!(feasibility.feasible_turtles, 0);
+        assert!(!feasibility.is_feasible());
+        assert_eq!(feasibility.binding(), Some(1));
+        assert_eq!(feasibility.violations[2], 0.);
+        optimizer.report_constrained(&constrained);
+    }
+}
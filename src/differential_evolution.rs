@@ -0,0 +1,251 @@
+//! Differential evolution over the same objectives and boundaries as the turtles.
+//!
+//! Every generation each member of the population is challenged by a trial built from the
+//! difference of two other members added to a base, `rand/1/bin` taking a random member as the
+//! base and `best/1/bin` the best one, crossed over coordinate by coordinate with the member. The
+//! trial replaces the member when it scores at least as well. Runs stop like an `Optimizer`'s, at
+//! the goal or at `max_iterations`, and finish with the same `OptimizationResult`, so a problem
+//! can be handed to either without changes.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ndarray::Array1;
+#[cfg(feature = "std")]
+use rand::thread_rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::boundary::Boundary;
+use crate::objective::ObjectiveFunction;
+use crate::{CubicBoundary, Direction, OptimizationResult, Termination};
+
+/// How the base of a trial is chosen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Strategy {
+    /// A random member other than the one challenged.
+    #[default]
+    Rand1Bin,
+    /// The best member, which converges faster at the cost of diversity.
+    Best1Bin,
+}
+
+pub struct DifferentialEvolution<'a, B = CubicBoundary> {
+    pub population: Vec<Array1<f64>>,
+    /// The score of every member, empty until the population has been evaluated.
+    pub scores: Vec<f64>,
+    pub boundaries: B,
+    pub iterations: usize,
+    /// How many times the objective has been evaluated.
+    pub evaluations: usize,
+    pub best_score: f64,
+    pub best_position: Array1<f64>,
+    pub objective_function: ObjectiveFunction<'a>,
+    pub goal: f64,
+    /// The most generations `optimize` may spend before giving up on the goal.
+    pub max_iterations: Option<usize>,
+    pub direction: Direction,
+    pub strategy: Strategy,
+    /// The weight `F` of the difference between the two members.
+    pub differential_weight: f64,
+    /// The probability `CR` that a coordinate is taken from the mutant.
+    pub crossover: f64,
+    rng: ChaCha12Rng,
+}
+
+impl<'a, B: Boundary> DifferentialEvolution<'a, B> {
+    #[cfg(feature = "std")]
+    pub fn new(
+        population: usize,
+        boundaries: B,
+        objective_function: impl Into<ObjectiveFunction<'a>>,
+        goal: f64,
+    ) -> Self {
+        let rng = ChaCha12Rng::from_rng(thread_rng()).expect("the rng provides a seed");
+        Self::with_rng(population, boundaries, objective_function.into(), goal, rng)
+    }
+
+    /// Like `new`, but the population is drawn reproducibly from `seed`.
+    pub fn new_seeded(
+        population: usize,
+        boundaries: B,
+        objective_function: impl Into<ObjectiveFunction<'a>>,
+        goal: f64,
+        seed: u64,
+    ) -> Self {
+        let rng = ChaCha12Rng::seed_from_u64(seed);
+        Self::with_rng(population, boundaries, objective_function.into(), goal, rng)
+    }
+
+    fn with_rng(
+        population: usize,
+        boundaries: B,
+        objective_function: ObjectiveFunction<'a>,
+        goal: f64,
+        mut rng: ChaCha12Rng,
+    ) -> Self {
+        let shape = boundaries.dimensions();
+        Self {
+            population: (0..population)
+                .map(|_| boundaries.sample(&mut rng))
+                .collect(),
+            scores: Vec::new(),
+            boundaries,
+            iterations: 0,
+            evaluations: 0,
+            best_score: f64::INFINITY,
+            best_position: Array1::zeros(shape),
+            objective_function,
+            goal,
+            max_iterations: None,
+            direction: Direction::Minimize,
+            strategy: Strategy::Rand1Bin,
+            differential_weight: 0.8,
+            crossover: 0.9,
+            rng,
+        }
+    }
+
+    /// Evolves generations until the best score reaches the goal or `max_iterations` is spent.
+    pub fn optimize(&mut self) -> OptimizationResult {
+        loop {
+            if let Some(termination) = self.termination() {
+                return self.result(termination);
+            }
+            self.iterate();
+        }
+    }
+
+    /// Evolves a single generation. Returns why `optimize` would stop here, if it would.
+    pub fn step(&mut self) -> Option<Termination> {
+        self.iterate();
+        self.termination()
+    }
+
+    fn termination(&self) -> Option<Termination> {
+        if self.scores.is_empty() {
+            return None;
+        }
+        if self.direction.reaches(self.best_score, self.goal) {
+            return Some(Termination::GoalReached);
+        }
+        self.max_iterations
+            .filter(|limit| self.iterations >= *limit)
+            .map(|_| Termination::IterationLimit)
+    }
+
+    fn result(&self, termination: Termination) -> OptimizationResult {
+        OptimizationResult {
+            best_position: self.best_position.clone(),
+            best_score: self.best_score,
+            iterations: self.iterations,
+            evaluations: self.evaluations,
+            turtle_bests: self
+                .scores
+                .iter()
+                .copied()
+                .zip(self.population.iter().cloned())
+                .collect(),
+            termination,
+        }
+    }
+
+    fn consider(&mut self, score: f64, position: &Array1<f64>) {
+        if self.direction.is_better(score, self.best_score) {
+            self.best_score = score;
+            self.best_position = position.clone();
+        }
+    }
+
+    // A member other than those in `taken`, or any member when there are too few.
+    fn pick(&mut self, taken: &[usize]) -> usize {
+        let size = self.population.len();
+        if size <= taken.len() {
+            return self.rng.gen_range(0..size);
+        }
+        loop {
+            let member = self.rng.gen_range(0..size);
+            if !taken.contains(&member) {
+                return member;
+            }
+        }
+    }
+
+    pub(crate) fn iterate(&mut self) {
+        if self.population.is_empty() {
+            self.iterations += 1;
+            return;
+        }
+        if self.scores.is_empty() {
+            self.best_score = self.direction.worst();
+            for member in 0..self.population.len() {
+                let score = (self.objective_function)(&self.population[member]);
+                self.evaluations += 1;
+                self.scores.push(score);
+                let position = self.population[member].clone();
+                self.consider(score, &position);
+            }
+        }
+        let best = (0..self.scores.len())
+            .reduce(|best, member| {
+                if self
+                    .direction
+                    .is_better(self.scores[member], self.scores[best])
+                {
+                    member
+                } else {
+                    best
+                }
+            })
+            .unwrap_or(0);
+        let dimensions = self.boundaries.dimensions();
+        for member in 0..self.population.len() {
+            let base = match self.strategy {
+                Strategy::Rand1Bin => self.pick(&[member]),
+                Strategy::Best1Bin => best,
+            };
+            let first = self.pick(&[member, base]);
+            let second = self.pick(&[member, base, first]);
+            let mutant = &self.population[base]
+                + self.differential_weight * (&self.population[first] - &self.population[second]);
+            // One coordinate always comes from the mutant so the trial differs from the member.
+            let forced = self.rng.gen_range(0..dimensions.max(1));
+            let mut trial = self.population[member].clone();
+            for dimension in 0..dimensions {
+                if dimension == forced || self.rng.gen::<f64>() < self.crossover {
+                    trial[dimension] = mutant[dimension];
+                }
+            }
+            self.boundaries.project(&mut trial);
+            let score = (self.objective_function)(&trial);
+            self.evaluations += 1;
+            if !self.direction.is_better(self.scores[member], score) && !score.is_nan() {
+                self.consider(score, &trial);
+                self.population[member] = trial;
+                self.scores[member] = score;
+            }
+        }
+        self.iterations += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn both_strategies_solve_the_swarms_problems() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| (x - 0.3) * (x - 0.3)).sum();
+        let boundaries = CubicBoundary::new(3, -2., 2.);
+        for strategy in [Strategy::Rand1Bin, Strategy::Best1Bin] {
+            let mut evolution =
+                DifferentialEvolution::new_seeded(20, boundaries, &parabola, 1e-6, 3);
+            evolution.strategy = strategy;
+            evolution.max_iterations = Some(500);
+            let result = evolution.optimize();
+            assert_eq!(result.termination, Termination::GoalReached);
+            assert_eq!(result.turtle_bests.len(), 20);
+            assert!(evolution.boundaries.contains(&result.best_position));
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! Cost estimates before committing to a run.
+//!
+//! A dry run times a few evaluations of the positions the turtles are about to be scored at,
+//! without touching the swarm, and projects how many evaluations and how much wall time a run of
+//! a given number of iterations will take.
+
+use std::time::{Duration, Instant};
+
+use crate::Optimizer;
+
+/// What one evaluation costs, measured on a few samples.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CostEstimate {
+    /// How many evaluations were timed.
+    pub samples: usize,
+    pub mean_latency: Duration,
+    pub max_latency: Duration,
+    /// Evaluations per iteration, one per turtle.
+    pub evaluations_per_iteration: usize,
+}
+
+/// The projected cost of a run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Projection {
+    pub iterations: usize,
+    pub evaluations: usize,
+    pub duration: Duration,
+}
+
+// Durations are multiplied and divided in nanoseconds, so counts beyond `u32::MAX` are taken in
+// full. Whatever overflows a `Duration` becomes `Duration::MAX`.
+fn from_nanos(nanos: u128) -> Duration {
+    u64::try_from(nanos / 1_000_000_000).map_or(Duration::MAX, |seconds| {
+        Duration::new(seconds, (nanos % 1_000_000_000) as u32)
+    })
+}
+
+// The wall time of `count` evaluations taking `latency` each.
+fn repeated(latency: Duration, count: usize) -> Duration {
+    from_nanos(latency.as_nanos().saturating_mul(count as u128))
+}
+
+impl CostEstimate {
+    /// The mean wall time of one iteration.
+    pub fn time_per_iteration(&self) -> Duration {
+        repeated(self.mean_latency, self.evaluations_per_iteration)
+    }
+
+    pub fn project(&self, iterations: usize) -> Projection {
+        let evaluations = iterations.saturating_mul(self.evaluations_per_iteration);
+        Projection {
+            iterations,
+            evaluations,
+            duration: repeated(self.mean_latency, evaluations),
+        }
+    }
+
+    /// How many iterations fit into `duration`.
+    pub fn iterations_within(&self, duration: Duration) -> usize {
+        duration
+            .as_nanos()
+            .checked_div(self.time_per_iteration().as_nanos())
+            .map_or(usize::MAX, |iterations| {
+                iterations.min(usize::MAX as u128) as usize
+            })
+    }
+}
+
+impl<B> Optimizer<'_, B> {
+    /// Times `samples` evaluations at the turtles' current positions, cycling through the turtles
+    /// when there are more samples than turtles. The swarm and its bests are left untouched.
+    pub fn dry_run(&self, samples: usize) -> CostEstimate {
+        let mut total = Duration::ZERO;
+        let mut max_latency = Duration::ZERO;
+        let samples = if self.turtles.is_empty() { 0 } else { samples };
+        for sample in 0..samples {
+            let position = &self.turtles[sample % self.turtles.len()].position;
+            let start = Instant::now();
+            (self.objective_function)(position);
+            let latency = start.elapsed();
+            total += latency;
+            max_latency = max_latency.max(latency);
+        }
+        CostEstimate {
+            samples,
+            mean_latency: total
+                .as_nanos()
+                .checked_div(samples as u128)
+                .map_or(Duration::ZERO, from_nanos),
+            max_latency,
+            evaluations_per_iteration: self.turtles.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CubicBoundary;
+    use ndarray::Array1;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn runs_are_projected_from_timed_samples() {
+        let calls = AtomicUsize::new(0);
+        let slow = |x: &Array1<f64>| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            std::thread::sleep(Duration::from_millis(2));
+            x.sum()
+        };
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let optimizer = Optimizer::new_seeded(10, boundaries, &slow, -1., 1);
+        let estimate = optimizer.dry_run(3);
+
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+        assert_eq!(optimizer.best_score, f64::INFINITY);
+        assert!(estimate.mean_latency >= Duration::from_millis(2));
+        assert!(estimate.max_latency >= estimate.mean_latency);
+        let projection = estimate.project(100);
+        assert_eq!(projection.evaluations, 1000);
+        assert!(projection.duration >= Duration::from_secs(2));
+        assert_eq!(
+            estimate.iterations_within(estimate.time_per_iteration() * 7),
+            7
+        );
+    }
+
+    #[test]
+    fn projections_take_counts_beyond_u32_in_full() {
+        let estimate = CostEstimate {
+            samples: 1,
+            mean_latency: Duration::from_nanos(3),
+            max_latency: Duration::from_nanos(3),
+            evaluations_per_iteration: 1 << 33,
+        };
+        assert_eq!(estimate.time_per_iteration(), Duration::from_nanos(3 << 33));
+        assert_eq!(estimate.project(2).duration, Duration::from_nanos(3 << 34));
+        let glacial = CostEstimate {
+            mean_latency: Duration::from_secs(u64::MAX / 2),
+            ..estimate
+        };
+        assert_eq!(glacial.project(1).duration, Duration::MAX);
+    }
+}
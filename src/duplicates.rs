@@ -0,0 +1,121 @@
+//! Spreading out turtles that have piled onto the same spot.
+//!
+//! Turtles clamped into a corner of the boundary, or pulled onto the same best, end up at
+//! numerically identical positions and every one of them is scored for the same answer. With
+//! `Optimizer::deduplication` set, all but the first turtle at a spot are nudged to a random
+//! point nearby within the boundary after every move.
+
+use ndarray::Array1;
+use rand::{Rng, RngCore};
+
+use crate::boundary::Boundary;
+use crate::Turtle;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Deduplication {
+    /// Positions that differ by no more than this in every dimension are duplicates.
+    pub tolerance: f64,
+    /// How far a duplicate is nudged along each dimension, at most. A duplicate is moved to a
+    /// random position in the boundary when that is not far enough to leave the spot.
+    pub radius: f64,
+    /// How many duplicates have been nudged so far.
+    pub perturbed: usize,
+}
+
+impl Default for Deduplication {
+    fn default() -> Self {
+        Self {
+            tolerance: 1e-12,
+            radius: 1e-3,
+            perturbed: 0,
+        }
+    }
+}
+
+impl Deduplication {
+    pub fn new(tolerance: f64, radius: f64) -> Self {
+        Self {
+            tolerance,
+            radius,
+            perturbed: 0,
+        }
+    }
+
+    fn same(&self, a: &Array1<f64>, b: &Array1<f64>) -> bool {
+        a.iter()
+            .zip(b.iter())
+            .all(|(a, b)| a - b <= self.tolerance && b - a <= self.tolerance)
+    }
+
+    /// Nudges every turtle that shares its position with an earlier one and returns how many
+    /// were nudged.
+    pub(crate) fn spread<B: Boundary + ?Sized>(
+        &mut self,
+        turtles: &mut [Turtle],
+        boundaries: &B,
+        rng: &mut dyn RngCore,
+    ) -> usize {
+        let mut perturbed = 0;
+        for turtle in 1..turtles.len() {
+            let (earlier, rest) = turtles.split_at_mut(turtle);
+            let position = &mut rest[0].position;
+            if !earlier
+                .iter()
+                .any(|other| self.same(&other.position, position))
+            {
+                continue;
+            }
+            let radius = if self.radius > 0. {
+                self.radius
+            } else {
+                -self.radius
+            };
+            // Nudges pushed back onto the spot by the boundary are tried again, and a turtle
+            // that cannot be nudged off it is placed anywhere in the boundary instead.
+            let mut nudged = None;
+            for _ in 0..10 {
+                let mut candidate = position.mapv(|x| x + rng.gen_range(-radius..=radius));
+                boundaries.project(&mut candidate);
+                if !earlier
+                    .iter()
+                    .any(|other| self.same(&other.position, &candidate))
+                {
+                    nudged = Some(candidate);
+                    break;
+                }
+            }
+            *position = nudged.unwrap_or_else(|| boundaries.sample(rng));
+            perturbed += 1;
+        }
+        self.perturbed += perturbed;
+        perturbed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CubicBoundary, Optimizer};
+
+    #[test]
+    fn turtles_piled_into_a_corner_are_spread_out() {
+        let slope = |x: &Array1<f64>| x.sum();
+        let corner: Array1<f64> = -Array1::ones(2);
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(8, boundaries, &slope, -10., 2);
+        for turtle in optimizer.turtles.iter_mut() {
+            turtle.position = corner.clone();
+            turtle.velocity = Array1::zeros(2);
+        }
+        optimizer.deduplication = Some(Deduplication::default());
+        optimizer.iterate();
+
+        assert_eq!(optimizer.deduplication.unwrap().perturbed, 7);
+        assert_eq!(optimizer.turtles[0].position, corner);
+        for turtle in optimizer.turtles.iter().skip(1) {
+            assert_ne!(turtle.position, corner);
+            assert!(optimizer.boundaries.contains(&turtle.position));
+        }
+    }
+}
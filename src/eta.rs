@@ -0,0 +1,120 @@
+//! Estimates of how long a run still has to go.
+//!
+//! The estimator keeps the last few observations of the best score and extrapolates the recent
+//! improvement rate linearly, so users can decide whether to keep waiting or kill a run. Feed it
+//! from an ask/tell loop or from a `Watch` on a run in another thread.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::watch::Watch;
+
+#[derive(Clone, Copy, Debug)]
+struct Observation {
+    iterations: usize,
+    score: f64,
+    at: Instant,
+}
+
+#[derive(Clone, Debug)]
+pub struct EtaEstimator {
+    /// How many observations the improvement rate is estimated from.
+    pub window: usize,
+    observations: VecDeque<Observation>,
+}
+
+impl EtaEstimator {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window,
+            observations: VecDeque::new(),
+        }
+    }
+
+    /// Records the best score after `iterations` iterations. Unscored optimizers are skipped.
+    pub fn observe(&mut self, iterations: usize, score: f64) {
+        self.observe_at(iterations, score, Instant::now());
+    }
+
+    /// Records the latest best published to `watch`.
+    pub fn observe_watch(&mut self, watch: &Watch) {
+        let best = watch.best();
+        self.observe(best.iterations, best.score);
+    }
+
+    fn observe_at(&mut self, iterations: usize, score: f64, at: Instant) {
+        if !score.is_finite() {
+            return;
+        }
+        if let Some(last) = self.observations.back() {
+            if iterations <= last.iterations {
+                return;
+            }
+        }
+        self.observations.push_back(Observation {
+            iterations,
+            score,
+            at,
+        });
+        while self.observations.len() > self.window.max(2) {
+            self.observations.pop_front();
+        }
+    }
+
+    /// The recent improvement of the best score per iteration, if there is enough history.
+    pub fn rate(&self) -> Option<f64> {
+        let (first, last) = (self.observations.front()?, self.observations.back()?);
+        if last.iterations == first.iterations {
+            return None;
+        }
+        Some((first.score - last.score) / (last.iterations - first.iterations) as f64)
+    }
+
+    /// Iterations until the best score reaches `goal` at the recent rate. `None` when the run is
+    /// not improving, zero when it is already there.
+    pub fn estimated_iterations_to(&self, goal: f64) -> Option<f64> {
+        let last = self.observations.back()?;
+        if last.score <= goal {
+            return Some(0.);
+        }
+        match self.rate()? {
+            rate if rate > 0. => Some((last.score - goal) / rate),
+            _ => None,
+        }
+    }
+
+    /// Wall time until the best score reaches `goal`, at the recent rate and iteration speed.
+    pub fn estimated_time_to(&self, goal: f64) -> Option<Duration> {
+        let iterations = self.estimated_iterations_to(goal)?;
+        let (first, last) = (self.observations.front()?, self.observations.back()?);
+        let per_iteration = last.at.duration_since(first.at).as_secs_f64()
+            / (last.iterations - first.iterations).max(1) as f64;
+        Duration::try_from_secs_f64(iterations * per_iteration).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steady_improvement_is_extrapolated() {
+        let mut estimator = EtaEstimator::new(3);
+        let start = Instant::now();
+        assert_eq!(estimator.estimated_iterations_to(0.), None);
+        for step in 0..5 {
+            let at = start + Duration::from_millis(10 * step as u64);
+            estimator.observe_at(step * 2, 10. - step as f64, at);
+        }
+
+        assert_eq!(estimator.rate(), Some(0.5));
+        assert_eq!(estimator.estimated_iterations_to(0.), Some(12.));
+        assert_eq!(estimator.estimated_iterations_to(7.), Some(0.));
+        let time = estimator.estimated_time_to(0.).unwrap();
+        assert!((time.as_secs_f64() - 0.06).abs() < 1e-9);
+
+        estimator.observe_at(10, 6., start + Duration::from_millis(50));
+        estimator.observe_at(12, 6., start + Duration::from_millis(60));
+        assert_eq!(estimator.estimated_iterations_to(0.), None);
+    }
+}
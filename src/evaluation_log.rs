@@ -0,0 +1,135 @@
+//! An audit trail of every evaluation the swarm makes.
+//!
+//! With a log set on `Optimizer::evaluation_log`, each score recorded for a turtle, whether the
+//! optimizer evaluated it or it came in through `tell`, is written as one JSON line:
+//!
+//! `{"iteration":3,"turtle":0,"position":[0.5,-0.25],"score":0.3125,"duration":0.0012}`
+//!
+//! Durations are in seconds and `null` for scores computed outside of the optimizer, as are
+//! scores that are not finite. The lines are a complete record of an expensive campaign and a
+//! ready made training set for surrogates.
+
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use ndarray::Array1;
+
+pub struct EvaluationLog<'a> {
+    writer: Box<dyn Write + Send + 'a>,
+    error: Option<io::Error>,
+}
+
+fn number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        String::from("null")
+    }
+}
+
+impl<'a> EvaluationLog<'a> {
+    pub fn new(writer: impl Write + Send + 'a) -> Self {
+        Self {
+            writer: Box::new(writer),
+            error: None,
+        }
+    }
+
+    /// A buffered log written to a new file at `path`.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+
+    pub(crate) fn write(
+        &mut self,
+        iteration: usize,
+        turtle: usize,
+        position: &Array1<f64>,
+        score: f64,
+        duration: Option<Duration>,
+    ) {
+        if self.error.is_some() {
+            return;
+        }
+        let mut line = format!(
+            "{{\"iteration\":{},\"turtle\":{},\"position\":[",
+            iteration, turtle
+        );
+        for (dimension, x) in position.iter().enumerate() {
+            if dimension > 0 {
+                line.push(',');
+            }
+            line.push_str(&number(*x));
+        }
+        let duration = duration.map_or(String::from("null"), |duration| {
+            number(duration.as_secs_f64())
+        });
+        let _ = writeln!(
+            line,
+            "],\"score\":{},\"duration\":{}}}",
+            number(score),
+            duration
+        );
+        if let Err(error) = self.writer.write_all(line.as_bytes()) {
+            self.error = Some(error);
+        }
+    }
+
+    /// The first write error, after which the log stops writing. Optimization carries on either
+    /// way.
+    pub fn error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CubicBoundary, Optimizer};
+    use std::sync::{Arc, Mutex};
+
+    // A writer that can still be read after the optimizer has taken the log.
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(bytes)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn every_evaluation_is_logged_as_a_json_line() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(3, boundaries, &parabola, -1., 7);
+        let output = Shared::default();
+        optimizer.evaluation_log = Some(EvaluationLog::new(output.clone()));
+        optimizer.iterate();
+        let positions = optimizer.ask();
+        optimizer.tell(&[f64::NAN, 1., 2.]);
+
+        let text = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert!(lines[0].starts_with("{\"iteration\":0,\"turtle\":0,\"position\":["));
+        assert!(!lines[2].ends_with("\"duration\":null}"));
+        let expected = format!(
+            "{{\"iteration\":1,\"turtle\":0,\"position\":[{},{}],\
+             \"score\":null,\"duration\":null}}",
+            positions[0][0], positions[0][1]
+        );
+        assert_eq!(lines[3], expected);
+    }
+}
@@ -0,0 +1,379 @@
+//! Benchmarking experiments over grids of algorithms, problems and repetitions.
+//!
+//! Every algorithm is run on every problem a number of times, each repetition seeded with its
+//! index so experiments can be repeated exactly. The collected convergence curves can be
+//! summarized, compared with nonparametric significance tests, and exported as tables.
+
+use std::fmt::Write;
+
+use crate::multistart::Statistics;
+use crate::{Optimizer, Problem};
+
+type Runner<'a> = Box<dyn Fn(&Problem<'a>, u64) -> Vec<f64> + 'a>;
+
+/// A named optimization algorithm. Given a problem and a seed it returns the best score observed
+/// after every iteration.
+pub struct Algorithm<'a> {
+    pub name: String,
+    run: Runner<'a>,
+}
+
+impl<'a> Algorithm<'a> {
+    pub fn new<R>(name: &str, run: R) -> Self
+    where
+        R: Fn(&Problem<'a>, u64) -> Vec<f64> + 'a,
+    {
+        Self {
+            name: name.to_string(),
+            run: Box::new(run),
+        }
+    }
+
+    /// The turtle swarm optimizer with the given swarm size and iteration budget.
+    pub fn turtle_swarm(name: &str, turtles: usize, iterations: usize) -> Self {
+        Self::new(name, move |problem: &Problem<'a>, seed| {
+            let mut optimizer = Optimizer::new_seeded(
+                turtles,
+                problem.boundaries,
+                problem.objective_function,
+                f64::NEG_INFINITY,
+                seed,
+            );
+            (0..iterations)
+                .map(|_| {
+                    optimizer.iterate();
+                    optimizer.best_score
+                })
+                .collect()
+        })
+    }
+}
+
+pub struct Experiment<'a> {
+    pub algorithms: Vec<Algorithm<'a>>,
+    pub problems: Vec<(String, Problem<'a>)>,
+    pub repetitions: usize,
+}
+
+impl<'a> Experiment<'a> {
+    pub fn new(repetitions: usize) -> Self {
+        Self {
+            algorithms: Vec::new(),
+            problems: Vec::new(),
+            repetitions,
+        }
+    }
+
+    pub fn add_algorithm(&mut self, algorithm: Algorithm<'a>) -> &mut Self {
+        self.algorithms.push(algorithm);
+        self
+    }
+
+    pub fn add_problem(&mut self, name: &str, problem: Problem<'a>) -> &mut Self {
+        self.problems.push((name.to_string(), problem));
+        self
+    }
+
+    /// Runs the full algorithm × problem × repetition grid.
+    pub fn run(&self) -> ExperimentResults {
+        let mut records = Vec::new();
+        for algorithm in self.algorithms.iter() {
+            for (problem_name, problem) in self.problems.iter() {
+                for repetition in 0..self.repetitions {
+                    records.push(Record {
+                        algorithm: algorithm.name.clone(),
+                        problem: problem_name.clone(),
+                        repetition,
+                        convergence: (algorithm.run)(problem, repetition as u64),
+                    });
+                }
+            }
+        }
+        ExperimentResults { records }
+    }
+}
+
+/// The convergence curve of one algorithm on one problem in one repetition.
+#[derive(Clone, Debug)]
+pub struct Record {
+    pub algorithm: String,
+    pub problem: String,
+    pub repetition: usize,
+    pub convergence: Vec<f64>,
+}
+
+impl Record {
+    pub fn final_score(&self) -> f64 {
+        self.convergence.last().copied().unwrap_or(f64::INFINITY)
+    }
+}
+
+/// Both significance tests for a pair of algorithms on the same problem.
+#[derive(Clone, Copy, Debug)]
+pub struct Comparison {
+    pub mann_whitney: TestResult,
+    pub wilcoxon: TestResult,
+}
+
+#[derive(Clone, Debug)]
+pub struct ExperimentResults {
+    pub records: Vec<Record>,
+}
+
+impl ExperimentResults {
+    /// Final best scores of `algorithm` on `problem`, ordered by repetition.
+    pub fn final_scores(&self, algorithm: &str, problem: &str) -> Vec<f64> {
+        let mut records: Vec<&Record> = self
+            .records
+            .iter()
+            .filter(|r| r.algorithm == algorithm && r.problem == problem)
+            .collect();
+        records.sort_by_key(|r| r.repetition);
+        records.iter().map(|r| r.final_score()).collect()
+    }
+
+    pub fn summary(&self, algorithm: &str, problem: &str) -> Statistics {
+        Statistics::from_scores(&self.final_scores(algorithm, problem))
+    }
+
+    /// Compares the final scores of two algorithms on a problem. Repetitions share seeds, so the
+    /// Wilcoxon test pairs them by repetition.
+    pub fn compare(&self, a: &str, b: &str, problem: &str) -> Comparison {
+        let a = self.final_scores(a, problem);
+        let b = self.final_scores(b, problem);
+        Comparison {
+            mann_whitney: mann_whitney_u(&a, &b),
+            wilcoxon: wilcoxon_signed_rank(&a, &b),
+        }
+    }
+
+    /// One row per algorithm and problem with summary statistics of the final scores.
+    pub fn summary_csv(&self) -> String {
+        let mut csv = String::from("algorithm,problem,runs,mean,median,std,best\n");
+        for (algorithm, problem) in self.cells() {
+            let scores = self.final_scores(&algorithm, &problem);
+            let statistics = Statistics::from_scores(&scores);
+            let best = scores.iter().copied().fold(f64::INFINITY, f64::min);
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{}",
+                algorithm,
+                problem,
+                scores.len(),
+                statistics.mean,
+                statistics.median,
+                statistics.std,
+                best
+            );
+        }
+        csv
+    }
+
+    /// The same table as `summary_csv`, formatted for papers and READMEs.
+    pub fn summary_markdown(&self) -> String {
+        let mut table = String::from("| algorithm | problem | runs | mean | median | std |\n");
+        table.push_str("|---|---|---|---|---|---|\n");
+        for (algorithm, problem) in self.cells() {
+            let scores = self.final_scores(&algorithm, &problem);
+            let statistics = Statistics::from_scores(&scores);
+            let _ = writeln!(
+                table,
+                "| {} | {} | {} | {:.6e} | {:.6e} | {:.6e} |",
+                algorithm,
+                problem,
+                scores.len(),
+                statistics.mean,
+                statistics.median,
+                statistics.std
+            );
+        }
+        table
+    }
+
+    /// Every convergence curve in long format, one row per iteration.
+    pub fn convergence_csv(&self) -> String {
+        let mut csv = String::from("algorithm,problem,repetition,iteration,best_score\n");
+        for record in self.records.iter() {
+            for (iteration, score) in record.convergence.iter().enumerate() {
+                let _ = writeln!(
+                    csv,
+                    "{},{},{},{},{}",
+                    record.algorithm, record.problem, record.repetition, iteration, score
+                );
+            }
+        }
+        csv
+    }
+
+    // Algorithm and problem pairs in the order they were first recorded.
+    fn cells(&self) -> Vec<(String, String)> {
+        let mut cells: Vec<(String, String)> = Vec::new();
+        for record in self.records.iter() {
+            let cell = (record.algorithm.clone(), record.problem.clone());
+            if !cells.contains(&cell) {
+                cells.push(cell);
+            }
+        }
+        cells
+    }
+}
+
+/// The statistic of a significance test and its two-sided p-value from the normal approximation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TestResult {
+    pub statistic: f64,
+    pub p_value: f64,
+}
+
+/// The Mann–Whitney U test for two independent samples. The statistic is the U of `a`.
+pub fn mann_whitney_u(a: &[f64], b: &[f64]) -> TestResult {
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    if a.is_empty() || b.is_empty() {
+        return TestResult {
+            statistic: f64::NAN,
+            p_value: 1.,
+        };
+    }
+    let combined: Vec<f64> = a.iter().chain(b.iter()).copied().collect();
+    let (ranks, ties) = average_ranks(&combined);
+    let rank_sum_a: f64 = ranks[..a.len()].iter().sum();
+    let u = rank_sum_a - n1 * (n1 + 1.) / 2.;
+
+    let n = n1 + n2;
+    let mean = n1 * n2 / 2.;
+    let variance = n1 * n2 / 12. * ((n + 1.) - ties / (n * (n - 1.)));
+    TestResult {
+        statistic: u,
+        p_value: two_sided_p(u, mean, variance),
+    }
+}
+
+/// The Wilcoxon signed-rank test for paired samples. The statistic is the smaller of the positive
+/// and negative rank sums; pairs with no difference are dropped.
+pub fn wilcoxon_signed_rank(a: &[f64], b: &[f64]) -> TestResult {
+    let differences: Vec<f64> = a
+        .iter()
+        .zip(b.iter())
+        .map(|(a, b)| a - b)
+        .filter(|d| *d != 0.)
+        .collect();
+    if differences.is_empty() {
+        return TestResult {
+            statistic: 0.,
+            p_value: 1.,
+        };
+    }
+    let magnitudes: Vec<f64> = differences.iter().map(|d| d.abs()).collect();
+    let (ranks, ties) = average_ranks(&magnitudes);
+    let positive: f64 = ranks
+        .iter()
+        .zip(differences.iter())
+        .filter(|(_, d)| **d > 0.)
+        .map(|(rank, _)| rank)
+        .sum();
+
+    let n = differences.len() as f64;
+    let total = n * (n + 1.) / 2.;
+    let mean = total / 2.;
+    let variance = n * (n + 1.) * (2. * n + 1.) / 24. - ties / 48.;
+    TestResult {
+        statistic: positive.min(total - positive),
+        p_value: two_sided_p(positive, mean, variance),
+    }
+}
+
+// Ranks starting at one with ties given their average rank, plus the tie correction sum(t³ - t).
+fn average_ranks(values: &[f64]) -> (Vec<f64>, f64) {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|a, b| values[*a].total_cmp(&values[*b]));
+
+    let mut ranks = vec![0.; values.len()];
+    let mut ties = 0.;
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        let rank = (start + end + 1) as f64 / 2.;
+        for index in order[start..end].iter() {
+            ranks[*index] = rank;
+        }
+        let t = (end - start) as f64;
+        ties += t * t * t - t;
+        start = end;
+    }
+    (ranks, ties)
+}
+
+// Normal approximation with a continuity correction.
+fn two_sided_p(statistic: f64, mean: f64, variance: f64) -> f64 {
+    if variance <= 0. {
+        return 1.;
+    }
+    let z = ((statistic - mean).abs() - 0.5).max(0.) / variance.sqrt();
+    erfc(z / std::f64::consts::SQRT_2).min(1.)
+}
+
+// Complementary error function with fractional error below 1.2e-7 (Numerical Recipes' erfcc).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1. / (1. + 0.5 * z);
+    let polynomial = -z * z - 1.26551223
+        + t * (1.00002368
+            + t * (0.37409196
+                + t * (0.09678418
+                    + t * (-0.18628806
+                        + t * (0.27886807
+                            + t * (-1.13520398
+                                + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277))))))));
+    let result = t * polynomial.exp();
+    if x >= 0. {
+        result
+    } else {
+        2. - result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CubicBoundary;
+    use ndarray::Array1;
+
+    #[test]
+    fn grid_is_fully_covered() {
+        fn parabola(x: &Array1<f64>) -> f64 {
+            (x * x).sum()
+        }
+        let mut experiment = Experiment::new(3);
+        experiment
+            .add_algorithm(Algorithm::turtle_swarm("small", 3, 4))
+            .add_algorithm(Algorithm::turtle_swarm("large", 12, 4))
+            .add_problem(
+                "parabola",
+                Problem {
+                    objective_function: &parabola,
+                    boundaries: CubicBoundary::new(2, -1., 1.),
+                },
+            );
+        let results = experiment.run();
+
+        assert_eq!(results.records.len(), 6);
+        assert_eq!(results.final_scores("small", "parabola").len(), 3);
+        assert_eq!(results.summary_csv().lines().count(), 3);
+        assert_eq!(results.convergence_csv().lines().count(), 1 + 6 * 4);
+        let comparison = results.compare("small", "large", "parabola");
+        assert!((0.0..=1.0).contains(&comparison.mann_whitney.p_value));
+    }
+
+    #[test]
+    fn significance_tests_separate_shifted_samples() {
+        let a: Vec<f64> = (0..12).map(|i| i as f64).collect();
+        let b: Vec<f64> = (0..12).map(|i| i as f64 + 20.).collect();
+        assert_eq!(mann_whitney_u(&a, &b).statistic, 0.);
+        assert!(mann_whitney_u(&a, &b).p_value < 1e-3);
+        assert!(wilcoxon_signed_rank(&a, &b).p_value < 1e-2);
+        assert_eq!(wilcoxon_signed_rank(&a, &a).p_value, 1.);
+    }
+}
@@ -0,0 +1,176 @@
+//! Objectives that can fail, such as ones calling out to an external solver.
+//!
+//! A `Fallible` objective returns a `Result`, and its `FailurePolicy` decides what a failure
+//! means to the run: the position is scored as infinitely bad, it is tried again a few times
+//! first, or the run is aborted and `Optimizer::try_optimize` hands the error back. A `Fallible`
+//! converts into the optimizer's objective by reference, so the same one is passed to the
+//! constructor and to `try_optimize`:
+//!
+//! ```
+//! use ndarray::Array1;
+//! use turtle_swarm_optimizer::fallible::{Fallible, FailurePolicy};
+//! use turtle_swarm_optimizer::{CubicBoundary, Optimizer};
+//!
+//! let solve = |x: &Array1<f64>| -> Result<f64, String> { Ok(x.mapv(|x| x * x).sum()) };
+//! let solver = Fallible::new(&solve, FailurePolicy::Abort);
+//! let boundaries = CubicBoundary::new(2, -1., 1.);
+//! let mut optimizer = Optimizer::new(20, boundaries, &solver, 1.);
+//! assert!(optimizer.try_optimize(&solver).is_ok());
+//! ```
+//!
+//! Errors are whatever the objective returns. For transient failures that deserve a pause
+//! before trying again, wrap the objective in `retry::Retrying` and use its `try_evaluate`.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use ndarray::Array1;
+
+use crate::boundary::Boundary;
+use crate::objective::ObjectiveFunction;
+use crate::{OptimizationResult, Optimizer};
+
+/// What a failed evaluation means to the run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FailurePolicy {
+    /// The position scores `f64::INFINITY`, which the optimizer's `nan_policy` treats as an
+    /// invalid score, the worst of either direction by default.
+    #[default]
+    Infinity,
+    /// The position is evaluated up to this many times in total, and scores `f64::INFINITY`
+    /// when every attempt fails.
+    Retry(usize),
+    /// The run ends with the error. Positions scored after the failure, until the iteration
+    /// ends, are not evaluated and score `f64::INFINITY`.
+    Abort,
+}
+
+type Function<'a, E> = dyn Fn(&Array1<f64>) -> Result<f64, E> + Sync + 'a;
+
+pub struct Fallible<'a, E> {
+    objective_function: &'a Function<'a, E>,
+    pub policy: FailurePolicy,
+    failures: AtomicUsize,
+    // The error that aborted the run, until `try_optimize` takes it.
+    error: Mutex<Option<E>>,
+}
+
+impl<'a, E> Fallible<'a, E> {
+    pub fn new(objective_function: &'a Function<'a, E>, policy: FailurePolicy) -> Self {
+        Self {
+            objective_function,
+            policy,
+            failures: AtomicUsize::new(0),
+            error: Mutex::new(None),
+        }
+    }
+
+    /// Scores `position` under the policy.
+    pub fn evaluate(&self, position: &Array1<f64>) -> f64 {
+        if self.lock_error().is_some() {
+            return f64::INFINITY;
+        }
+        let attempts = match self.policy {
+            FailurePolicy::Retry(attempts) => attempts.max(1),
+            FailurePolicy::Infinity | FailurePolicy::Abort => 1,
+        };
+        for _ in 0..attempts {
+            match (self.objective_function)(position) {
+                Ok(score) => return score,
+                Err(error) => {
+                    self.failures.fetch_add(1, Ordering::Relaxed);
+                    if self.policy == FailurePolicy::Abort {
+                        *self.lock_error() = Some(error);
+                    }
+                }
+            }
+        }
+        f64::INFINITY
+    }
+
+    /// How many evaluations have failed, counting every failed attempt.
+    pub fn failures(&self) -> usize {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    /// The error that aborted the run, if one did, which is cleared so the run can go on.
+    pub fn take_error(&self) -> Option<E> {
+        self.lock_error().take()
+    }
+
+    // The lock is only held to look at or swap the error, so a poisoned one still holds a whole
+    // error or none.
+    fn lock_error(&self) -> std::sync::MutexGuard<'_, Option<E>> {
+        self.error
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<'a, E: Send> From<&'a Fallible<'a, E>> for ObjectiveFunction<'a> {
+    fn from(fallible: &'a Fallible<'a, E>) -> Self {
+        ObjectiveFunction::new(move |position: &Array1<f64>| fallible.evaluate(position))
+    }
+}
+
+impl<B: Boundary> Optimizer<'_, B> {
+    /// Like `optimize`, for an optimizer scoring with `fallible`. A failure under
+    /// `FailurePolicy::Abort` ends the run at the end of its iteration with the error.
+    pub fn try_optimize<E>(&mut self, fallible: &Fallible<'_, E>) -> Result<OptimizationResult, E> {
+        loop {
+            if let Some(error) = fallible.take_error() {
+                return Err(error);
+            }
+            if let Some(termination) = self.termination() {
+                return Ok(self.result(termination));
+            }
+            self.iterate();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CubicBoundary, Termination};
+
+    #[test]
+    fn failures_follow_the_policy() {
+        // The solver does not converge left of the origin, and fails every other call elsewhere.
+        let calls = AtomicUsize::new(0);
+        let solve = |x: &Array1<f64>| {
+            let calls = calls.fetch_add(1, Ordering::Relaxed) + 1;
+            if x[0] < 0. {
+                Err("diverged")
+            } else if calls.is_multiple_of(2) {
+                Err("timed out")
+            } else {
+                Ok(x.mapv(|x| x * x).sum())
+            }
+        };
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+
+        let infinite = Fallible::new(&solve, FailurePolicy::Infinity);
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &infinite, -1., 4);
+        optimizer.max_iterations = Some(5);
+        let result = optimizer.try_optimize(&infinite).unwrap();
+        assert_eq!(result.termination, Termination::IterationLimit);
+        assert!(infinite.failures() > 0);
+        assert!(result.best_score.is_finite() && result.best_position[0] >= 0.);
+
+        // The next call times out, and the retry after it goes through.
+        calls.store(1, Ordering::Relaxed);
+        let retried = Fallible::new(&solve, FailurePolicy::Retry(2));
+        assert_eq!(retried.evaluate(&Array1::from(vec![0.5, 0.])), 0.25);
+        assert_eq!(
+            retried.evaluate(&Array1::from(vec![-0.5, 0.])),
+            f64::INFINITY
+        );
+        assert_eq!(retried.failures(), 3);
+
+        let aborting = Fallible::new(&solve, FailurePolicy::Abort);
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &aborting, -1., 4);
+        assert!(optimizer.try_optimize(&aborting).is_err());
+        assert_eq!(optimizer.iterations, 1);
+    }
+}
@@ -0,0 +1,279 @@
+//! Evaluation over a message queue, enabled with the `farm` feature.
+//!
+//! Every candidate position becomes a JSON job, `{"reply_to": ..., "id": ..., "position": [...]}`,
+//! pushed onto a jobs queue. Workers written in any language take jobs, score them, and answer
+//! with `{"id": ..., "score": ...}` on the queue named by `reply_to`. The `redis` and `nats`
+//! features provide queues for those brokers; anything else can implement [`Queue`].
+
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+use ndarray::Array1;
+use serde::{Deserialize, Serialize};
+
+use crate::{OptimizationResult, Optimizer};
+
+/// A candidate position waiting for a worker.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Job {
+    pub reply_to: String,
+    pub id: u64,
+    pub position: Vec<f64>,
+}
+
+/// A worker's answer to a [`Job`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Outcome {
+    pub id: u64,
+    pub score: f64,
+}
+
+/// The broker connection a [`Farm`] talks through.
+pub trait Queue {
+    /// Where workers should send their outcomes.
+    fn reply_to(&self) -> &str;
+    /// Hands a job to the workers.
+    fn push(&mut self, job: &Job) -> Result<(), Box<dyn Error>>;
+    /// Waits a short while for the next outcome, returning `None` if none arrived.
+    fn pop(&mut self) -> Result<Option<Outcome>, Box<dyn Error>>;
+}
+
+/// Scores swarms by farming their positions out to workers.
+pub struct Farm<Q: Queue> {
+    pub queue: Q,
+    /// How long to wait for a whole generation before giving up. Without it the farm waits for
+    /// the workers forever.
+    pub timeout: Option<Duration>,
+    next_id: u64,
+}
+
+impl<Q: Queue> Farm<Q> {
+    pub fn new(queue: Q) -> Self {
+        Self {
+            queue,
+            timeout: None,
+            next_id: 0,
+        }
+    }
+
+    /// Publishes one job per position and waits for all of their scores. Outcomes of earlier
+    /// batches, such as late answers after a timeout, are ignored.
+    pub fn evaluate(&mut self, positions: &[Array1<f64>]) -> Result<Vec<f64>, Box<dyn Error>> {
+        let first = self.next_id;
+        for position in positions {
+            let job = Job {
+                reply_to: self.queue.reply_to().to_string(),
+                id: self.next_id,
+                position: position.to_vec(),
+            };
+            self.queue.push(&job)?;
+            self.next_id += 1;
+        }
+
+        let started = Instant::now();
+        let mut scores: Vec<Option<f64>> = vec![None; positions.len()];
+        let mut missing = positions.len();
+        while missing > 0 {
+            if self
+                .timeout
+                .is_some_and(|timeout| started.elapsed() >= timeout)
+            {
+                return Err(format!("timed out waiting for {} scores", missing).into());
+            }
+            let Some(outcome) = self.queue.pop()? else {
+                continue;
+            };
+            let Some(slot) = outcome
+                .id
+                .checked_sub(first)
+                .and_then(|index| scores.get_mut(index as usize))
+            else {
+                continue;
+            };
+            if slot.is_none() {
+                missing -= 1;
+            }
+            *slot = Some(outcome.score);
+        }
+        Ok(scores.into_iter().flatten().collect())
+    }
+
+    /// Drives `optimizer` through ask and tell until it terminates, as `Optimizer::optimize`
+    /// would, with every generation scored by the workers.
+    pub fn optimize(
+        &mut self,
+        optimizer: &mut Optimizer,
+    ) -> Result<OptimizationResult, Box<dyn Error>> {
+        loop {
+            if let Some(termination) = optimizer.termination() {
+                return Ok(optimizer.result(termination));
+            }
+            let scores = self.evaluate(&optimizer.ask())?;
+            optimizer.tell(&scores);
+        }
+    }
+}
+
+/// Jobs and outcomes on Redis lists. Jobs are `LPUSH`ed onto the jobs list, and workers `BRPOP`
+/// them and `LPUSH` their outcomes onto the list named by `reply_to`.
+#[cfg(feature = "redis")]
+pub struct RedisQueue {
+    connection: redis::Connection,
+    jobs: String,
+    results: String,
+}
+
+#[cfg(feature = "redis")]
+impl RedisQueue {
+    /// Connects to `url` (for example `redis://127.0.0.1/`) and uses the `jobs` and `results`
+    /// lists. Runs sharing workers should each use their own results list.
+    pub fn connect(url: &str, jobs: &str, results: &str) -> Result<Self, Box<dyn Error>> {
+        let connection = redis::Client::open(url)?.get_connection()?;
+        Ok(Self {
+            connection,
+            jobs: jobs.to_string(),
+            results: results.to_string(),
+        })
+    }
+}
+
+#[cfg(feature = "redis")]
+impl Queue for RedisQueue {
+    fn reply_to(&self) -> &str {
+        &self.results
+    }
+
+    fn push(&mut self, job: &Job) -> Result<(), Box<dyn Error>> {
+        use redis::Commands;
+        let _: usize = self
+            .connection
+            .lpush(&self.jobs, serde_json::to_string(job)?)?;
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<Option<Outcome>, Box<dyn Error>> {
+        use redis::Commands;
+        let popped: Option<[String; 2]> = self.connection.brpop(&self.results, 1.)?;
+        match popped {
+            Some([_, outcome]) => Ok(Some(serde_json::from_str(&outcome)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Jobs published on a NATS subject, with outcomes returned to a private inbox. Workers should
+/// subscribe in a queue group so each job is scored once.
+#[cfg(feature = "nats")]
+pub struct NatsQueue {
+    // The client is asynchronous; the queue keeps a small runtime of its own to block on it.
+    runtime: tokio::runtime::Runtime,
+    client: async_nats::Client,
+    subject: String,
+    inbox: String,
+    subscriber: async_nats::Subscriber,
+}
+
+#[cfg(feature = "nats")]
+impl NatsQueue {
+    /// Connects to `url` (for example `nats://127.0.0.1:4222`) and publishes jobs on `subject`.
+    pub fn connect(url: &str, subject: &str) -> Result<Self, Box<dyn Error>> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()?;
+        let client = runtime.block_on(async_nats::connect(url))?;
+        let inbox = client.new_inbox();
+        let subscriber = runtime.block_on(client.subscribe(inbox.clone()))?;
+        Ok(Self {
+            runtime,
+            client,
+            subject: subject.to_string(),
+            inbox,
+            subscriber,
+        })
+    }
+}
+
+#[cfg(feature = "nats")]
+impl Queue for NatsQueue {
+    fn reply_to(&self) -> &str {
+        &self.inbox
+    }
+
+    fn push(&mut self, job: &Job) -> Result<(), Box<dyn Error>> {
+        let payload = serde_json::to_vec(job)?;
+        self.runtime
+            .block_on(self.client.publish(self.subject.clone(), payload.into()))?;
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Result<Option<Outcome>, Box<dyn Error>> {
+        use futures::StreamExt;
+        let subscriber = &mut self.subscriber;
+        let next = self.runtime.block_on(async {
+            tokio::time::timeout(Duration::from_secs(1), subscriber.next()).await
+        });
+        match next {
+            Ok(Some(message)) => Ok(Some(serde_json::from_slice(&message.payload)?)),
+            Ok(None) => Err("the NATS subscription was closed".into()),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{external_objective, CubicBoundary, Termination};
+    use std::collections::VecDeque;
+
+    /// Workers that answer in reverse order and repeat a stale outcome first.
+    struct Loopback {
+        pending: Vec<Job>,
+        outcomes: VecDeque<Outcome>,
+    }
+
+    impl Queue for Loopback {
+        fn reply_to(&self) -> &str {
+            "loopback"
+        }
+
+        fn push(&mut self, job: &Job) -> Result<(), Box<dyn Error>> {
+            self.pending.push(job.clone());
+            Ok(())
+        }
+
+        fn pop(&mut self) -> Result<Option<Outcome>, Box<dyn Error>> {
+            if self.outcomes.is_empty() {
+                if let Some(first) = self.pending.first() {
+                    self.outcomes.push_back(Outcome {
+                        id: first.id.wrapping_sub(1),
+                        score: -1.,
+                    });
+                }
+                for job in self.pending.drain(..).rev() {
+                    let score = job.position.iter().map(|x| x * x).sum();
+                    self.outcomes.push_back(Outcome { id: job.id, score });
+                }
+            }
+            Ok(self.outcomes.pop_front())
+        }
+    }
+
+    #[test]
+    fn workers_score_generations_in_any_order() {
+        let mut farm = Farm::new(Loopback {
+            pending: Vec::new(),
+            outcomes: VecDeque::new(),
+        });
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(5, boundaries, &external_objective, -1., 2);
+        optimizer.max_iterations = Some(3);
+        let result = farm.optimize(&mut optimizer).unwrap();
+
+        assert_eq!(result.termination, Termination::IterationLimit);
+        assert_eq!(optimizer.iterations, 3);
+        let expected: f64 = optimizer.best_position.iter().map(|x| x * x).sum();
+        assert_eq!(optimizer.best_score, expected);
+    }
+}
@@ -0,0 +1,306 @@
+//! A C interface, enabled with the `ffi` feature.
+//!
+//! C and C++ codes create an optimizer, register an objective callback, and then either step the
+//! swarm themselves or let it run to the goal. The matching declarations live in
+//! `include/turtle_swarm_optimizer.h`, which is regenerated from this file with
+//! `cbindgen --output include/turtle_swarm_optimizer.h`. The shared library to link against comes
+//! from the `bindings` crate: `cargo build --release --manifest-path bindings/Cargo.toml
+//! --features ffi`.
+
+use std::ffi::c_void;
+use std::slice;
+
+use ndarray::Array1;
+
+use crate::objective::ObjectiveFunction;
+use crate::{external_objective, CubicBoundary, Optimizer};
+
+/// An objective function implemented in C. It receives the position and its length along with the
+/// `user_data` pointer given to `tso_optimizer_set_objective`.
+pub type TsoObjective =
+    Option<extern "C" fn(position: *const f64, dimensions: usize, user_data: *mut c_void) -> f64>;
+
+#[derive(Clone, Copy)]
+struct Callback {
+    function: extern "C" fn(position: *const f64, dimensions: usize, user_data: *mut c_void) -> f64,
+    user_data: *mut c_void,
+}
+
+// SAFETY: the library never calls the objective from a thread of its own, only from the thread
+// calling into it, so whatever `user_data` points to is shared exactly as the C caller shares the
+// optimizer handle.
+unsafe impl Send for Callback {}
+unsafe impl Sync for Callback {}
+
+impl Callback {
+    fn call(&self, position: &Array1<f64>) -> f64 {
+        let position = position.to_vec();
+        (self.function)(position.as_ptr(), position.len(), self.user_data)
+    }
+}
+
+/// An opaque optimizer handle for C callers.
+pub struct TsoOptimizer {
+    optimizer: Optimizer<'static>,
+}
+
+impl TsoOptimizer {
+    fn new(
+        turtles: usize,
+        boundaries: CubicBoundary,
+        goal: f64,
+        seed: Option<u64>,
+    ) -> Box<TsoOptimizer> {
+        // Without an objective every position is as bad as any other.
+        let optimizer = match seed {
+            Some(seed) => {
+                Optimizer::new_seeded(turtles, boundaries, &external_objective, goal, seed)
+            }
+            None => Optimizer::new(turtles, boundaries, &external_objective, goal),
+        };
+        Box::new(TsoOptimizer { optimizer })
+    }
+}
+
+/// Creates an optimizer searching the cube `[lower, upper]^dimensions`. Free it with
+/// `tso_optimizer_destroy`.
+#[no_mangle]
+pub extern "C" fn tso_optimizer_new(
+    turtles: usize,
+    dimensions: usize,
+    lower: f64,
+    upper: f64,
+    goal: f64,
+) -> *mut TsoOptimizer {
+    let boundaries = CubicBoundary::new(dimensions, lower, upper);
+    Box::into_raw(TsoOptimizer::new(turtles, boundaries, goal, None))
+}
+
+/// Like `tso_optimizer_new`, but the turtles are placed reproducibly from `seed`.
+#[no_mangle]
+pub extern "C" fn tso_optimizer_new_seeded(
+    turtles: usize,
+    dimensions: usize,
+    lower: f64,
+    upper: f64,
+    goal: f64,
+    seed: u64,
+) -> *mut TsoOptimizer {
+    let boundaries = CubicBoundary::new(dimensions, lower, upper);
+    Box::into_raw(TsoOptimizer::new(turtles, boundaries, goal, Some(seed)))
+}
+
+/// Registers the objective function. `user_data` is handed back to every call.
+///
+/// # Safety
+/// `optimizer` must be null or a live pointer returned by one of the constructors.
+#[no_mangle]
+pub unsafe extern "C" fn tso_optimizer_set_objective(
+    optimizer: *mut TsoOptimizer,
+    objective: TsoObjective,
+    user_data: *mut c_void,
+) {
+    if let Some(optimizer) = optimizer.as_mut() {
+        optimizer.optimizer.objective_function = match objective {
+            Some(function) => {
+                let callback = Callback {
+                    function,
+                    user_data,
+                };
+                ObjectiveFunction::new(move |position: &Array1<f64>| callback.call(position))
+            }
+            None => ObjectiveFunction::new(external_objective),
+        };
+    }
+}
+
+/// Limits `tso_optimizer_optimize` to `max_iterations` iterations in total. Zero lifts the limit.
+///
+/// # Safety
+/// `optimizer` must be null or a live pointer returned by one of the constructors.
+#[no_mangle]
+pub unsafe extern "C" fn tso_optimizer_set_max_iterations(
+    optimizer: *mut TsoOptimizer,
+    max_iterations: usize,
+) {
+    if let Some(optimizer) = optimizer.as_mut() {
+        optimizer.optimizer.max_iterations = Some(max_iterations).filter(|limit| *limit > 0);
+    }
+}
+
+/// Limits `tso_optimizer_optimize` to `max_evaluations` objective evaluations in total. Zero lifts
+/// the limit.
+///
+/// # Safety
+/// `optimizer` must be null or a live pointer returned by one of the constructors.
+#[no_mangle]
+pub unsafe extern "C" fn tso_optimizer_set_max_evaluations(
+    optimizer: *mut TsoOptimizer,
+    max_evaluations: usize,
+) {
+    if let Some(optimizer) = optimizer.as_mut() {
+        optimizer.optimizer.max_evaluations = Some(max_evaluations).filter(|limit| *limit > 0);
+    }
+}
+
+/// Performs a single iteration of the algorithm. Returns whether the goal has been reached.
+///
+/// # Safety
+/// `optimizer` must be null or a live pointer returned by one of the constructors.
+#[no_mangle]
+pub unsafe extern "C" fn tso_optimizer_step(optimizer: *mut TsoOptimizer) -> bool {
+    match optimizer.as_mut() {
+        Some(optimizer) => {
+            optimizer.optimizer.iterate();
+            optimizer.optimizer.best_score <= optimizer.optimizer.goal
+        }
+        None => false,
+    }
+}
+
+/// Iterates until the goal is reached, or until a limit set with `tso_optimizer_set_max_iterations`
+/// or `tso_optimizer_set_max_evaluations` is spent.
+///
+/// # Safety
+/// `optimizer` must be null or a live pointer returned by one of the constructors.
+#[no_mangle]
+pub unsafe extern "C" fn tso_optimizer_optimize(optimizer: *mut TsoOptimizer) {
+    if let Some(optimizer) = optimizer.as_mut() {
+        optimizer.optimizer.optimize();
+    }
+}
+
+/// The best score observed so far, or NaN for a null optimizer.
+///
+/// # Safety
+/// `optimizer` must be null or a live pointer returned by one of the constructors.
+#[no_mangle]
+pub unsafe extern "C" fn tso_optimizer_best_score(optimizer: *const TsoOptimizer) -> f64 {
+    optimizer
+        .as_ref()
+        .map_or(f64::NAN, |optimizer| optimizer.optimizer.best_score)
+}
+
+/// Copies up to `capacity` coordinates of the best position into `out` and returns the number of
+/// dimensions, so callers may pass a null `out` to size their buffer first.
+///
+/// # Safety
+/// `optimizer` must be null or a live pointer returned by one of the constructors, and `out` must
+/// be null or valid for `capacity` writes.
+#[no_mangle]
+pub unsafe extern "C" fn tso_optimizer_best_position(
+    optimizer: *const TsoOptimizer,
+    out: *mut f64,
+    capacity: usize,
+) -> usize {
+    let Some(optimizer) = optimizer.as_ref() else {
+        return 0;
+    };
+    let best_position = &optimizer.optimizer.best_position;
+    if !out.is_null() {
+        let out = slice::from_raw_parts_mut(out, capacity.min(best_position.len()));
+        for (destination, value) in out.iter_mut().zip(best_position.iter()) {
+            *destination = *value;
+        }
+    }
+    best_position.len()
+}
+
+/// The number of iterations performed so far.
+///
+/// # Safety
+/// `optimizer` must be null or a live pointer returned by one of the constructors.
+#[no_mangle]
+pub unsafe extern "C" fn tso_optimizer_iterations(optimizer: *const TsoOptimizer) -> usize {
+    optimizer
+        .as_ref()
+        .map_or(0, |optimizer| optimizer.optimizer.iterations)
+}
+
+/// The number of objective evaluations performed so far.
+///
+/// # Safety
+/// `optimizer` must be null or a live pointer returned by one of the constructors.
+#[no_mangle]
+pub unsafe extern "C" fn tso_optimizer_evaluations(optimizer: *const TsoOptimizer) -> usize {
+    optimizer
+        .as_ref()
+        .map_or(0, |optimizer| optimizer.optimizer.evaluations)
+}
+
+/// Frees an optimizer. Passing null is a no-op.
+///
+/// # Safety
+/// `optimizer` must be null or a pointer returned by one of the constructors that has not been
+/// destroyed yet.
+#[no_mangle]
+pub unsafe extern "C" fn tso_optimizer_destroy(optimizer: *mut TsoOptimizer) {
+    if !optimizer.is_null() {
+        drop(Box::from_raw(optimizer));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    extern "C" fn parabola(position: *const f64, dimensions: usize, user_data: *mut c_void) -> f64 {
+        let calls = unsafe { &mut *(user_data as *mut usize) };
+        *calls += 1;
+        let position = unsafe { slice::from_raw_parts(position, dimensions) };
+        position.iter().map(|x| x * x).sum()
+    }
+
+    #[test]
+    fn c_callers_can_drive_the_swarm() {
+        let mut calls = 0usize;
+        unsafe {
+            let optimizer = tso_optimizer_new_seeded(6, 3, -1., 1., -1., 3);
+            tso_optimizer_set_objective(
+                optimizer,
+                Some(parabola),
+                &mut calls as *mut usize as *mut c_void,
+            );
+            assert!(!tso_optimizer_step(optimizer));
+            assert!(!tso_optimizer_step(optimizer));
+            assert_eq!(tso_optimizer_iterations(optimizer), 2);
+
+            let mut best = [0.; 3];
+            assert_eq!(
+                tso_optimizer_best_position(optimizer, ptr::null_mut(), 0),
+                3
+            );
+            tso_optimizer_best_position(optimizer, best.as_mut_ptr(), best.len());
+            let score: f64 = best.iter().map(|x| x * x).sum();
+            assert_eq!(score, tso_optimizer_best_score(optimizer));
+            tso_optimizer_destroy(optimizer);
+        }
+        assert_eq!(calls, 12);
+    }
+
+    #[test]
+    fn c_callers_can_limit_the_run() {
+        let mut calls = 0usize;
+        unsafe {
+            // A goal below zero is never reached, so only the limits end these runs.
+            let optimizer = tso_optimizer_new_seeded(6, 3, -1., 1., -1., 3);
+            tso_optimizer_set_objective(
+                optimizer,
+                Some(parabola),
+                &mut calls as *mut usize as *mut c_void,
+            );
+            tso_optimizer_set_max_iterations(optimizer, 4);
+            tso_optimizer_optimize(optimizer);
+            assert_eq!(tso_optimizer_iterations(optimizer), 4);
+
+            tso_optimizer_set_max_iterations(optimizer, 0);
+            tso_optimizer_set_max_evaluations(optimizer, 36);
+            tso_optimizer_optimize(optimizer);
+            assert_eq!(tso_optimizer_evaluations(optimizer), 36);
+            assert_eq!(tso_optimizer_iterations(optimizer), 6);
+            tso_optimizer_destroy(optimizer);
+        }
+        assert_eq!(calls, 36);
+    }
+}
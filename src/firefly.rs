@@ -0,0 +1,220 @@
+//! The firefly algorithm, a swarm of lights over the same objectives and boundaries as the
+//! turtles.
+//!
+//! A firefly's brightness is the score of where it sits. Every iteration each firefly flies
+//! towards every brighter one, drawn with an attractiveness `attractiveness * exp(-absorption *
+//! r²)` that fades with the distance `r` measured in widths of the boundary, and jitters by up to
+//! `randomness` times the width, which decays by `damping` every iteration. Fireflies are
+//! `Turtle`s, scored and reported like the swarm's, and runs finish with the same
+//! `OptimizationResult` as an `Optimizer`.
+
+use ndarray::Array1;
+use ndarray_rand::rand::{thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::boundary::Boundary;
+use crate::objective::ObjectiveFunction;
+use crate::{CubicBoundary, Direction, OptimizationResult, Termination, Turtle};
+
+pub struct FireflyOptimizer<'a, B = CubicBoundary> {
+    pub fireflies: Vec<Turtle>,
+    /// The score of every firefly's current position, empty until the swarm has been scored.
+    pub brightness: Vec<f64>,
+    pub boundaries: B,
+    pub iterations: usize,
+    /// How many times the objective has been evaluated.
+    pub evaluations: usize,
+    pub best_score: f64,
+    pub best_position: Array1<f64>,
+    pub objective_function: ObjectiveFunction<'a>,
+    pub goal: f64,
+    /// The most iterations `optimize` may spend before giving up on the goal.
+    pub max_iterations: Option<usize>,
+    pub direction: Direction,
+    /// The attraction between fireflies at no distance.
+    pub attractiveness: f64,
+    /// How fast attraction fades with the squared distance.
+    pub absorption: f64,
+    /// The largest random jitter, as a fraction of the boundary's width.
+    pub randomness: f64,
+    /// The factor `randomness` is multiplied by every iteration.
+    pub damping: f64,
+    rng: ChaCha12Rng,
+}
+
+impl<'a, B: Boundary> FireflyOptimizer<'a, B> {
+    pub fn new(
+        fireflies: usize,
+        boundaries: B,
+        objective_function: impl Into<ObjectiveFunction<'a>>,
+        goal: f64,
+    ) -> Self {
+        let rng = ChaCha12Rng::from_rng(thread_rng()).expect("the rng provides a seed");
+        Self::with_rng(fireflies, boundaries, objective_function.into(), goal, rng)
+    }
+
+    /// Like `new`, but the fireflies are placed reproducibly from `seed`.
+    pub fn new_seeded(
+        fireflies: usize,
+        boundaries: B,
+        objective_function: impl Into<ObjectiveFunction<'a>>,
+        goal: f64,
+        seed: u64,
+    ) -> Self {
+        let rng = ChaCha12Rng::seed_from_u64(seed);
+        Self::with_rng(fireflies, boundaries, objective_function.into(), goal, rng)
+    }
+
+    fn with_rng(
+        fireflies: usize,
+        boundaries: B,
+        objective_function: ObjectiveFunction<'a>,
+        goal: f64,
+        mut rng: ChaCha12Rng,
+    ) -> Self {
+        let shape = boundaries.dimensions();
+        Self {
+            fireflies: (0..fireflies)
+                .map(|_| Turtle::new_using(&boundaries, &mut rng))
+                .collect(),
+            brightness: Vec::new(),
+            boundaries,
+            iterations: 0,
+            evaluations: 0,
+            best_score: f64::INFINITY,
+            best_position: Array1::zeros(shape),
+            objective_function,
+            goal,
+            max_iterations: None,
+            direction: Direction::Minimize,
+            attractiveness: 1.,
+            absorption: 1.,
+            randomness: 0.05,
+            damping: 0.97,
+            rng,
+        }
+    }
+
+    /// Flies until the best score reaches the goal or `max_iterations` is spent.
+    pub fn optimize(&mut self) -> OptimizationResult {
+        loop {
+            if let Some(termination) = self.termination() {
+                return self.result(termination);
+            }
+            self.iterate();
+        }
+    }
+
+    /// Moves the swarm once. Returns why `optimize` would stop here, if it would.
+    pub fn step(&mut self) -> Option<Termination> {
+        self.iterate();
+        self.termination()
+    }
+
+    fn termination(&self) -> Option<Termination> {
+        if self.brightness.is_empty() {
+            return None;
+        }
+        if self.direction.reaches(self.best_score, self.goal) {
+            return Some(Termination::GoalReached);
+        }
+        self.max_iterations
+            .filter(|limit| self.iterations >= *limit)
+            .map(|_| Termination::IterationLimit)
+    }
+
+    fn result(&self, termination: Termination) -> OptimizationResult {
+        OptimizationResult {
+            best_position: self.best_position.clone(),
+            best_score: self.best_score,
+            iterations: self.iterations,
+            evaluations: self.evaluations,
+            turtle_bests: self
+                .fireflies
+                .iter()
+                .map(|firefly| (firefly.best_score, firefly.best_position.clone()))
+                .collect(),
+            termination,
+        }
+    }
+
+    // Scores a firefly where it sits and updates the bests.
+    fn evaluate(&mut self, firefly: usize) -> f64 {
+        let turtle = &mut self.fireflies[firefly];
+        let score = (self.objective_function)(&turtle.position);
+        self.evaluations += 1;
+        turtle.stats.evaluations += 1;
+        if self.direction.is_better(score, turtle.best_score) {
+            turtle.best_score = score;
+            turtle.best_position = turtle.position.clone();
+            if self.direction.is_better(score, self.best_score) {
+                turtle.stats.improvements += 1;
+                self.best_score = score;
+                self.best_position = turtle.position.clone();
+            }
+        }
+        score
+    }
+
+    pub(crate) fn iterate(&mut self) {
+        if self.brightness.is_empty() {
+            self.best_score = self.direction.worst();
+            for firefly in 0..self.fireflies.len() {
+                self.fireflies[firefly].best_score = self.direction.worst();
+                let score = self.evaluate(firefly);
+                self.brightness.push(score);
+            }
+        }
+        let (lower, upper) = self.boundaries.bounding_box();
+        let width = (&upper - &lower).mapv(|width| if width > 0. { width } else { 1. });
+        for i in 0..self.fireflies.len() {
+            for j in 0..self.fireflies.len() {
+                if i == j
+                    || !self
+                        .direction
+                        .is_better(self.brightness[j], self.brightness[i])
+                {
+                    continue;
+                }
+                let separation = &self.fireflies[j].position - &self.fireflies[i].position;
+                let distance = (&separation / &width).mapv(|x| x * x).sum();
+                let attraction = self.attractiveness * (-self.absorption * distance).exp();
+                let jitter: Array1<f64> = width
+                    .iter()
+                    .map(|width| self.randomness * width * (self.rng.gen::<f64>() - 0.5))
+                    .collect();
+                let firefly = &mut self.fireflies[i];
+                let previous = firefly.position.clone();
+                firefly.position = &firefly.position + &(attraction * &separation) + &jitter;
+                self.boundaries.project(&mut firefly.position);
+                firefly.velocity = &firefly.position - &previous;
+                firefly.stats.displacement += firefly.velocity.mapv(|x| x * x).sum().sqrt();
+                self.brightness[i] = self.evaluate(i);
+            }
+        }
+        self.randomness *= self.damping;
+        self.iterations += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fireflies_gather_at_the_brightest_spot() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| (x - 0.3) * (x - 0.3)).sum();
+        let boundaries = CubicBoundary::new(2, -2., 2.);
+        let mut swarm = FireflyOptimizer::new_seeded(15, boundaries, &parabola, 1e-6, 4);
+        swarm.max_iterations = Some(200);
+        let result = swarm.optimize();
+
+        assert_eq!(result.termination, Termination::GoalReached);
+        assert_eq!(result.turtle_bests.len(), 15);
+        assert!(swarm.randomness < 0.05);
+        assert!(swarm
+            .fireflies
+            .iter()
+            .all(|firefly| swarm.boundaries.contains(&firefly.position)));
+    }
+}
@@ -0,0 +1,176 @@
+//! A fixed-point turtle swarm, enabled with the `fixed` feature.
+//!
+//! Positions, velocities and scores are `I32F32` numbers, so a swarm runs on microcontrollers
+//! without a floating point unit. Like the core optimizer it works without `std`. Arithmetic
+//! saturates instead of overflowing, and `I32F32::MAX` stands in for a score not yet observed.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use fixed::types::I32F32;
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+
+// The float turtles crawl at machine epsilon, which fixed-point numbers cannot represent. The
+// smallest step they can represent is the closest a fixed-point turtle gets to that pace.
+const TURTLE_VELOCITY: I32F32 = I32F32::DELTA;
+
+#[derive(Clone, Debug)]
+pub struct FixedTurtle {
+    pub position: Vec<I32F32>,
+    velocity: Vec<I32F32>,
+    pub best_score: I32F32,
+    pub best_position: Vec<I32F32>,
+}
+
+/// Minimizes an objective over the cube `[lower, upper]^dimensions` in fixed-point arithmetic.
+pub struct FixedOptimizer<'a> {
+    pub turtles: Vec<FixedTurtle>,
+    pub dimensions: usize,
+    pub lower: I32F32,
+    pub upper: I32F32,
+    pub iterations: usize,
+    pub best_score: I32F32,
+    pub best_position: Vec<I32F32>,
+    pub objective_function: &'a dyn Fn(&[I32F32]) -> I32F32,
+    pub goal: I32F32,
+}
+
+impl<'a> FixedOptimizer<'a> {
+    /// Places the turtles reproducibly from `seed`.
+    pub fn new_seeded(
+        turtles: usize,
+        dimensions: usize,
+        lower: I32F32,
+        upper: I32F32,
+        objective_function: &'a dyn Fn(&[I32F32]) -> I32F32,
+        goal: I32F32,
+        seed: u64,
+    ) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        Self::new_using(
+            turtles,
+            dimensions,
+            lower,
+            upper,
+            objective_function,
+            goal,
+            &mut rng,
+        )
+    }
+
+    /// Places the turtles with draws from `rng`.
+    pub fn new_using<R: RngCore + ?Sized>(
+        turtles: usize,
+        dimensions: usize,
+        lower: I32F32,
+        upper: I32F32,
+        objective_function: &'a dyn Fn(&[I32F32]) -> I32F32,
+        goal: I32F32,
+        rng: &mut R,
+    ) -> Self {
+        // Reversed bounds are swapped, so the range the turtles are drawn from is never empty.
+        let (lower, upper) = if lower > upper {
+            (upper, lower)
+        } else {
+            (lower, upper)
+        };
+        let mut place = || -> Vec<I32F32> {
+            (0..dimensions)
+                .map(|_| I32F32::from_bits(rng.gen_range(lower.to_bits()..=upper.to_bits())))
+                .collect()
+        };
+        Self {
+            turtles: (0..turtles)
+                .map(|_| FixedTurtle {
+                    position: place(),
+                    velocity: vec![TURTLE_VELOCITY; dimensions],
+                    best_score: I32F32::MAX,
+                    best_position: vec![I32F32::ZERO; dimensions],
+                })
+                .collect(),
+            dimensions,
+            lower,
+            upper,
+            iterations: 0,
+            best_score: I32F32::MAX,
+            best_position: vec![I32F32::ZERO; dimensions],
+            objective_function,
+            goal,
+        }
+    }
+
+    fn evaluate(&mut self) {
+        for turtle in self.turtles.iter_mut() {
+            let score = (self.objective_function)(&turtle.position);
+            if score < turtle.best_score {
+                turtle.best_score = score;
+                turtle.best_position = turtle.position.clone();
+                if score < self.best_score {
+                    self.best_score = score;
+                    self.best_position = turtle.position.clone();
+                }
+            }
+        }
+    }
+
+    fn update(&mut self) {
+        for turtle in self.turtles.iter_mut() {
+            for dimension in 0..self.dimensions {
+                let position = turtle.position[dimension];
+                let personal = turtle.best_position[dimension].saturating_sub(position);
+                let social = self.best_position[dimension].saturating_sub(position);
+                let velocity = turtle.velocity[dimension]
+                    .saturating_add(TURTLE_VELOCITY.saturating_mul(personal))
+                    .saturating_add(TURTLE_VELOCITY.saturating_mul(social));
+                turtle.velocity[dimension] = velocity;
+                turtle.position[dimension] = position
+                    .saturating_add(velocity)
+                    .clamp(self.lower, self.upper);
+            }
+        }
+    }
+
+    /// Iterates until the best observed score matches the goal.
+    pub fn optimize(&mut self) {
+        while self.best_score > self.goal {
+            self.iterate();
+        }
+    }
+
+    pub(crate) fn iterate(&mut self) {
+        self.evaluate();
+        self.update();
+        self.iterations += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_point_swarms_find_small_scores() {
+        let parabola = |x: &[I32F32]| {
+            x.iter().fold(I32F32::ZERO, |total, x| {
+                total.saturating_add(x.saturating_mul(*x))
+            })
+        };
+        let mut optimizer = FixedOptimizer::new_seeded(
+            64,
+            2,
+            I32F32::from_num(-1),
+            I32F32::from_num(1),
+            &parabola,
+            I32F32::from_num(0.05),
+            8,
+        );
+        optimizer.optimize();
+
+        assert!(optimizer.best_score <= I32F32::from_num(0.05));
+        assert_eq!(parabola(&optimizer.best_position), optimizer.best_score);
+        assert!(optimizer
+            .turtles
+            .iter()
+            .flat_map(|turtle| turtle.position.iter())
+            .all(|x| x.abs() <= I32F32::from_num(1)));
+    }
+}
@@ -0,0 +1,243 @@
+//! A turtle swarm over any floating point type, enabled with the `float` feature.
+//!
+//! Positions, velocities and scores are a `num_traits::Float`, `f64` unless told otherwise, so a
+//! swarm can run in single precision for embedded targets or to match `f32` models. Like the
+//! core optimizer it works without `std`, with the float maths from `libm`.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ndarray::{Array1, Zip};
+use num_traits::Float;
+#[cfg(feature = "std")]
+use rand::thread_rng;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::objective::ObjectiveFunction;
+use crate::{OptimizationResult, OptimizerParams, Termination};
+
+#[derive(Clone, Debug)]
+pub struct FloatTurtle<T = f64> {
+    pub position: Array1<T>,
+    velocity: Array1<T>,
+    pub best_score: T,
+    pub best_position: Array1<T>,
+}
+
+/// The cube `[lower, upper]^dimensions`, like `CubicBoundary`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FloatBoundary<T = f64> {
+    pub dimensions: usize,
+    pub lower: T,
+    pub upper: T,
+}
+
+impl<T: Float> FloatBoundary<T> {
+    pub fn new(dimensions: usize, lower: T, upper: T) -> Self {
+        // Reversed bounds describe the same cube.
+        let (lower, upper) = if lower > upper {
+            (upper, lower)
+        } else {
+            (lower, upper)
+        };
+        Self {
+            dimensions,
+            lower,
+            upper,
+        }
+    }
+
+    fn sample<R: RngCore + ?Sized>(&self, rng: &mut R) -> Array1<T> {
+        Array1::from_shape_fn(self.dimensions, |_| {
+            let unit = T::from(rng.gen::<f64>()).unwrap_or_else(T::zero);
+            self.lower + (self.upper - self.lower) * unit
+        })
+    }
+
+    fn project(&self, position: &mut Array1<T>) {
+        position.mapv_inplace(|x| x.max(self.lower).min(self.upper));
+    }
+}
+
+/// Minimizes an objective over a `FloatBoundary` in the precision of `T`.
+pub struct FloatOptimizer<'a, T = f64> {
+    pub turtles: Vec<FloatTurtle<T>>,
+    pub boundaries: FloatBoundary<T>,
+    pub iterations: usize,
+    /// How many times the objective has been evaluated.
+    pub evaluations: usize,
+    pub best_score: T,
+    pub best_position: Array1<T>,
+    pub objective_function: ObjectiveFunction<'a, T>,
+    pub goal: T,
+    /// The most iterations `optimize` may spend before giving up on the goal.
+    pub max_iterations: Option<usize>,
+    /// The turtle's own coefficients by default, in the precision of `T`.
+    pub params: OptimizerParams<T>,
+}
+
+impl<'a, T: Float> FloatOptimizer<'a, T> {
+    #[cfg(feature = "std")]
+    pub fn new(
+        turtles: usize,
+        boundaries: FloatBoundary<T>,
+        objective_function: impl Into<ObjectiveFunction<'a, T>>,
+        goal: T,
+    ) -> Self {
+        let rng = ChaCha12Rng::from_rng(thread_rng()).expect("the rng provides a seed");
+        Self::with_rng(turtles, boundaries, objective_function.into(), goal, rng)
+    }
+
+    /// Like `new`, but the turtles are placed reproducibly from `seed`.
+    pub fn new_seeded(
+        turtles: usize,
+        boundaries: FloatBoundary<T>,
+        objective_function: impl Into<ObjectiveFunction<'a, T>>,
+        goal: T,
+        seed: u64,
+    ) -> Self {
+        let rng = ChaCha12Rng::seed_from_u64(seed);
+        Self::with_rng(turtles, boundaries, objective_function.into(), goal, rng)
+    }
+
+    fn with_rng(
+        turtles: usize,
+        boundaries: FloatBoundary<T>,
+        objective_function: ObjectiveFunction<'a, T>,
+        goal: T,
+        mut rng: ChaCha12Rng,
+    ) -> Self {
+        let dimensions = boundaries.dimensions;
+        // The turtles crawl at the pace of the type's machine epsilon.
+        let pace = T::epsilon();
+        Self {
+            turtles: (0..turtles)
+                .map(|_| FloatTurtle {
+                    position: boundaries.sample(&mut rng),
+                    velocity: Array1::from_elem(dimensions, pace),
+                    best_score: T::infinity(),
+                    best_position: Array1::from_elem(dimensions, T::zero()),
+                })
+                .collect(),
+            boundaries,
+            iterations: 0,
+            evaluations: 0,
+            best_score: T::infinity(),
+            best_position: Array1::from_elem(dimensions, T::zero()),
+            objective_function,
+            goal,
+            max_iterations: None,
+            params: OptimizerParams {
+                inertia: T::one(),
+                cognitive: pace,
+                social: pace,
+            },
+        }
+    }
+
+    /// Iterates until the best score reaches the goal or `max_iterations` is spent.
+    pub fn optimize(&mut self) -> OptimizationResult<T> {
+        loop {
+            if let Some(termination) = self.termination() {
+                return self.result(termination);
+            }
+            self.iterate();
+        }
+    }
+
+    /// Moves the turtles once. Returns why `optimize` would stop here, if it would.
+    pub fn step(&mut self) -> Option<Termination> {
+        self.iterate();
+        self.termination()
+    }
+
+    fn termination(&self) -> Option<Termination> {
+        if self.best_score <= self.goal {
+            return Some(Termination::GoalReached);
+        }
+        self.max_iterations
+            .filter(|limit| self.iterations >= *limit)
+            .map(|_| Termination::IterationLimit)
+    }
+
+    fn result(&self, termination: Termination) -> OptimizationResult<T> {
+        OptimizationResult {
+            best_position: self.best_position.clone(),
+            best_score: self.best_score,
+            iterations: self.iterations,
+            evaluations: self.evaluations,
+            turtle_bests: self
+                .turtles
+                .iter()
+                .map(|turtle| (turtle.best_score, turtle.best_position.clone()))
+                .collect(),
+            termination,
+        }
+    }
+
+    fn evaluate(&mut self) {
+        for turtle in self.turtles.iter_mut() {
+            let score = (self.objective_function)(&turtle.position);
+            self.evaluations += 1;
+            if score < turtle.best_score {
+                turtle.best_score = score;
+                turtle.best_position.clone_from(&turtle.position);
+                if score < self.best_score {
+                    self.best_score = score;
+                    self.best_position.clone_from(&turtle.position);
+                }
+            }
+        }
+    }
+
+    fn update(&mut self) {
+        let params = self.params;
+        for turtle in self.turtles.iter_mut() {
+            Zip::from(&mut turtle.velocity)
+                .and(&turtle.position)
+                .and(&turtle.best_position)
+                .and(&self.best_position)
+                .for_each(|velocity, &position, &personal, &best| {
+                    *velocity = params.velocity(*velocity, position, personal, best);
+                });
+            Zip::from(&mut turtle.position)
+                .and(&turtle.velocity)
+                .for_each(|position, &velocity| *position = *position + velocity);
+            self.boundaries.project(&mut turtle.position);
+        }
+    }
+
+    pub(crate) fn iterate(&mut self) {
+        self.evaluate();
+        self.update();
+        self.iterations += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_precision_swarms_find_small_scores() {
+        let parabola = |x: &Array1<f32>| x.iter().map(|x| x * x).sum::<f32>();
+        let boundaries = FloatBoundary::new(2, 1f32, -1.);
+        let mut optimizer = FloatOptimizer::new_seeded(20, boundaries, &parabola, 1e-4, 8);
+        optimizer.params = OptimizerParams {
+            inertia: 0.6,
+            cognitive: 0.7,
+            social: 0.7,
+        };
+        optimizer.max_iterations = Some(1000);
+        let result = optimizer.optimize();
+
+        assert_eq!(result.termination, Termination::GoalReached);
+        assert!(result.best_score <= 1e-4);
+        assert_eq!(parabola(&optimizer.best_position), optimizer.best_score);
+        assert!(optimizer
+            .turtles
+            .iter()
+            .flat_map(|turtle| turtle.position.iter())
+            .all(|x| x.abs() <= 1.));
+    }
+}
@@ -0,0 +1,234 @@
+//! The grey wolf optimizer, a pack hunting over the same objectives and boundaries as the turtles.
+//!
+//! The three wolves at the best current positions, alpha, beta and delta, lead the hunt. Every
+//! other wolf moves to the average of three steps, one towards each leader, whose length shrinks
+//! as the coefficient `a` falls from 2 to 0 over the hunt: early on the pack encircles widely and
+//! explores, late it closes in on its prey. Wolves are `Turtle`s, scored and reported like the
+//! swarm's, and runs finish with the same `OptimizationResult` as an `Optimizer`.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ndarray::Array1;
+#[cfg(feature = "std")]
+use rand::thread_rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::boundary::Boundary;
+use crate::objective::ObjectiveFunction;
+use crate::{CubicBoundary, Direction, OptimizationResult, Termination, Turtle};
+
+pub struct GreyWolfOptimizer<'a, B = CubicBoundary> {
+    pub wolves: Vec<Turtle>,
+    /// The score of every wolf's current position, empty until the pack has been scored.
+    pub scores: Vec<f64>,
+    pub boundaries: B,
+    pub iterations: usize,
+    /// How many times the objective has been evaluated.
+    pub evaluations: usize,
+    pub best_score: f64,
+    pub best_position: Array1<f64>,
+    pub objective_function: ObjectiveFunction<'a>,
+    pub goal: f64,
+    /// The most iterations `optimize` may spend before giving up on the goal.
+    pub max_iterations: Option<usize>,
+    pub direction: Direction,
+    /// The iterations over which `a` falls to 0, `max_iterations` when it is set.
+    pub hunt: usize,
+    rng: ChaCha12Rng,
+}
+
+impl<'a, B: Boundary> GreyWolfOptimizer<'a, B> {
+    #[cfg(feature = "std")]
+    pub fn new(
+        wolves: usize,
+        boundaries: B,
+        objective_function: impl Into<ObjectiveFunction<'a>>,
+        goal: f64,
+    ) -> Self {
+        let rng = ChaCha12Rng::from_rng(thread_rng()).expect("the rng provides a seed");
+        Self::with_rng(wolves, boundaries, objective_function.into(), goal, rng)
+    }
+
+    /// Like `new`, but the pack is placed reproducibly from `seed`.
+    pub fn new_seeded(
+        wolves: usize,
+        boundaries: B,
+        objective_function: impl Into<ObjectiveFunction<'a>>,
+        goal: f64,
+        seed: u64,
+    ) -> Self {
+        let rng = ChaCha12Rng::seed_from_u64(seed);
+        Self::with_rng(wolves, boundaries, objective_function.into(), goal, rng)
+    }
+
+    fn with_rng(
+        wolves: usize,
+        boundaries: B,
+        objective_function: ObjectiveFunction<'a>,
+        goal: f64,
+        mut rng: ChaCha12Rng,
+    ) -> Self {
+        let shape = boundaries.dimensions();
+        Self {
+            wolves: (0..wolves)
+                .map(|_| Turtle::new_using(&boundaries, &mut rng))
+                .collect(),
+            scores: Vec::new(),
+            boundaries,
+            iterations: 0,
+            evaluations: 0,
+            best_score: f64::INFINITY,
+            best_position: Array1::zeros(shape),
+            objective_function,
+            goal,
+            max_iterations: None,
+            direction: Direction::Minimize,
+            hunt: 500,
+            rng,
+        }
+    }
+
+    /// Hunts until the best score reaches the goal or `max_iterations` is spent.
+    pub fn optimize(&mut self) -> OptimizationResult {
+        loop {
+            if let Some(termination) = self.termination() {
+                return self.result(termination);
+            }
+            self.iterate();
+        }
+    }
+
+    /// Moves the pack once. Returns why `optimize` would stop here, if it would.
+    pub fn step(&mut self) -> Option<Termination> {
+        self.iterate();
+        self.termination()
+    }
+
+    fn termination(&self) -> Option<Termination> {
+        if self.scores.is_empty() {
+            return None;
+        }
+        if self.direction.reaches(self.best_score, self.goal) {
+            return Some(Termination::GoalReached);
+        }
+        self.max_iterations
+            .filter(|limit| self.iterations >= *limit)
+            .map(|_| Termination::IterationLimit)
+    }
+
+    fn result(&self, termination: Termination) -> OptimizationResult {
+        OptimizationResult {
+            best_position: self.best_position.clone(),
+            best_score: self.best_score,
+            iterations: self.iterations,
+            evaluations: self.evaluations,
+            turtle_bests: self
+                .wolves
+                .iter()
+                .map(|wolf| (wolf.best_score, wolf.best_position.clone()))
+                .collect(),
+            termination,
+        }
+    }
+
+    // Scores every wolf where it stands and updates the bests.
+    fn evaluate(&mut self) {
+        let first = self.scores.is_empty();
+        if first {
+            self.best_score = self.direction.worst();
+        }
+        self.scores.clear();
+        for wolf in self.wolves.iter_mut() {
+            if first {
+                wolf.best_score = self.direction.worst();
+            }
+            let score = (self.objective_function)(&wolf.position);
+            self.evaluations += 1;
+            wolf.stats.evaluations += 1;
+            if self.direction.is_better(score, wolf.best_score) {
+                wolf.best_score = score;
+                wolf.best_position = wolf.position.clone();
+                if self.direction.is_better(score, self.best_score) {
+                    wolf.stats.improvements += 1;
+                    self.best_score = score;
+                    self.best_position = wolf.position.clone();
+                }
+            }
+            self.scores.push(score);
+        }
+    }
+
+    // The wolves at the three best current positions, best first.
+    fn leaders(&self) -> Vec<Array1<f64>> {
+        let mut ranked: Vec<usize> = (0..self.wolves.len()).collect();
+        ranked.sort_by(|a, b| {
+            let (a, b) = (self.scores[*a], self.scores[*b]);
+            if self.direction.is_better(a, b) {
+                core::cmp::Ordering::Less
+            } else if self.direction.is_better(b, a) {
+                core::cmp::Ordering::Greater
+            } else {
+                core::cmp::Ordering::Equal
+            }
+        });
+        ranked
+            .iter()
+            .cycle()
+            .take(3)
+            .map(|wolf| self.wolves[*wolf].position.clone())
+            .collect()
+    }
+
+    pub(crate) fn iterate(&mut self) {
+        if self.wolves.is_empty() {
+            self.iterations += 1;
+            return;
+        }
+        if self.scores.is_empty() {
+            self.evaluate();
+        }
+        let hunt = self.max_iterations.unwrap_or(self.hunt).max(1);
+        let a = 2. * (1. - self.iterations as f64 / hunt as f64).max(0.);
+        let leaders = self.leaders();
+        for wolf in self.wolves.iter_mut() {
+            let previous = wolf.position.clone();
+            let mut next = Array1::zeros(previous.len());
+            for leader in leaders.iter() {
+                for dimension in 0..previous.len() {
+                    let big_a = a * (2. * self.rng.gen::<f64>() - 1.);
+                    let c = 2. * self.rng.gen::<f64>();
+                    let distance = c * leader[dimension] - previous[dimension];
+                    let distance = if distance < 0. { -distance } else { distance };
+                    next[dimension] += (leader[dimension] - big_a * distance) / 3.;
+                }
+            }
+            self.boundaries.project(&mut next);
+            let step = (&next - &previous).mapv(|x| x * x).sum();
+            wolf.stats.displacement += crate::sqrt(step);
+            wolf.velocity = &next - &previous;
+            wolf.position = next;
+        }
+        self.evaluate();
+        self.iterations += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_pack_closes_in_on_its_prey() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| (x - 0.3) * (x - 0.3)).sum();
+        let boundaries = CubicBoundary::new(3, -2., 2.);
+        let mut pack = GreyWolfOptimizer::new_seeded(12, boundaries, &parabola, 1e-6, 4);
+        pack.max_iterations = Some(300);
+        let result = pack.optimize();
+
+        assert_eq!(result.termination, Termination::GoalReached);
+        assert_eq!(result.turtle_bests.len(), 12);
+        assert!(pack.wolves.iter().all(|wolf| wolf.stats.evaluations > 1));
+        assert!(result.to_string().contains("12 turtles performed"));
+    }
+}
@@ -0,0 +1,221 @@
+//! Runs on a background thread that can be paused and cancelled without losing their best.
+//!
+//! An `OptimizationHandle` owns a run on its own thread, started with `Optimizer::spawn` on an
+//! optimizer that owns its objective, an `Optimizer<'static>`, which is moved to the worker as it
+//! is. The run can be paused and resumed between
+//! iterations, and asked to `cancel`, which it does at the end of the current iteration with
+//! `Termination::Cancelled`. `join` hands back its `OptimizationResult`. A handle that is dropped
+//! instead, say by a service shutting down, still cancels and waits for the run and passes the
+//! best so far to its `on_drop` callback, so nothing that was paid for is lost.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+
+use crate::boundary::Boundary;
+use crate::watch::{Best, Watch};
+use crate::{OptimizationResult, Optimizer, Termination};
+
+type OnDrop = Box<dyn FnOnce(Best) + Send>;
+
+// Whether the run should wait before its next iteration, with the condition it waits on.
+#[derive(Default)]
+struct Pause {
+    paused: Mutex<bool>,
+    resumed: Condvar,
+}
+
+impl Pause {
+    // The lock only guards a flag, so a poisoned one still holds a whole one.
+    fn lock(&self) -> MutexGuard<'_, bool> {
+        self.paused
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn set(&self, paused: bool) {
+        *self.lock() = paused;
+        self.resumed.notify_all();
+    }
+
+    // Taking the lock first means a run about to wait has either seen the cancellation or is
+    // already waiting for this.
+    fn wake(&self) {
+        let _paused = self.lock();
+        self.resumed.notify_all();
+    }
+
+    fn wait(&self, cancel: &AtomicBool) {
+        let mut paused = self.lock();
+        while *paused && !cancel.load(Ordering::Relaxed) {
+            paused = self
+                .resumed
+                .wait(paused)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+    }
+}
+
+pub struct OptimizationHandle {
+    cancel: Arc<AtomicBool>,
+    pause: Arc<Pause>,
+    watch: Watch,
+    thread: Option<JoinHandle<OptimizationResult>>,
+    on_drop: Option<OnDrop>,
+}
+
+impl OptimizationHandle {
+    /// Starts optimizing on a new thread, which iterates until `optimize` would stop or the run
+    /// is cancelled.
+    pub fn spawn<B: Boundary + Send + 'static>(mut optimizer: Optimizer<'static, B>) -> Self {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let pause = Arc::new(Pause::default());
+        let watch = Watch::new();
+        let thread = {
+            let cancel = cancel.clone();
+            let pause = pause.clone();
+            let watch = watch.clone();
+            thread::spawn(move || {
+                optimizer.watch = Some(watch.clone());
+                watch.publish(&optimizer);
+                loop {
+                    pause.wait(&cancel);
+                    if cancel.load(Ordering::Relaxed) {
+                        return optimizer.result(Termination::Cancelled);
+                    }
+                    if let Some(termination) = optimizer.termination() {
+                        return optimizer.result(termination);
+                    }
+                    optimizer.iterate();
+                }
+            })
+        };
+        Self {
+            cancel,
+            pause,
+            watch,
+            thread: Some(thread),
+            on_drop: None,
+        }
+    }
+
+    /// Asks the run to stop after its current iteration, paused or not. Does not wait.
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+        self.pause.wake();
+    }
+
+    /// Holds the run after its current iteration until it is resumed. Does not wait.
+    pub fn pause(&self) {
+        self.pause.set(true);
+    }
+
+    pub fn resume(&self) {
+        self.pause.set(false);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.pause.lock()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.thread
+            .as_ref()
+            .is_none_or(|thread| thread.is_finished())
+    }
+
+    /// The best found so far.
+    pub fn best(&self) -> Best {
+        self.watch.best()
+    }
+
+    /// Waits for the run to finish and returns its result, or the panic of a run that panicked.
+    /// `best` still has what such a run published before it did.
+    pub fn join(mut self) -> thread::Result<OptimizationResult> {
+        let thread = self.thread.take().expect("only joining takes the thread");
+        thread.join()
+    }
+
+    /// Called with the best so far when the handle is dropped without being joined.
+    pub fn on_drop(&mut self, callback: impl FnOnce(Best) + Send + 'static) {
+        self.on_drop = Some(Box::new(callback));
+    }
+}
+
+impl<B: Boundary + Send + 'static> Optimizer<'static, B> {
+    /// Runs this optimizer on a background thread, see `handle`.
+    pub fn spawn(self) -> OptimizationHandle {
+        OptimizationHandle::spawn(self)
+    }
+}
+
+impl Drop for OptimizationHandle {
+    fn drop(&mut self) {
+        let Some(thread) = self.thread.take() else {
+            return;
+        };
+        self.cancel();
+        // The best is published after every iteration, so a panic loses at most one.
+        let _ = thread.join();
+        let best = self.watch.best();
+        if let Some(callback) = self.on_drop.take() {
+            callback(best);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CubicBoundary;
+    use ndarray::Array1;
+    use std::sync::mpsc;
+
+    fn parabola(x: &Array1<f64>) -> f64 {
+        x.mapv(|x| x * x).sum()
+    }
+
+    fn build() -> Optimizer<'static> {
+        Optimizer::new_seeded(8, CubicBoundary::new(2, -1., 1.), &parabola, -1., 3)
+    }
+
+    // Checked by the compiler: an optimizer owning its objective can move between threads.
+    fn assert_send<T: Send>() {}
+
+    #[test]
+    fn cancelled_and_dropped_runs_yield_their_best() {
+        assert_send::<Optimizer<'static>>();
+        let handle = build().spawn();
+        while handle.best().iterations < 3 {
+            thread::yield_now();
+        }
+        handle.pause();
+        // A paused run holds still once its current iteration is done.
+        thread::sleep(std::time::Duration::from_millis(20));
+        let paused = handle.best().iterations;
+        thread::sleep(std::time::Duration::from_millis(20));
+        assert_eq!(handle.best().iterations, paused);
+        handle.resume();
+        while handle.best().iterations < paused + 3 {
+            thread::yield_now();
+        }
+        handle.pause();
+        handle.cancel();
+        let result = handle.join().unwrap();
+        assert_eq!(result.termination, Termination::Cancelled);
+        assert!(result.iterations >= paused + 3);
+        assert!(result.best_score.is_finite());
+        assert_eq!(result.best_score, parabola(&result.best_position));
+
+        let (sender, receiver) = mpsc::channel();
+        let mut handle = OptimizationHandle::spawn(build());
+        handle.on_drop(move |best| sender.send(best).unwrap());
+        while handle.best().iterations < 3 {
+            thread::yield_now();
+        }
+        drop(handle);
+        let best = receiver.try_recv().unwrap();
+        assert!(best.iterations >= 3);
+        assert!(best.score.is_finite());
+    }
+}
@@ -0,0 +1,117 @@
+//! The best score and position of every iteration, kept for convergence plots.
+//!
+//! With `Optimizer::history` set, the overall best is recorded at the end of every
+//! `interval`-th iteration and stays available after `optimize` returns. Long runs are kept
+//! within `capacity` by thinning: once it is full every other entry is dropped and the interval
+//! doubles, so the history always spans the whole run at an even spacing.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ndarray::Array1;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HistoryEntry {
+    /// The iterations spent when the entry was recorded.
+    pub iteration: usize,
+    pub best_score: f64,
+    pub best_position: Array1<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct History {
+    /// Every how many iterations an entry is recorded.
+    pub interval: usize,
+    /// The most entries kept, unlimited when `None`.
+    pub capacity: Option<usize>,
+    entries: Vec<HistoryEntry>,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl History {
+    /// Records every iteration without a limit.
+    pub fn new() -> Self {
+        Self {
+            interval: 1,
+            capacity: None,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Records every iteration, thinning to stay within `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity: Some(capacity),
+            ..Self::new()
+        }
+    }
+
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// The recorded best scores, oldest first.
+    pub fn scores(&self) -> Vec<f64> {
+        self.entries.iter().map(|entry| entry.best_score).collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub(crate) fn record(&mut self, iteration: usize, best_score: f64, best: &Array1<f64>) {
+        if !iteration.is_multiple_of(self.interval.max(1)) {
+            return;
+        }
+        if let Some(capacity) = self.capacity {
+            if self.entries.len() >= capacity.max(1) {
+                self.interval = self.interval.max(1) * 2;
+                let interval = self.interval;
+                self.entries
+                    .retain(|entry| entry.iteration.is_multiple_of(interval));
+                if !iteration.is_multiple_of(interval) {
+                    return;
+                }
+            }
+        }
+        self.entries.push(HistoryEntry {
+            iteration,
+            best_score,
+            best_position: best.clone(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CubicBoundary, Optimizer};
+
+    #[test]
+    fn histories_thin_out_to_fit() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(5, boundaries, &parabola, -1., 3);
+        optimizer.history = Some(History::with_capacity(4));
+        optimizer.max_iterations = Some(10);
+        optimizer.optimize();
+
+        let history = optimizer.history.as_ref().unwrap();
+        let iterations: Vec<usize> = history
+            .entries()
+            .iter()
+            .map(|entry| entry.iteration)
+            .collect();
+        assert_eq!(iterations, vec![4, 8]);
+        assert_eq!(history.interval, 4);
+        let scores = history.scores();
+        assert!(scores.windows(2).all(|pair| pair[1] <= pair[0]));
+        assert_eq!(*scores.last().unwrap(), optimizer.best_score);
+    }
+}
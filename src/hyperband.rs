@@ -0,0 +1,175 @@
+//! Successive halving and Hyperband budget allocation.
+//!
+//! Expensive objectives make it wasteful to give every candidate swarm the same number of
+//! iterations. These drivers start many swarms on a small budget and only promote the best
+//! performers to larger budgets. Budgets are counted in optimizer iterations.
+
+use crate::{Direction, Optimizer};
+
+/// Runs successive halving over the given swarms. Every surviving swarm is advanced until it has
+/// spent `min_iterations`, then only the best `1 / eta` of them are promoted to a budget `eta`
+/// times larger. This repeats until a single swarm remains, which is returned.
+pub fn successive_halving<'a>(
+    swarms: Vec<Optimizer<'a>>,
+    min_iterations: usize,
+    eta: usize,
+) -> Option<Optimizer<'a>> {
+    // Halving by anything less than two would never finish.
+    let eta = eta.max(2);
+    let rungs = rungs_needed(swarms.len(), eta);
+    run_bracket(swarms, min_iterations.max(1), rungs, eta)
+}
+
+/// Hyperband hedges successive halving over several brackets, trading the number of swarms
+/// against the budget each one starts with.
+#[derive(Clone, Copy, Debug)]
+pub struct Hyperband {
+    pub max_iterations: usize,
+    pub eta: usize,
+}
+
+impl Hyperband {
+    pub fn new(max_iterations: usize, eta: usize) -> Self {
+        // Halving by anything less than two would never finish.
+        Self {
+            max_iterations: max_iterations.max(1),
+            eta: eta.max(2),
+        }
+    }
+
+    /// Runs every bracket, drawing fresh swarms from `sample`, and returns the swarm with the best
+    /// score across all brackets. `sample` is where a new configuration should be chosen.
+    pub fn run<'a, S>(&self, mut sample: S) -> Option<Optimizer<'a>>
+    where
+        S: FnMut() -> Optimizer<'a>,
+    {
+        let s_max = self.brackets() - 1;
+        let mut champion: Option<Optimizer<'a>> = None;
+
+        for s in (0..=s_max).rev() {
+            let swarm_count = ((s_max + 1) as f64 / (s + 1) as f64 * self.eta.pow(s as u32) as f64)
+                .ceil() as usize;
+            let min_iterations = (self.max_iterations / self.eta.pow(s as u32)).max(1);
+            let swarms = (0..swarm_count).map(|_| sample()).collect();
+
+            if let Some(winner) = run_bracket(swarms, min_iterations, s + 1, self.eta) {
+                match &champion {
+                    Some(best)
+                        if !best
+                            .direction()
+                            .is_better(winner.best_score, best.best_score) => {}
+                    _ => champion = Some(winner),
+                }
+            }
+        }
+        champion
+    }
+
+    /// The number of brackets Hyperband will run for this budget.
+    pub fn brackets(&self) -> usize {
+        let mut brackets = 1;
+        let mut budget = self.eta;
+        while budget <= self.max_iterations {
+            brackets += 1;
+            budget *= self.eta;
+        }
+        brackets
+    }
+}
+
+fn rungs_needed(mut swarms: usize, eta: usize) -> usize {
+    let mut rungs = 1;
+    while swarms > 1 {
+        swarms = (swarms / eta).max(1);
+        rungs += 1;
+    }
+    rungs
+}
+
+fn run_bracket<'a>(
+    mut swarms: Vec<Optimizer<'a>>,
+    min_iterations: usize,
+    rungs: usize,
+    eta: usize,
+) -> Option<Optimizer<'a>> {
+    let mut budget = min_iterations;
+    for rung in 0..rungs {
+        for swarm in swarms.iter_mut() {
+            swarm.advance_to(budget);
+        }
+        // Best first, whichever way the swarms score.
+        swarms.sort_by(|a, b| match a.direction() {
+            Direction::Minimize => a.best_score.total_cmp(&b.best_score),
+            Direction::Maximize => b.best_score.total_cmp(&a.best_score),
+        });
+        if rung + 1 < rungs {
+            swarms.truncate((swarms.len() / eta).max(1));
+            budget *= eta;
+        }
+    }
+    swarms.into_iter().next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CubicBoundary;
+    use ndarray::Array1;
+
+    fn parabola(x: &Array1<f64>) -> f64 {
+        (x * x).sum()
+    }
+
+    #[test]
+    fn successive_halving_keeps_the_best_swarm() {
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let swarms = (0..9)
+            .map(|_| Optimizer::new(4, boundaries, &parabola, -1.))
+            .collect();
+
+        let winner = successive_halving(swarms, 2, 3).unwrap();
+        assert_eq!(winner.iterations, 18);
+        assert!(winner.best_score < f64::INFINITY);
+    }
+
+    #[test]
+    fn maximizing_swarms_are_ranked_highest_first() {
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let swarm = |seed| {
+            let mut optimizer = Optimizer::new_seeded(4, boundaries, &parabola, 3., seed);
+            optimizer.set_direction(Direction::Maximize);
+            optimizer
+        };
+        let early = |seed| {
+            let mut optimizer = swarm(seed);
+            optimizer.advance_to(2);
+            optimizer.best_score
+        };
+        let leader = (0..9)
+            .max_by(|a, b| early(*a).total_cmp(&early(*b)))
+            .unwrap();
+
+        // With an eta of nine the first rung keeps only the leader, which then runs to 18.
+        let winner = successive_halving((0..9).map(swarm).collect(), 2, 9).unwrap();
+        let mut expected = swarm(leader);
+        expected.advance_to(18);
+        assert_eq!(winner.best_score, expected.best_score);
+    }
+
+    #[test]
+    fn hyperband_runs_every_bracket() {
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let hyperband = Hyperband::new(9, 3);
+        assert_eq!(hyperband.brackets(), 3);
+
+        let mut sampled = 0;
+        let winner = hyperband
+            .run(|| {
+                sampled += 1;
+                Optimizer::new(4, boundaries, &parabola, -1.)
+            })
+            .unwrap();
+        assert_eq!(sampled, 9 + 5 + 3);
+        assert!(winner.iterations <= 9);
+    }
+}
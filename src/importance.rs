@@ -0,0 +1,177 @@
+//! Which parameters matter, estimated from the evaluations of a run.
+//!
+//! A lightweight variance decomposition: for every dimension the evaluated positions are sorted
+//! along it and split into bins holding equally many evaluations. How much the mean score
+//! differs between the bins, relative to the total variance of the scores, is the share of the
+//! variance that dimension explains on its own. Dimensions with a share near zero are candidates
+//! for pruning in a follow-up run.
+
+use core::cell::RefCell;
+
+use ndarray::Array1;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// How much of the score's variance one dimension explains.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ParameterImportance {
+    pub dimension: usize,
+    /// The first order share of the variance, between 0 and 1.
+    pub importance: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Importance {
+    /// The variance of the finite scores that went into the estimate.
+    pub variance: f64,
+    pub evaluations: usize,
+    pub dimensions: Vec<ParameterImportance>,
+}
+
+impl Importance {
+    /// The dimensions ordered from the most to the least important.
+    pub fn ranking(&self) -> Vec<usize> {
+        let mut ranking: Vec<&ParameterImportance> = self.dimensions.iter().collect();
+        ranking.sort_by(|a, b| b.importance.total_cmp(&a.importance));
+        ranking
+            .iter()
+            .map(|dimension| dimension.dimension)
+            .collect()
+    }
+
+    /// The dimensions whose importance falls below `threshold`.
+    pub fn negligible(&self, threshold: f64) -> Vec<usize> {
+        self.dimensions
+            .iter()
+            .filter(|dimension| dimension.importance < threshold)
+            .map(|dimension| dimension.dimension)
+            .collect()
+    }
+}
+
+/// Estimates the importance of every dimension from evaluated `positions` and their `scores`,
+/// splitting each dimension into `bins` bins. Evaluations with scores that are not finite are
+/// left out.
+pub fn importance(positions: &[Array1<f64>], scores: &[f64], bins: usize) -> Importance {
+    assert_eq!(
+        positions.len(),
+        scores.len(),
+        "importance expects one score per position"
+    );
+    let evaluations: Vec<(&Array1<f64>, f64)> = positions
+        .iter()
+        .zip(scores.iter().copied())
+        .filter(|(_, score)| score.is_finite())
+        .collect();
+    let n = evaluations.len();
+    let dimensions = evaluations
+        .first()
+        .map_or(0, |(position, _)| position.len());
+    let mean = evaluations.iter().map(|(_, score)| score).sum::<f64>() / n.max(1) as f64;
+    let variance = evaluations
+        .iter()
+        .map(|(_, score)| (score - mean) * (score - mean))
+        .sum::<f64>()
+        / n.max(1) as f64;
+    let bins = bins.clamp(1, n.max(1));
+
+    let dimensions = (0..dimensions)
+        .map(|dimension| {
+            let mut along: Vec<(f64, f64)> = evaluations
+                .iter()
+                .map(|(position, score)| (position[dimension], *score))
+                .collect();
+            along.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let mut explained = 0.;
+            for bin in 0..bins {
+                let scores = &along[bin * n / bins..(bin + 1) * n / bins];
+                if scores.is_empty() {
+                    continue;
+                }
+                let bin_mean =
+                    scores.iter().map(|(_, score)| score).sum::<f64>() / scores.len() as f64;
+                explained += scores.len() as f64 * (bin_mean - mean) * (bin_mean - mean);
+            }
+            ParameterImportance {
+                dimension,
+                importance: if variance > 0. {
+                    (explained / n as f64 / variance).min(1.)
+                } else {
+                    0.
+                },
+            }
+        })
+        .collect();
+    Importance {
+        variance,
+        evaluations: n,
+        dimensions,
+    }
+}
+
+/// An objective that keeps every position it scores, building the archive the importance is
+/// estimated from.
+pub struct Recorded<'a> {
+    objective_function: &'a (dyn Fn(&Array1<f64>) -> f64 + Sync),
+    positions: RefCell<Vec<Array1<f64>>>,
+    scores: RefCell<Vec<f64>>,
+}
+
+impl<'a> Recorded<'a> {
+    pub fn new(objective_function: &'a (dyn Fn(&Array1<f64>) -> f64 + Sync)) -> Self {
+        Self {
+            objective_function,
+            positions: RefCell::new(Vec::new()),
+            scores: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn evaluate(&self, position: &Array1<f64>) -> f64 {
+        let score = (self.objective_function)(position);
+        self.positions.borrow_mut().push(position.clone());
+        self.scores.borrow_mut().push(score);
+        score
+    }
+
+    /// How many evaluations have been recorded.
+    pub fn evaluations(&self) -> usize {
+        self.scores.borrow().len()
+    }
+
+    pub fn importance(&self, bins: usize) -> Importance {
+        importance(&self.positions.borrow(), &self.scores.borrow(), bins)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn influential_dimensions_rank_first() {
+        let objective = |x: &Array1<f64>| 10. * x[0] * x[0] + x[1];
+        let recorded = Recorded::new(&objective);
+        let grid = |i: usize| -1. + 2. * i as f64 / 7.;
+        for i in 0..8 {
+            for j in 0..8 {
+                for k in 0..8 {
+                    recorded.evaluate(&Array1::from(vec![grid(i), grid(j), grid(k)]));
+                }
+            }
+        }
+        recorded.evaluate(&Array1::zeros(3));
+        let nan = |_: &Array1<f64>| f64::NAN;
+        let broken = Recorded::new(&nan);
+        broken.evaluate(&Array1::zeros(3));
+
+        assert_eq!(recorded.evaluations(), 513);
+        let importance = recorded.importance(4);
+        assert_eq!(importance.evaluations, 513);
+        assert_eq!(importance.ranking(), vec![0, 1, 2]);
+        assert!(importance.dimensions[0].importance > 0.7);
+        assert!(importance.dimensions[2].importance < 1e-3);
+        assert_eq!(importance.negligible(0.01), vec![2]);
+        assert_eq!(broken.importance(4).evaluations, 0);
+    }
+}
@@ -0,0 +1,123 @@
+//! Inertia that changes over the course of a run.
+//!
+//! A fixed inertia either keeps the turtles exploring for too long or settles them too early. A
+//! schedule picks the inertia anew every iteration, in place of `OptimizerParams::inertia`:
+//! falling linearly from exploration to exploitation, falling with a chaotic wobble from the
+//! logistic map laid over it, or following the share of turtles that improved their personal
+//! best in the last iteration, so a swarm that keeps finding better spots keeps its momentum and
+//! one that does not slows down to look closer.
+
+/// How the inertia is chosen every iteration.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InertiaSchedule {
+    /// `OptimizerParams::inertia`, unchanged.
+    #[default]
+    Constant,
+    /// From `start` to `end` over `iterations`, then `end`.
+    Linear {
+        start: f64,
+        end: f64,
+        iterations: usize,
+    },
+    /// Like `Linear`, with the end value scaled by the logistic map `z = 4z(1 - z)`, which
+    /// `chaotic` starts at 0.7.
+    Chaotic {
+        start: f64,
+        end: f64,
+        iterations: usize,
+        z: f64,
+    },
+    /// Between `min` and `max` in proportion to the share of turtles whose personal best improved
+    /// in the last iteration.
+    SuccessRate { min: f64, max: f64 },
+}
+
+impl InertiaSchedule {
+    /// The usual linear decay, from 0.9 to 0.4.
+    pub fn linear(iterations: usize) -> Self {
+        InertiaSchedule::Linear {
+            start: 0.9,
+            end: 0.4,
+            iterations,
+        }
+    }
+
+    /// The linear decay from 0.9 to 0.4 with its end value made chaotic.
+    pub fn chaotic(iterations: usize) -> Self {
+        InertiaSchedule::Chaotic {
+            start: 0.9,
+            end: 0.4,
+            iterations,
+            z: 0.7,
+        }
+    }
+
+    /// Success rate adaptation between 0 and 1.
+    pub fn success_rate() -> Self {
+        InertiaSchedule::SuccessRate { min: 0., max: 1. }
+    }
+
+    /// The inertia for `iteration`, given the fixed `inertia` of the params and the share of
+    /// turtles that just improved. Chaotic schedules step their map every time.
+    pub(crate) fn inertia(&mut self, inertia: f64, iteration: usize, success_rate: f64) -> f64 {
+        let decay = |start: f64, end: f64, iterations: usize| {
+            let remaining = 1. - (iteration as f64 / iterations.max(1) as f64).min(1.);
+            (start - end) * remaining + end
+        };
+        match self {
+            InertiaSchedule::Constant => inertia,
+            InertiaSchedule::Linear {
+                start,
+                end,
+                iterations,
+            } => decay(*start, *end, *iterations),
+            InertiaSchedule::Chaotic {
+                start,
+                end,
+                iterations,
+                z,
+            } => {
+                *z = 4. * *z * (1. - *z);
+                decay(*start, *end * *z, *iterations)
+            }
+            InertiaSchedule::SuccessRate { min, max } => {
+                *min + (*max - *min) * success_rate.clamp(0., 1.)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CubicBoundary, Optimizer, OptimizerParams};
+    use ndarray::Array1;
+
+    #[test]
+    fn schedules_pick_the_inertia_every_iteration() {
+        let mut linear = InertiaSchedule::linear(10);
+        assert_eq!(linear.inertia(1., 0, 0.), 0.9);
+        assert!((linear.inertia(1., 5, 0.) - 0.65).abs() < 1e-12);
+        assert_eq!(linear.inertia(1., 20, 0.), 0.4);
+        let mut chaotic = InertiaSchedule::chaotic(10);
+        let (first, second) = (chaotic.inertia(1., 10, 0.), chaotic.inertia(1., 10, 0.));
+        assert!(first != second && first < 0.4 && second < 0.4);
+        assert_eq!(InertiaSchedule::success_rate().inertia(1., 3, 0.25), 0.25);
+
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &parabola, 1e-6, 4);
+        optimizer.params = OptimizerParams {
+            inertia: 0.,
+            cognitive: 0.7,
+            social: 0.7,
+        };
+        optimizer.inertia_schedule = InertiaSchedule::linear(100);
+        optimizer.max_iterations = Some(200);
+        assert_eq!(
+            optimizer.optimize().termination,
+            crate::Termination::GoalReached
+        );
+    }
+}
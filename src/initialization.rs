@@ -0,0 +1,333 @@
+//! Alternative ways of placing the turtles before a run.
+//!
+//! Optimizers scatter their turtles uniformly over the boundary. When a rough solution is already
+//! known, as is common in calibration problems, starting the swarm around it saves the turtles a
+//! long crawl.
+//!
+//! In many dimensions a uniform scatter leaves whole regions without a turtle. A Latin hypercube
+//! splits every dimension into one slice per turtle and puts exactly one turtle in each slice, and
+//! a Sobol sequence fills the space evenly at every scale. Both are picked with an `InitStrategy`,
+//! on the builder or through `Optimizer::initialize`.
+//!
+//! Good solutions from a previous run, or from domain knowledge, can be handed over directly as
+//! the rows of an `Array2` with `Optimizer::with_initial_positions` or `place_turtles`.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ndarray::{Array1, Array2};
+#[cfg(feature = "std")]
+use ndarray_rand::rand_distr::{Distribution, Normal};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::boundary::Boundary;
+#[cfg(feature = "std")]
+use crate::objective::ObjectiveFunction;
+use crate::{Optimizer, Turtle};
+
+/// How the turtles are spread over the boundary before the first evaluation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InitStrategy {
+    /// Every turtle is placed independently of the others.
+    #[default]
+    Uniform,
+    /// One turtle per slice of every dimension, with the slices paired up at random.
+    LatinHypercube,
+    /// The first points of a Sobol sequence, which stratifies every dimension evenly whenever the
+    /// number of turtles is a power of two. Dimensions beyond the
+    /// `SOBOL_DIMENSIONS` with direction numbers fall back to Latin hypercube slices.
+    Sobol,
+}
+
+/// How many dimensions the Sobol sequence has direction numbers for.
+pub const SOBOL_DIMENSIONS: usize = 21;
+
+// The degree, the coefficients and the initial direction numbers of the primitive polynomial
+// behind every Sobol dimension after the first, from Joe and Kuo's new-joe-kuo-6.21201 table.
+const SOBOL_POLYNOMIALS: [(u32, u32, &[u32]); SOBOL_DIMENSIONS - 1] = [
+    (1, 0, &[1]),
+    (2, 1, &[1, 3]),
+    (3, 1, &[1, 3, 1]),
+    (3, 2, &[1, 1, 1]),
+    (4, 1, &[1, 1, 3, 3]),
+    (4, 4, &[1, 3, 5, 13]),
+    (5, 2, &[1, 1, 5, 5, 17]),
+    (5, 4, &[1, 1, 5, 5, 5]),
+    (5, 7, &[1, 1, 7, 11, 19]),
+    (5, 11, &[1, 1, 5, 1, 1]),
+    (5, 13, &[1, 1, 1, 3, 11]),
+    (5, 14, &[1, 3, 5, 5, 31]),
+    (6, 1, &[1, 3, 3, 9, 7, 49]),
+    (6, 13, &[1, 1, 1, 15, 21, 21]),
+    (6, 16, &[1, 3, 1, 13, 27, 49]),
+    (6, 19, &[1, 1, 1, 15, 7, 5]),
+    (6, 22, &[1, 3, 1, 15, 13, 25]),
+    (6, 25, &[1, 1, 5, 5, 19, 61]),
+    (7, 1, &[1, 3, 7, 11, 23, 15, 103]),
+    (7, 4, &[1, 3, 7, 13, 13, 15, 69]),
+];
+
+const BITS: usize = 32;
+
+// The 32 direction numbers of a Sobol dimension, the first one being the van der Corput sequence.
+fn direction_numbers(dimension: usize) -> [u32; BITS] {
+    let mut v = [0u32; BITS];
+    if dimension == 0 {
+        for (k, v) in v.iter_mut().enumerate() {
+            *v = 1 << (BITS - 1 - k);
+        }
+        return v;
+    }
+    let (degree, coefficients, initial) = SOBOL_POLYNOMIALS[dimension - 1];
+    let degree = degree as usize;
+    for k in 0..BITS {
+        v[k] = if k < degree {
+            initial[k] << (BITS - 1 - k)
+        } else {
+            let mut next = v[k - degree] ^ (v[k - degree] >> degree);
+            for j in 1..degree {
+                if (coefficients >> (degree - 1 - j)) & 1 == 1 {
+                    next ^= v[k - j];
+                }
+            }
+            next
+        };
+    }
+    v
+}
+
+/// The first `count` points of the Sobol sequence in `[0, 1)^dimensions`, starting from the
+/// origin, one row per point.
+/// Dimensions past `SOBOL_DIMENSIONS` are left at zero.
+pub fn sobol(count: usize, dimensions: usize) -> Vec<Array1<f64>> {
+    let directions: Vec<[u32; BITS]> = (0..dimensions.min(SOBOL_DIMENSIONS))
+        .map(direction_numbers)
+        .collect();
+    let mut state: Vec<u32> = directions.iter().map(|_| 0).collect();
+    let mut points = Vec::with_capacity(count);
+    // Gray code order: each point flips the direction number at the lowest zero bit of its
+    // index to get the next one.
+    for index in 0..count {
+        let mut point = Array1::zeros(dimensions);
+        for (dimension, x) in state.iter().enumerate() {
+            point[dimension] = *x as f64 / (1u64 << BITS) as f64;
+        }
+        points.push(point);
+        let bit = ((!index).trailing_zeros() as usize).min(BITS - 1);
+        for (x, directions) in state.iter_mut().zip(directions.iter()) {
+            *x ^= directions[bit];
+        }
+    }
+    points
+}
+
+/// `count` points of a Latin hypercube in `[0, 1)^dimensions`, one row per point.
+pub fn latin_hypercube<R: Rng + ?Sized>(
+    count: usize,
+    dimensions: usize,
+    rng: &mut R,
+) -> Vec<Array1<f64>> {
+    let mut points: Vec<Array1<f64>> = (0..count).map(|_| Array1::zeros(dimensions)).collect();
+    let mut slices: Vec<usize> = (0..count).collect();
+    for dimension in 0..dimensions {
+        slices.shuffle(rng);
+        for (point, slice) in points.iter_mut().zip(slices.iter()) {
+            point[dimension] = (*slice as f64 + rng.gen::<f64>()) / count as f64;
+        }
+    }
+    points
+}
+
+impl<'a, B: Boundary> Optimizer<'a, B> {
+    /// Like `new`, but with one turtle starting at every row of `positions`.
+    #[cfg(feature = "std")]
+    pub fn with_initial_positions(
+        positions: &Array2<f64>,
+        boundaries: B,
+        objective_function: impl Into<ObjectiveFunction<'a>>,
+        goal: f64,
+    ) -> Self {
+        let mut optimizer = Self::new(0, boundaries, objective_function, goal);
+        optimizer.place_turtles(positions);
+        optimizer
+    }
+
+    /// Replaces the swarm with one turtle at every row of `positions`, projected into the
+    /// boundary. The overall best is kept.
+    pub fn place_turtles(&mut self, positions: &Array2<f64>) {
+        let shape = self.boundaries.dimensions();
+        assert_eq!(
+            positions.ncols(),
+            shape,
+            "initial positions need every dimension"
+        );
+        let worst = self.direction.worst();
+        self.turtles = positions
+            .rows()
+            .into_iter()
+            .map(|row| {
+                let mut position = row.to_owned();
+                self.boundaries.project(&mut position);
+                Turtle {
+                    position,
+                    velocity: f64::EPSILON * Array1::ones(shape),
+                    best_score: worst,
+                    best_position: Array1::zeros(shape),
+                    stats: Default::default(),
+                }
+            })
+            .collect();
+    }
+
+    /// Re-places every turtle with `strategy`, stretching the unit cube over the boundary's
+    /// bounding box. Points that land outside the boundary itself are redrawn uniformly inside it.
+    /// Personal bests are forgotten; the overall best is kept.
+    pub fn initialize(&mut self, strategy: InitStrategy) {
+        let count = self.turtles.len();
+        let shape = self.boundaries.dimensions();
+        let mut points = match strategy {
+            InitStrategy::Uniform => (0..count)
+                .map(|_| self.boundaries.sample(&mut self.rng))
+                .collect(),
+            InitStrategy::LatinHypercube => latin_hypercube(count, shape, &mut self.rng),
+            InitStrategy::Sobol => sobol(count, shape),
+        };
+        if strategy == InitStrategy::Sobol && shape > SOBOL_DIMENSIONS {
+            let rest = latin_hypercube(count, shape - SOBOL_DIMENSIONS, &mut self.rng);
+            for (point, rest) in points.iter_mut().zip(rest.iter()) {
+                for (dimension, x) in rest.iter().enumerate() {
+                    point[SOBOL_DIMENSIONS + dimension] = *x;
+                }
+            }
+        }
+        if strategy != InitStrategy::Uniform {
+            let (lower, upper) = self.boundaries.bounding_box();
+            for point in points.iter_mut() {
+                *point = &lower + &(&*point * &(&upper - &lower));
+                if !self.boundaries.contains(point) {
+                    *point = self.boundaries.sample(&mut self.rng);
+                }
+            }
+        }
+        let worst = self.direction.worst();
+        for (turtle, position) in self.turtles.iter_mut().zip(points) {
+            turtle.position = position;
+            turtle.velocity = f64::EPSILON * Array1::ones(shape);
+            turtle.best_score = worst;
+            turtle.best_position = Array1::zeros(shape);
+        }
+    }
+
+    /// Re-places every turtle by sampling a Gaussian centered on `nominal`, truncated to the
+    /// boundary. `sigmas` holds one standard deviation per dimension, or a single one shared by
+    /// all of them. Personal bests are forgotten; the overall best is kept.
+    #[cfg(feature = "std")]
+    pub fn initialize_gaussian(&mut self, nominal: &Array1<f64>, sigmas: &[f64]) {
+        let shape = self.boundaries.dimensions();
+        assert_eq!(
+            nominal.len(),
+            shape,
+            "the nominal point needs every dimension"
+        );
+        assert!(
+            sigmas.len() == shape || sigmas.len() == 1,
+            "gaussian initialization expects one sigma per dimension or a single shared sigma"
+        );
+        let normals: Vec<Normal<f64>> = (0..shape)
+            .map(|dimension| {
+                let sigma = sigmas[if sigmas.len() == 1 { 0 } else { dimension }];
+                // `Normal` rejects a negative sigma, which is taken for its magnitude instead.
+                Normal::new(nominal[dimension], sigma.abs()).expect("sigma is finite")
+            })
+            .collect();
+        for turtle in 0..self.turtles.len() {
+            let mut draw = || -> Array1<f64> {
+                normals
+                    .iter()
+                    .map(|normal| normal.sample(&mut self.rng))
+                    .collect()
+            };
+            // Truncation by rejection; a nominal point far outside the boundary falls back to
+            // projecting the last draw.
+            let mut position = draw();
+            for _ in 0..100 {
+                if self.boundaries.contains(&position) {
+                    break;
+                }
+                position = draw();
+            }
+            self.boundaries.project(&mut position);
+
+            let turtle = &mut self.turtles[turtle];
+            turtle.position = position;
+            turtle.velocity = f64::EPSILON * Array1::ones(shape);
+            turtle.best_score = self.direction.worst();
+            turtle.best_position = Array1::zeros(shape);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CubicBoundary;
+    use ndarray::array;
+
+    #[test]
+    fn space_filling_starts_cover_every_slice() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(3, -2., 2.);
+        let mut optimizer = Optimizer::new_seeded(16, boundaries, &parabola, -1., 9);
+        // Every quarter of the range of every dimension holds exactly four turtles.
+        let quarters = |optimizer: &Optimizer| {
+            (0..3).all(|dimension| {
+                (0..4).all(|quarter| {
+                    let low = -2. + quarter as f64;
+                    let inside = optimizer
+                        .turtles
+                        .iter()
+                        .filter(|turtle| (low..low + 1.).contains(&turtle.position[dimension]));
+                    inside.count() == 4
+                })
+            })
+        };
+        optimizer.initialize(InitStrategy::LatinHypercube);
+        assert!(quarters(&optimizer));
+        optimizer.initialize(InitStrategy::Sobol);
+        assert!(quarters(&optimizer));
+        assert_eq!(sobol(3, 2)[2], array![0.75, 0.25]);
+        assert_eq!(sobol(2, 30)[1][SOBOL_DIMENSIONS - 1], 0.5);
+        assert_eq!(sobol(2, 30)[1][SOBOL_DIMENSIONS], 0.);
+    }
+
+    #[test]
+    fn turtles_start_where_they_are_told() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let positions = array![[0.5, 0.5], [0., 0.25], [3., -0.5]];
+        let mut optimizer =
+            Optimizer::with_initial_positions(&positions, boundaries, &parabola, -1.);
+        assert_eq!(optimizer.turtles.len(), 3);
+        assert_eq!(optimizer.turtles[1].position, array![0., 0.25]);
+        assert_eq!(optimizer.turtles[2].position, array![1., -0.5]);
+
+        optimizer.iterate();
+        assert_eq!(optimizer.best_score, 0.0625);
+    }
+
+    #[test]
+    fn turtles_start_around_the_nominal_point() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(2, -10., 10.);
+        let mut optimizer = Optimizer::new_seeded(200, boundaries, &parabola, -1., 9);
+        optimizer.initialize_gaussian(&array![9., 0.], &[2., 0.1]);
+
+        let positions = || optimizer.turtles.iter().map(|turtle| &turtle.position);
+        assert!(positions().all(|position| boundaries.contains(position)));
+        assert!(positions().all(|position| position[1].abs() < 1.));
+        assert!(positions().any(|position| position[0] < 9.));
+        let mean = positions().map(|position| position[0]).sum::<f64>() / 200.;
+        assert!(mean > 7. && mean < 9.);
+    }
+}
@@ -0,0 +1,153 @@
+//! Dimensions that only take whole numbers, for mixed continuous and discrete problems.
+//!
+//! Hyperparameter searches mix real valued knobs like a learning rate with counts like the number
+//! of layers. `Optimizer::set_integer_dimensions` marks the counts: turtles keep flying through
+//! the continuous space, but every time they move the marked coordinates are rounded, so the
+//! objective only ever sees whole numbers there. Rounding to the nearest whole number can leave a
+//! turtle stuck when its steps are shorter than a half, which stochastic rounding avoids by
+//! rounding up with a chance equal to the fractional part.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ndarray::Array1;
+use rand::{Rng, RngCore};
+
+use crate::boundary::Boundary;
+use crate::Optimizer;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rounding {
+    /// Halves round away from zero.
+    #[default]
+    Nearest,
+    /// Rounds up with a chance equal to the fractional part, down otherwise.
+    Stochastic,
+}
+
+/// The dimensions kept whole and how they are rounded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntegerDimensions {
+    pub dimensions: Vec<usize>,
+    pub rounding: Rounding,
+}
+
+// `f64::floor` lives in std, and every whole number an objective will count fits an `i64`.
+fn floor(x: f64) -> f64 {
+    let truncated = x as i64 as f64;
+    if truncated > x {
+        truncated - 1.
+    } else {
+        truncated
+    }
+}
+
+impl IntegerDimensions {
+    pub fn new(dimensions: Vec<usize>) -> Self {
+        Self {
+            dimensions,
+            rounding: Rounding::Nearest,
+        }
+    }
+
+    pub fn stochastic(dimensions: Vec<usize>) -> Self {
+        Self {
+            dimensions,
+            rounding: Rounding::Stochastic,
+        }
+    }
+
+    /// Rounds the marked coordinates of `position`. A coordinate rounded out of `boundaries` is
+    /// projected back and rounded towards the inside instead, and kept as projected when no whole
+    /// number fits.
+    pub(crate) fn round<B: Boundary + ?Sized>(
+        &self,
+        boundaries: &B,
+        position: &mut Array1<f64>,
+        rng: &mut dyn RngCore,
+    ) {
+        for dimension in self.dimensions.iter().copied() {
+            let Some(x) = position.get(dimension).copied() else {
+                continue;
+            };
+            let below = floor(x);
+            let up = match self.rounding {
+                Rounding::Nearest if x < 0. => x - below > 0.5,
+                Rounding::Nearest => x - below >= 0.5,
+                Rounding::Stochastic => rng.gen_bool((x - below).clamp(0., 1.)),
+            };
+            position[dimension] = if up { below + 1. } else { below };
+        }
+        if boundaries.contains(position) {
+            return;
+        }
+        let rounded = position.clone();
+        boundaries.project(position);
+        for dimension in self.dimensions.iter().copied() {
+            let Some(projected) = position.get(dimension).copied() else {
+                continue;
+            };
+            let inward = if projected > rounded[dimension] {
+                -floor(-projected)
+            } else {
+                floor(projected)
+            };
+            position[dimension] = inward;
+            if !boundaries.contains(position) {
+                position[dimension] = projected;
+            }
+        }
+    }
+}
+
+impl<B: Boundary> Optimizer<'_, B> {
+    /// The dimensions kept whole, if any.
+    pub fn integer_dimensions(&self) -> Option<&IntegerDimensions> {
+        self.integer_dimensions.as_ref()
+    }
+
+    /// Keeps the given dimensions whole from now on. The turtles are rounded where they stand
+    /// right away, so the first evaluation already sees whole numbers.
+    pub fn set_integer_dimensions(&mut self, integer_dimensions: Option<IntegerDimensions>) {
+        if let Some(integer) = &integer_dimensions {
+            for turtle in self.turtles.iter_mut() {
+                integer.round(&self.boundaries, &mut turtle.position, &mut self.rng);
+            }
+        }
+        self.integer_dimensions = integer_dimensions;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::RectangularBoundary;
+    use crate::Termination;
+
+    #[test]
+    fn whole_dimensions_only_see_whole_numbers() {
+        // A layer count between 1 and 8 and a rate, best at 5 layers.
+        let tuning = |x: &Array1<f64>| {
+            assert_eq!(x[0], floor(x[0]));
+            (x[0] - 5.2) * (x[0] - 5.2) + (x[1] - 0.25) * (x[1] - 0.25)
+        };
+        let boundaries = RectangularBoundary::from_bounds(&[(0.5, 8.5), (0., 1.)]);
+        for integer in [
+            IntegerDimensions::new(vec![0]),
+            IntegerDimensions::stochastic(vec![0]),
+        ] {
+            let mut optimizer = Optimizer::new_seeded(20, boundaries.clone(), &tuning, -1., 4);
+            optimizer.max_iterations = Some(100);
+            optimizer.set_integer_dimensions(Some(integer));
+            let result = optimizer.optimize();
+
+            assert_eq!(result.termination, Termination::IterationLimit);
+            assert_eq!(result.best_position[0], 5.);
+            assert!(optimizer
+                .turtles
+                .iter()
+                .all(|turtle| (1. ..=8.).contains(&turtle.position[0])));
+        }
+    }
+}
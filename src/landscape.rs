@@ -0,0 +1,187 @@
+//! Fitness landscape analysis, for sizing up a problem before spending a real budget on it.
+//!
+//! - Ruggedness is measured on a random walk: the autocorrelation of consecutive scores and the
+//!   correlation length derived from it. Smooth landscapes stay correlated over long walks.
+//! - Fitness-distance correlation compares uniform samples' scores with their distance to the best
+//!   sample. Values near one mean the landscape funnels towards its best point.
+//! - Basins are estimated by nearest-better clustering: a sample with no better neighbour among its
+//!   nearest ones is taken to sit at the bottom of its own basin.
+
+use ndarray::Array1;
+use ndarray_rand::rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::Problem;
+
+#[derive(Clone, Copy, Debug)]
+pub struct LandscapeAnalysis {
+    /// Uniform samples for the fitness-distance correlation and the basin estimate.
+    pub samples: usize,
+    pub walk_length: usize,
+    /// The walk's step size, as a fraction of the search space's width.
+    pub step: f64,
+    /// How many nearest samples are searched for a better one.
+    pub neighbours: usize,
+}
+
+impl Default for LandscapeAnalysis {
+    fn default() -> Self {
+        Self {
+            samples: 500,
+            walk_length: 1000,
+            step: 0.05,
+            neighbours: 5,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct LandscapeReport {
+    /// The lag one autocorrelation of scores along the random walk.
+    pub autocorrelation: f64,
+    /// How many steps apart scores stay correlated, `-1 / ln |autocorrelation|`.
+    pub correlation_length: f64,
+    pub fitness_distance_correlation: f64,
+    pub basins: usize,
+    pub evaluations: usize,
+}
+
+impl LandscapeAnalysis {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn analyze(&self, problem: &Problem, seed: u64) -> LandscapeReport {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let boundaries = problem.boundaries;
+        let (lower, upper) = (boundaries.lower, boundaries.upper);
+        let uniform = |rng: &mut StdRng| -> Array1<f64> {
+            (0..boundaries.shape)
+                .map(|_| {
+                    if lower < upper {
+                        rng.gen_range(lower..upper)
+                    } else {
+                        lower
+                    }
+                })
+                .collect()
+        };
+
+        let mut position = uniform(&mut rng);
+        let step = self.step * (upper - lower);
+        let mut walk = Vec::with_capacity(self.walk_length);
+        for _ in 0..self.walk_length {
+            walk.push((problem.objective_function)(&position));
+            position =
+                position.mapv(|x| (x + rng.gen_range(-1.0..=1.0) * step).clamp(lower, upper));
+        }
+
+        let positions: Vec<Array1<f64>> = (0..self.samples).map(|_| uniform(&mut rng)).collect();
+        let scores: Vec<f64> = positions
+            .iter()
+            .map(|position| (problem.objective_function)(position))
+            .collect();
+
+        let rho = autocorrelation(&walk, 1);
+        LandscapeReport {
+            autocorrelation: rho,
+            correlation_length: -1. / rho.abs().ln(),
+            fitness_distance_correlation: fitness_distance_correlation(&positions, &scores),
+            basins: estimate_basins(&positions, &scores, self.neighbours),
+            evaluations: walk.len() + scores.len(),
+        }
+    }
+}
+
+fn correlation(a: &[f64], b: &[f64]) -> f64 {
+    let n = a.len().min(b.len()) as f64;
+    let mean_a = a.iter().sum::<f64>() / n;
+    let mean_b = b.iter().sum::<f64>() / n;
+    let (mut covariance, mut variance_a, mut variance_b) = (0., 0., 0.);
+    for (x, y) in a.iter().zip(b.iter()) {
+        covariance += (x - mean_a) * (y - mean_b);
+        variance_a += (x - mean_a).powi(2);
+        variance_b += (y - mean_b).powi(2);
+    }
+    covariance / (variance_a * variance_b).sqrt()
+}
+
+/// The autocorrelation of `series` at `lag`. NaN for series that are too short or constant.
+pub fn autocorrelation(series: &[f64], lag: usize) -> f64 {
+    if series.len() <= lag + 1 {
+        return f64::NAN;
+    }
+    correlation(&series[..series.len() - lag], &series[lag..])
+}
+
+/// The correlation between scores and distances to the best scoring position.
+pub fn fitness_distance_correlation(positions: &[Array1<f64>], scores: &[f64]) -> f64 {
+    let Some(best) = scores
+        .iter()
+        .enumerate()
+        .min_by(|a, b| a.1.total_cmp(b.1))
+        .map(|(index, _)| &positions[index])
+    else {
+        return f64::NAN;
+    };
+    let distances: Vec<f64> = positions
+        .iter()
+        .map(|position| (position - best).mapv(|x| x * x).sum().sqrt())
+        .collect();
+    correlation(scores, &distances)
+}
+
+/// The number of samples with no better sample among their `neighbours` nearest.
+pub fn estimate_basins(positions: &[Array1<f64>], scores: &[f64], neighbours: usize) -> usize {
+    (0..positions.len())
+        .filter(|&i| {
+            let mut nearest: Vec<(f64, usize)> = (0..positions.len())
+                .filter(|&j| j != i)
+                .map(|j| ((&positions[i] - &positions[j]).mapv(|x| x * x).sum(), j))
+                .collect();
+            nearest.sort_by(|a, b| a.0.total_cmp(&b.0));
+            nearest
+                .iter()
+                .take(neighbours)
+                .all(|(_, j)| scores[*j] >= scores[i])
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CubicBoundary;
+
+    #[test]
+    fn rugged_landscapes_are_told_apart_from_smooth_ones() {
+        let boundaries = CubicBoundary::new(2, -5., 5.);
+        let sphere = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let rastrigin = |x: &Array1<f64>| {
+            x.mapv(|x| x * x - 10. * (2. * std::f64::consts::PI * x).cos() + 10.)
+                .sum()
+        };
+        let analysis = LandscapeAnalysis {
+            samples: 200,
+            ..LandscapeAnalysis::new()
+        };
+        let smooth = analysis.analyze(
+            &Problem {
+                objective_function: &sphere,
+                boundaries,
+            },
+            1,
+        );
+        let rugged = analysis.analyze(
+            &Problem {
+                objective_function: &rastrigin,
+                boundaries,
+            },
+            1,
+        );
+
+        assert_eq!(smooth.evaluations, 1200);
+        assert!(smooth.autocorrelation > rugged.autocorrelation);
+        assert!(smooth.fitness_distance_correlation > 0.9);
+        assert!(smooth.basins < rugged.basins);
+    }
+}
@@ -1,14 +1,239 @@
-use std::f64::{EPSILON, INFINITY};
+#![cfg_attr(not(feature = "std"), no_std)]
 
-use ndarray::{Array, Array1};
-use ndarray_rand::{rand_distr::Uniform, RandomExt};
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::fmt;
+use core::ops::{Add, Mul, Sub};
+use ndarray::Array1;
+#[cfg(feature = "std")]
+use rand::thread_rng;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use boundary::Boundary;
+use middleware::{Middleware, Phase};
+use tabu::TabuArchive;
+
+pub mod aggregation;
+pub mod archipelago;
+#[cfg(feature = "argmin")]
+pub mod argmin;
+#[cfg(feature = "async")]
+pub mod asynchronous;
+#[cfg(feature = "std")]
+pub mod basin_hopping;
+#[cfg(feature = "std")]
+pub mod benchmarks;
+#[cfg(feature = "std")]
+pub mod binary;
+pub mod boundary;
+pub mod builder;
+pub mod checkpoint;
+#[cfg(feature = "cli")]
+pub mod cli;
+#[cfg(feature = "std")]
+pub mod cma_es;
+pub mod constraints;
+pub mod differential_evolution;
+#[cfg(feature = "std")]
+pub mod dry_run;
+pub mod duplicates;
+#[cfg(feature = "std")]
+pub mod eta;
+#[cfg(feature = "std")]
+pub mod evaluation_log;
+#[cfg(feature = "std")]
+pub mod experiment;
+#[cfg(feature = "std")]
+pub mod fallible;
+#[cfg(feature = "farm")]
+pub mod farm;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "std")]
+pub mod firefly;
+#[cfg(feature = "fixed")]
+pub mod fixed_point;
+#[cfg(feature = "float")]
+pub mod float;
+pub mod grey_wolf;
+#[cfg(feature = "std")]
+pub mod handle;
+pub mod history;
+#[cfg(feature = "std")]
+pub mod hyperband;
+pub mod importance;
+pub mod inertia;
+pub mod initialization;
+pub mod integer;
+#[cfg(feature = "std")]
+pub mod landscape;
+pub mod matrix;
+#[cfg(feature = "std")]
+pub mod meta;
+pub mod middleware;
+#[cfg(feature = "std")]
+pub mod multistart;
+pub mod nan;
+pub mod nelder_mead;
+pub mod noise;
+#[cfg(feature = "std")]
+pub mod notebook;
+pub mod objective;
+pub mod observer;
+#[cfg(feature = "std")]
+pub mod panics;
+pub mod pareto;
+#[cfg(feature = "std")]
+pub mod permutation;
+#[cfg(feature = "std")]
+pub mod problems;
+#[cfg(feature = "std")]
+pub mod progress;
+#[cfg(feature = "python")]
+mod python;
+#[cfg(feature = "std")]
+pub mod rate_limit;
+#[cfg(feature = "std")]
+pub mod retry;
+pub mod robust;
+#[cfg(feature = "script")]
+pub mod script;
+#[cfg(feature = "std")]
+pub mod sensitivity;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "std")]
+pub mod simulated_annealing;
+pub mod stack;
+pub mod stagnation;
+pub mod step_scaling;
+pub mod stopping;
+#[cfg(feature = "onnx")]
+pub mod surrogate;
+pub mod tabu;
+#[cfg(feature = "tracing")]
+pub mod telemetry;
+pub mod topology;
+pub mod transfer;
+pub mod trust_region;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "std")]
+pub mod watch;
 
 // Turtles move slowly and methodically, whatever you do don't change this parameter.
 // Especially do not raise it's value, or you will lose all of the advantages of the
 // turtle swarm optimizer (TSO).
-const TURTLE_VELOCITY: f64 = EPSILON;
+const TURTLE_VELOCITY: f64 = f64::EPSILON;
 
-#[derive(Clone, Copy)]
+/// How a turtle weighs its own momentum against the pull of the bests. The defaults are the
+/// turtle's own: full momentum and the slowest possible pull towards either best. Raising the
+/// coefficients turns the turtles into something closer to ordinary particles. Swarms over other
+/// floats, see `float`, take their coefficients in that float.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptimizerParams<T = f64> {
+    /// How much of its velocity a turtle keeps from one iteration to the next.
+    pub inertia: T,
+    /// The pull towards the turtle's personal best.
+    pub cognitive: T,
+    /// The pull towards the swarm's best.
+    pub social: T,
+}
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>> OptimizerParams<T> {
+    /// The inertia form of the velocity update in one dimension, from the turtle's velocity and
+    /// position, its personal best, and the best it is drawn towards. Every swarm in the crate
+    /// moves its turtles with this.
+    pub fn velocity(&self, velocity: T, position: T, personal: T, best: T) -> T {
+        self.inertia * velocity
+            + self.cognitive * (personal - position)
+            + self.social * (best - position)
+    }
+}
+
+impl Default for OptimizerParams {
+    fn default() -> Self {
+        Self {
+            inertia: 1.,
+            cognitive: TURTLE_VELOCITY,
+            social: TURTLE_VELOCITY,
+        }
+    }
+}
+
+impl OptimizerParams {
+    /// Clerc and Kennedy's standard coefficients for the `Constriction` form, 2.05 each, which
+    /// constrict velocities by about 0.7298. The inertia is left at 1, which that form ignores.
+    pub fn clerc() -> Self {
+        Self {
+            inertia: 1.,
+            cognitive: 2.05,
+            social: 2.05,
+        }
+    }
+
+    /// Clerc and Kennedy's constriction factor `2 / |2 - phi - sqrt(phi^2 - 4 phi)|` for the sum
+    /// `phi` of the cognitive and social coefficients. Sums of 4 or less need no constriction
+    /// and give 1.
+    pub fn constriction(&self) -> f64 {
+        let phi = self.cognitive + self.social;
+        if phi <= 4. {
+            return 1.;
+        }
+        let denominator = 2. - phi - sqrt(phi * phi - 4. * phi);
+        2. / if denominator < 0. {
+            -denominator
+        } else {
+            denominator
+        }
+    }
+}
+
+/// How the coefficients combine into a turtle's new velocity.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VelocityUpdate {
+    /// `inertia * v + cognitive * (personal - x) + social * (best - x)`.
+    #[default]
+    Inertia,
+    /// `chi * (v + cognitive * (personal - x) + social * (best - x))`, with `chi` from
+    /// `OptimizerParams::constriction`. The inertia and any inertia schedule are not used, and
+    /// the swarm is guaranteed to settle down.
+    Constriction,
+}
+
+/// The fastest a turtle may move along any one dimension per iteration. Faster velocities are
+/// clamped component by component, so big coefficients do not slam turtles into the walls.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VelocityLimit {
+    /// The same speed in every dimension.
+    Absolute(f64),
+    /// This fraction of the width of the boundary's bounding box along each dimension.
+    Fraction(f64),
+}
+
+impl VelocityLimit {
+    /// The speed limit along each dimension of `boundaries`.
+    pub fn limits<B: Boundary + ?Sized>(&self, boundaries: &B) -> Array1<f64> {
+        match *self {
+            VelocityLimit::Absolute(limit) => {
+                Array1::from_elem(boundaries.dimensions(), limit.abs())
+            }
+            VelocityLimit::Fraction(fraction) => {
+                let (lower, upper) = boundaries.bounding_box();
+                (upper - lower).mapv(|width| (fraction * width).abs())
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CubicBoundary {
     pub lower: f64,
     pub upper: f64,
@@ -34,26 +259,216 @@ impl CubicBoundary {
     }
 }
 
+/// Which dimensions the turtles move along in each iteration.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UpdateMode {
+    /// Every dimension moves every iteration.
+    #[default]
+    All,
+    /// Consecutive blocks of `block` dimensions take turns, wrapping around at the end.
+    Cyclic { block: usize },
+    /// `block` dimensions drawn at random each iteration, the same ones for every turtle.
+    Random { block: usize },
+}
+
+/// Whether smaller or larger scores are better.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    #[default]
+    Minimize,
+    Maximize,
+}
+
+impl Direction {
+    /// The score every real score beats, which unscored bests hold.
+    pub fn worst(self) -> f64 {
+        match self {
+            Direction::Minimize => f64::INFINITY,
+            Direction::Maximize => f64::NEG_INFINITY,
+        }
+    }
+
+    /// Whether `score` is strictly better than `than`.
+    pub fn is_better(self, score: f64, than: f64) -> bool {
+        match self {
+            Direction::Minimize => score < than,
+            Direction::Maximize => score > than,
+        }
+    }
+
+    /// Whether `score` is at least as good as `goal`.
+    pub fn reaches(self, score: f64, goal: f64) -> bool {
+        match self {
+            Direction::Minimize => score <= goal,
+            Direction::Maximize => score >= goal,
+        }
+    }
+}
+
+/// Why `optimize` stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Termination {
+    /// The best score reached the goal.
+    GoalReached,
+    /// `max_iterations` were spent before the goal was reached.
+    IterationLimit,
+    /// `max_duration` ran out before the goal was reached.
+    TimeLimit,
+    /// `max_evaluations` were spent before the goal was reached.
+    EvaluationLimit,
+    /// The best score stopped improving, see `stopping::NoImprovement`.
+    Stagnated,
+    /// The run was cancelled through its `handle::OptimizationHandle`.
+    Cancelled,
+    /// A stopping criterion of the user's own said so.
+    Criterion,
+    /// An observer asked to stop.
+    Observer,
+    /// The objective returned NaN or an infinite score under `NanPolicy::Error`.
+    InvalidScore,
+}
+
+impl fmt::Display for Termination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            Termination::GoalReached => "the goal was reached",
+            Termination::IterationLimit => "the iteration limit was spent",
+            Termination::TimeLimit => "the time limit ran out",
+            Termination::EvaluationLimit => "the evaluation budget was spent",
+            Termination::Stagnated => "the best score stopped improving",
+            Termination::Cancelled => "the run was cancelled",
+            Termination::Criterion => "the stopping criterion was met",
+            Termination::Observer => "an observer asked to stop",
+            Termination::InvalidScore => "the objective returned an invalid score",
+        };
+        f.write_str(reason)
+    }
+}
+
+/// What a run found, as returned by `optimize`, in the float the swarm ran in.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OptimizationResult<T = f64> {
+    pub best_position: Array1<T>,
+    pub best_score: T,
+    pub iterations: usize,
+    /// How many times the objective was evaluated, which replicated evaluations and restarts can
+    /// set apart from the iterations.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub evaluations: usize,
+    /// Each turtle's personal best score and position, in turtle order.
+    pub turtle_bests: Vec<(T, Array1<T>)>,
+    pub termination: Termination,
+}
+
+/// The same run down as `Optimizer::report`, for a finished run.
+impl<T: fmt::Display> fmt::Display for OptimizationResult<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} turtles performed {} optimizer iterations for you, until {}.",
+            self.turtle_bests.len(),
+            self.iterations,
+            self.termination
+        )?;
+        writeln!(f, "The objective was evaluated {} times.", self.evaluations)?;
+        writeln!(
+            f,
+            "The best score: {} was observed at position: {}",
+            self.best_score, self.best_position
+        )?;
+        writeln!(f, "Below is a complete run down of the best locations: ")?;
+        for (turtle_number, (score, position)) in self.turtle_bests.iter().enumerate() {
+            writeln!(
+                f,
+                "\t Turtle #{}'s best score {}, was observed at {} ",
+                turtle_number, score, position
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A placeholder objective for optimizers driven purely through `ask` and `tell`, where scores are
+/// computed outside of this library and the optimizer never calls its own objective.
+pub fn external_objective(_position: &Array1<f64>) -> f64 {
+    f64::INFINITY
+}
+
+/// An objective function paired with the region it should be searched in.
+#[derive(Clone, Copy)]
+pub struct Problem<'a> {
+    pub objective_function: &'a (dyn Fn(&Array1<f64>) -> f64 + Sync),
+    pub boundaries: CubicBoundary,
+}
+
+// `f64::sqrt` lives in std. Without it a few Newton steps from a guess that halves the exponent
+// get to the same answer.
+#[cfg(feature = "std")]
+fn sqrt(x: f64) -> f64 {
+    x.sqrt()
+}
+
+#[cfg(not(feature = "std"))]
+fn sqrt(x: f64) -> f64 {
+    if x <= 0. || !x.is_finite() {
+        return if x == 0. || x == f64::INFINITY {
+            x
+        } else {
+            f64::NAN
+        };
+    }
+    let mut root = f64::from_bits((x.to_bits() >> 1) + (1023 << 51));
+    for _ in 0..6 {
+        root = 0.5 * (root + x / root);
+    }
+    root
+}
+
+/// What a turtle has contributed to a run, for spotting dead weight in the swarm.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TurtleStats {
+    /// Scores recorded for the turtle, by evaluation or through `tell`.
+    pub evaluations: usize,
+    /// How many times the turtle improved on the overall best.
+    pub improvements: usize,
+    /// The distance crawled, not counting restarts and tabu escapes.
+    pub displacement: f64,
+}
+
 // Turtles are similar to `particles` in particle swarm optimization strategies.
 // They store a location, velocity, and local best scoring information.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Turtle {
     pub position: Array1<f64>,
     velocity: Array1<f64>,
     pub best_score: f64,
     pub best_position: Array1<f64>,
+    pub stats: TurtleStats,
 }
 
 impl Turtle {
-    pub fn new(boundaries: &CubicBoundary) -> Self {
+    #[cfg(feature = "std")]
+    pub fn new<B: Boundary + ?Sized>(boundaries: &B) -> Self {
+        Self::new_using(boundaries, &mut thread_rng())
+    }
+
+    /// Places a new turtle using the given random number generator.
+    pub fn new_using<B: Boundary + ?Sized, R: RngCore + ?Sized>(
+        boundaries: &B,
+        mut rng: &mut R,
+    ) -> Self {
         Self {
-            position: Array::random(
-                boundaries.shape,
-                Uniform::new(boundaries.lower, boundaries.upper),
-            ),
-            velocity: EPSILON * Array1::ones(boundaries.shape),
-            best_score: INFINITY,
-            best_position: Array1::zeros(boundaries.shape),
+            position: boundaries.sample(&mut rng),
+            velocity: f64::EPSILON * Array1::ones(boundaries.dimensions()),
+            best_score: f64::INFINITY,
+            best_position: Array1::zeros(boundaries.dimensions()),
+            stats: TurtleStats::default(),
         }
     }
 }
@@ -61,106 +476,768 @@ impl Turtle {
 /// The Optimizer type is the core of this library. It defines how the optimization/minimization
 /// process should proceed. One important deviation between the TSO and PSO algorithms is, the TSO
 /// offers no early exit from achieving your goal (a minimum acceptabe float point value).
-/// This rule was derived to follow biomimetic inspiration.
-pub struct Optimizer<'a> {
+/// This rule was derived to follow biomimetic inspiration. For goals that may be out of reach,
+/// `max_iterations` is the one concession.
+///
+/// The turtles search a `CubicBoundary` unless they are given any other `Boundary`.
+pub struct Optimizer<'a, B = CubicBoundary> {
     pub turtles: Vec<Turtle>,
-    pub boundaries: CubicBoundary,
+    pub boundaries: B,
     pub iterations: usize,
+    /// How many times the objective has been evaluated, counting the scores handed to `tell`.
+    pub evaluations: usize,
     pub best_score: f64,
     pub best_position: Array1<f64>,
-    pub objective_function: &'a dyn Fn(&Array1<f64>) -> f64,
+    /// Borrowed or owned, see `objective`.
+    pub objective_function: objective::ObjectiveFunction<'a>,
     pub goal: f64,
+    /// The most iterations `optimize` may spend in total before giving up on the goal. Unlimited
+    /// by default, true to the turtles' nature.
+    pub max_iterations: Option<usize>,
+    /// The most evaluations `optimize` may spend in total. Checked between iterations, so the
+    /// last iteration may spend a few more. Unlimited by default.
+    pub max_evaluations: Option<usize>,
+    /// The most wall-clock time the run may take, counted from the first time `optimize` or
+    /// `step` checks whether to stop. Checked between iterations, so an iteration that has
+    /// started is finished. Unlimited by default.
+    #[cfg(feature = "std")]
+    pub max_duration: Option<std::time::Duration>,
+    // When the clock for `max_duration` started.
+    #[cfg(feature = "std")]
+    started: Option<std::time::Instant>,
+    /// Decides when `optimize` stops in place of the goal and `max_iterations`, see `stopping`.
+    pub stopping: Option<Box<dyn stopping::StoppingCriterion<Optimizer<'a, B>> + Send + 'a>>,
+    /// Whose best each turtle is drawn towards, see `topology`.
+    pub topology: topology::Topology,
+    /// The inertia, cognitive and social coefficients of the velocity update.
+    pub params: OptimizerParams,
+    /// Picks the inertia anew every iteration in place of the params', see `inertia`.
+    pub inertia_schedule: inertia::InertiaSchedule,
+    /// The inertia form of the velocity update, or Clerc and Kennedy's constriction form.
+    pub velocity_update: VelocityUpdate,
+    // How many personal bests improved since the turtles last moved.
+    successes: usize,
+    /// Clamps every velocity component after the update. Unlimited by default.
+    pub v_max: Option<VelocityLimit>,
+    /// How turtles that crawl out of the boundary are brought back, see `boundary`.
+    pub boundary_policy: boundary::BoundaryPolicy,
+    /// Regions the turtles are kept out of. Empty unless basins are archived, see `restart`.
+    pub tabu: TabuArchive,
+    /// Coordinate-descent style updates that freeze all but a few dimensions per iteration.
+    pub update_mode: UpdateMode,
+    /// Hooks that run after the turtles are scored and after they move, see `middleware`.
+    pub middleware: Vec<Box<dyn Middleware<B> + Send + 'a>>,
+    /// The best of every iteration, kept for convergence plots, see `history`. Off unless set.
+    pub history: Option<history::History>,
+    /// Callbacks with read access at the end of every iteration, see `observer`.
+    pub observers: Vec<Box<dyn observer::Observer<Optimizer<'a, B>> + Send + 'a>>,
+    // Whether an observer has asked the run to stop.
+    observer_stop: bool,
+    /// What a NaN or infinite score from the objective means, see `nan`.
+    pub nan_policy: nan::NanPolicy,
+    // Whether a score was invalid under `NanPolicy::Error`.
+    invalid_score: bool,
+    /// Per-dimension step sizes adapted to the objective, see `step_scaling`. Off unless set.
+    pub step_scaling: Option<step_scaling::StepScaling>,
+    /// Nudges turtles that share a position apart, see `duplicates`. Off unless set.
+    pub deduplication: Option<duplicates::Deduplication>,
+    /// Replicated evaluations for noisy objectives, see `noise`. Off unless set.
+    pub noise: Option<noise::NoiseHandling>,
+    /// Re-places the worst turtles once the best stops improving, see `stagnation`. Off unless
+    /// set.
+    pub stagnation: Option<stagnation::StagnationRestart>,
+    // Set through `set_integer_dimensions`, which rounds the turtles already placed.
+    integer_dimensions: Option<integer::IntegerDimensions>,
+    // Set through `set_direction`, which keeps the unscored bests consistent with it.
+    direction: Direction,
+    // The dimensions moving in the current iteration, or all of them when `None`.
+    active_dimensions: Option<Vec<usize>>,
+    // Where the turtle being moved started from, kept so measuring its displacement does not
+    // allocate.
+    previous: Array1<f64>,
+    // ChaCha12, the generator behind `StdRng`, which unlike `StdRng` can report its state.
+    rng: ChaCha12Rng,
+    #[cfg(feature = "std")]
+    watch: Option<watch::Watch>,
+    /// A JSON line per recorded score, see `evaluation_log`. Off unless set.
+    #[cfg(feature = "std")]
+    pub evaluation_log: Option<evaluation_log::EvaluationLog<'a>>,
+    /// Catches panics of the objective and records them, see `panics`. Off unless set.
+    #[cfg(feature = "std")]
+    pub panics: Option<panics::PanicLog>,
 }
 
-impl<'a> Optimizer<'a> {
+impl<'a, B: Boundary> Optimizer<'a, B> {
+    #[cfg(feature = "std")]
     pub fn new(
         turtles: usize,
-        boundaries: CubicBoundary,
-        objective_function: &'a dyn Fn(&Array1<f64>) -> f64,
+        boundaries: B,
+        objective_function: impl Into<objective::ObjectiveFunction<'a>>,
+        goal: f64,
+    ) -> Self {
+        Self::new_using(
+            turtles,
+            boundaries,
+            objective_function,
+            goal,
+            &mut thread_rng(),
+        )
+    }
+
+    /// Like `new`, but the turtles are placed reproducibly from `seed`.
+    pub fn new_seeded(
+        turtles: usize,
+        boundaries: B,
+        objective_function: impl Into<objective::ObjectiveFunction<'a>>,
+        goal: f64,
+        seed: u64,
+    ) -> Self {
+        let rng = ChaCha12Rng::seed_from_u64(seed);
+        Self::with_rng(turtles, boundaries, objective_function, goal, rng)
+    }
+
+    /// Like `new`, but all randomness is drawn from a generator seeded by `rng`. Without the
+    /// `std` feature there is no thread rng, so this or `new_seeded` is how optimizers are made.
+    ///
+    /// Panics if `rng` fails to produce a seed.
+    pub fn new_using<R: RngCore + ?Sized>(
+        turtles: usize,
+        boundaries: B,
+        objective_function: impl Into<objective::ObjectiveFunction<'a>>,
         goal: f64,
+        rng: &mut R,
     ) -> Self {
+        let rng = ChaCha12Rng::from_rng(rng).expect("the rng provides a seed");
+        Self::with_rng(turtles, boundaries, objective_function, goal, rng)
+    }
+
+    fn with_rng(
+        turtles: usize,
+        boundaries: B,
+        objective_function: impl Into<objective::ObjectiveFunction<'a>>,
+        goal: f64,
+        mut rng: ChaCha12Rng,
+    ) -> Self {
+        let shape = boundaries.dimensions();
         Self {
             turtles: (0..turtles)
-                .map(|_| Turtle::new(&boundaries))
+                .map(|_| Turtle::new_using(&boundaries, &mut rng))
                 .collect::<Vec<Turtle>>(),
             boundaries,
             iterations: 0,
-            best_score: INFINITY,
-            best_position: Array1::zeros(boundaries.shape),
-            objective_function,
+            evaluations: 0,
+            best_score: f64::INFINITY,
+            best_position: Array1::zeros(shape),
+            objective_function: objective_function.into(),
             goal,
+            max_iterations: None,
+            max_evaluations: None,
+            #[cfg(feature = "std")]
+            max_duration: None,
+            #[cfg(feature = "std")]
+            started: None,
+            stopping: None,
+            params: OptimizerParams::default(),
+            inertia_schedule: inertia::InertiaSchedule::Constant,
+            successes: 0,
+            velocity_update: VelocityUpdate::Inertia,
+            v_max: None,
+            boundary_policy: boundary::BoundaryPolicy::Clamp,
+            topology: topology::Topology::Star,
+            tabu: TabuArchive::new(),
+            update_mode: UpdateMode::All,
+            middleware: Vec::new(),
+            history: None,
+            observers: Vec::new(),
+            observer_stop: false,
+            nan_policy: nan::NanPolicy::Worst,
+            invalid_score: false,
+            step_scaling: None,
+            deduplication: None,
+            stagnation: None,
+            noise: None,
+            integer_dimensions: None,
+            direction: Direction::Minimize,
+            active_dimensions: None,
+            previous: Array1::zeros(shape),
+            rng,
+            #[cfg(feature = "std")]
+            watch: None,
+            #[cfg(feature = "std")]
+            evaluation_log: None,
+            #[cfg(feature = "std")]
+            panics: None,
         }
     }
 
-    fn evaluate(&mut self) {
+    /// Starts a new run on `objective_function` from where `previous` left off, carrying over the
+    /// turtles with their personal bests and the overall best, so an iterative design cycle does
+    /// not restart from scratch. The carried scores belong to the old objective; call
+    /// `reevaluate_bests` when the objective has changed.
+    pub fn from_previous(
+        previous: &Optimizer<'_, B>,
+        objective_function: impl Into<objective::ObjectiveFunction<'a>>,
+    ) -> Self
+    where
+        B: Clone,
+    {
+        let rng = ChaCha12Rng::from_rng(previous.rng.clone()).expect("chacha provides a seed");
+        let mut optimizer = Self::with_rng(
+            0,
+            previous.boundaries.clone(),
+            objective_function,
+            previous.goal,
+            rng,
+        );
+        optimizer.turtles = previous.turtles.clone();
+        optimizer.best_score = previous.best_score;
+        optimizer.best_position = previous.best_position.clone();
+        optimizer.max_iterations = previous.max_iterations;
+        optimizer.max_evaluations = previous.max_evaluations;
+        #[cfg(feature = "std")]
+        {
+            optimizer.max_duration = previous.max_duration;
+        }
+        optimizer.params = previous.params;
+        optimizer.inertia_schedule = previous.inertia_schedule;
+        optimizer.velocity_update = previous.velocity_update;
+        optimizer.v_max = previous.v_max;
+        optimizer.boundary_policy = previous.boundary_policy;
+        optimizer.topology = previous.topology;
+        optimizer.update_mode = previous.update_mode;
+        optimizer.direction = previous.direction;
+        optimizer.nan_policy = previous.nan_policy;
+        optimizer.integer_dimensions = previous.integer_dimensions.clone();
+        optimizer
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Minimizes or maximizes the objective from now on. The goal is read the same way, so a
+    /// maximizing run stops once the best score is at or above it. Bests that have not been scored
+    /// yet are reset to the new direction's worst score; set the direction before the first
+    /// evaluation, as scored bests are kept as they are.
+    pub fn set_direction(&mut self, direction: Direction) {
+        let unscored = self.direction.worst();
+        self.direction = direction;
         for turtle in self.turtles.iter_mut() {
-            let score = (self.objective_function)(&turtle.position);
-            if score < turtle.best_score {
-                turtle.best_score = score;
-                turtle.best_position = turtle.position.clone();
-                if score < self.best_score {
-                    self.best_score = score;
-                    self.best_position = turtle.position.clone();
-                }
+            if turtle.best_score == unscored {
+                turtle.best_score = direction.worst();
             }
         }
+        if self.best_score == unscored {
+            self.best_score = direction.worst();
+        }
     }
 
-    fn update_velocities(&mut self) {
+    /// Rescores every personal best with the current objective and rebuilds the overall best.
+    pub fn reevaluate_bests(&mut self) {
+        let unscored = self.direction.worst();
+        self.best_score = unscored;
         for turtle in self.turtles.iter_mut() {
-            // Here we deviate from Kennedy and Eberhart and omit social and personal motivation constants.
-            // We aren't sure exactly what motivates turtles so we favor neither in a stochastic sense.
-            turtle.velocity = &turtle.velocity
-                + TURTLE_VELOCITY * (&turtle.best_position - &turtle.position)
-                + TURTLE_VELOCITY * (&self.best_position - &turtle.position);
+            if turtle.best_score == unscored {
+                continue;
+            }
+            turtle.best_score = (self.objective_function)(&turtle.best_position);
+            self.evaluations += 1;
+            if self.direction.is_better(turtle.best_score, self.best_score) {
+                self.best_score = turtle.best_score;
+                self.best_position.clone_from(&turtle.best_position);
+            }
+        }
+    }
+
+    fn evaluate(&mut self) {
+        if self.noise.is_some() {
+            self.reevaluate_noisy_best();
+            for turtle in 0..self.turtles.len() {
+                self.evaluate_noisy(turtle);
+            }
+            return;
+        }
+        for turtle in 0..self.turtles.len() {
+            #[cfg(feature = "std")]
+            let start = self
+                .evaluation_log
+                .as_ref()
+                .map(|_| std::time::Instant::now());
+            #[cfg(feature = "std")]
+            let score = self.evaluate_catching(turtle);
+            #[cfg(not(feature = "std"))]
+            let score = Some((self.objective_function)(&self.turtles[turtle].position));
+            self.evaluations += 1;
+            // A panic scores the worst outright; it is not an invalid score.
+            let score = match score {
+                Some(score) => self.screen_score(turtle, score),
+                None => self.direction.worst(),
+            };
+            #[cfg(feature = "std")]
+            self.log_evaluation(turtle, score, start.map(|start| start.elapsed()));
+            self.record(turtle, score);
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn log_evaluation(&mut self, turtle: usize, score: f64, duration: Option<std::time::Duration>) {
+        if let Some(log) = self.evaluation_log.as_mut() {
+            log.write(
+                self.iterations,
+                turtle,
+                &self.turtles[turtle].position,
+                score,
+                duration,
+            );
+        }
+    }
+
+    /// The positions the turtles want scored next, one per turtle. Evaluate them however you like
+    /// and hand the scores back, in the same order, to `tell`.
+    pub fn ask(&self) -> Vec<Array1<f64>> {
+        self.turtles
+            .iter()
+            .map(|turtle| turtle.position.clone())
+            .collect()
+    }
+
+    /// Feeds back the scores of the positions returned by `ask` and advances the swarm by one
+    /// iteration. Returns why `optimize` would stop here, like `step`, so a loop evaluating
+    /// elsewhere knows when to stop asking.
+    pub fn tell(&mut self, scores: &[f64]) -> Option<Termination> {
+        assert_eq!(
+            scores.len(),
+            self.turtles.len(),
+            "tell expects one score per asked position"
+        );
+        for (turtle, score) in scores.iter().enumerate() {
+            #[cfg(feature = "std")]
+            self.log_evaluation(turtle, *score, None);
+            self.record(turtle, *score);
+        }
+        self.evaluations += scores.len();
+        self.advance();
+        self.termination()
+    }
+
+    /// Like `optimize`, but every position is scored by `objective` through `ask` and `tell`, and
+    /// the optimizer's own objective goes unused, so it may be `external_objective`. Unlike the
+    /// optimizer's own, `objective` need not be `Send` or `Sync`, which suits handles tied to one
+    /// thread like those of a JavaScript or Python host, and it may fail. An error ends the run.
+    pub fn optimize_with<E>(
+        &mut self,
+        mut objective: impl FnMut(&Array1<f64>) -> Result<f64, E>,
+    ) -> Result<OptimizationResult, E> {
+        loop {
+            if let Some(termination) = self.termination() {
+                return Ok(self.result(termination));
+            }
+            let scores = self
+                .ask()
+                .iter()
+                .map(&mut objective)
+                .collect::<Result<Vec<f64>, E>>()?;
+            self.tell(&scores);
+        }
+    }
+
+    // Updates the personal and global bests with the score of a turtle's current position.
+    fn record(&mut self, turtle: usize, score: f64) {
+        if let Some(scaling) = self.step_scaling.as_mut() {
+            scaling.observe(turtle, &self.turtles[turtle].position, score);
+        }
+        let turtle = &mut self.turtles[turtle];
+        turtle.stats.evaluations += 1;
+        if self.direction.is_better(score, turtle.best_score) {
+            self.successes += 1;
+            turtle.best_score = score;
+            // Copied into the allocations the bests already have rather than into new ones, see
+            // `benches/improvements.rs`.
+            turtle.best_position.clone_from(&turtle.position);
+            if self.direction.is_better(score, self.best_score) {
+                turtle.stats.improvements += 1;
+                self.best_score = score;
+                self.best_position.clone_from(&turtle.position);
+            }
+        }
+    }
+
+    fn update_velocities(&mut self) {
+        let mut params = self.params;
+        let success_rate = self.successes as f64 / self.turtles.len().max(1) as f64;
+        self.successes = 0;
+        params.inertia =
+            self.inertia_schedule
+                .inertia(params.inertia, self.iterations, success_rate);
+        // The constriction form is the inertia form with every term scaled by chi.
+        if self.velocity_update == VelocityUpdate::Constriction {
+            let chi = params.constriction();
+            params = OptimizerParams {
+                inertia: chi,
+                cognitive: chi * params.cognitive,
+                social: chi * params.social,
+            };
+        }
+        let limits = self.v_max.map(|limit| limit.limits(&self.boundaries));
+        let leaders = self
+            .topology
+            .leaders(&self.turtles, self.direction, &mut self.rng)
+            .map(|leaders| {
+                leaders
+                    .iter()
+                    .map(|leader| self.turtles[*leader].best_position.clone())
+                    .collect::<Vec<Array1<f64>>>()
+            });
+        for (index, turtle) in self.turtles.iter_mut().enumerate() {
+            let social = match &leaders {
+                None => &self.best_position,
+                Some(leaders) => &leaders[index],
+            };
+            // Here we deviate from Kennedy and Eberhart and omit stochastic social and personal
+            // motivation. We aren't sure exactly what motivates turtles so we favor neither in a
+            // stochastic sense.
+            let mut velocity = Array1::zeros(turtle.velocity.len());
+            ndarray::Zip::from(&mut velocity)
+                .and(&turtle.velocity)
+                .and(&turtle.position)
+                .and(&turtle.best_position)
+                .and(social)
+                .for_each(|new, &velocity, &position, &personal, &best| {
+                    *new = params.velocity(velocity, position, personal, best);
+                });
+            if let Some(limits) = &limits {
+                ndarray::Zip::from(&mut velocity)
+                    .and(limits)
+                    .for_each(|v, limit| *v = v.clamp(-limit, *limit));
+            }
+            match &self.active_dimensions {
+                None => turtle.velocity = velocity,
+                Some(active) => {
+                    for dimension in active.iter() {
+                        turtle.velocity[*dimension] = velocity[*dimension];
+                    }
+                }
+            }
         }
     }
 
     fn update_positions(&mut self) {
+        let scales = self.step_scaling.as_ref().map(|scaling| scaling.scales());
         for turtle in self.turtles.iter_mut() {
-            turtle.position = &turtle.position + &turtle.velocity;
-            for dimension in turtle.position.iter_mut() {
-                if *dimension > self.boundaries.upper {
-                    *dimension = self.boundaries.upper;
-                } else if *dimension < self.boundaries.lower {
-                    *dimension = self.boundaries.lower;
+            self.previous.clone_from(&turtle.position);
+            // Only scaled steps need an array of their own.
+            let scaled;
+            let step = match &scales {
+                None => &turtle.velocity,
+                Some(scales) => {
+                    scaled = &turtle.velocity * scales;
+                    &scaled
                 }
+            };
+            match &self.active_dimensions {
+                None => turtle.position += step,
+                Some(active) => {
+                    for dimension in active.iter() {
+                        turtle.position[*dimension] += step[*dimension];
+                    }
+                }
+            }
+            self.boundary_policy.apply(
+                &self.boundaries,
+                &mut turtle.position,
+                &mut turtle.velocity,
+                &mut self.rng,
+            );
+            if let Some(integer) = &self.integer_dimensions {
+                integer.round(&self.boundaries, &mut turtle.position, &mut self.rng);
+            }
+            let step = ndarray::Zip::from(&turtle.position)
+                .and(&self.previous)
+                .fold(0., |total, x, y| total + (x - y) * (x - y));
+            turtle.stats.displacement += sqrt(step);
+        }
+    }
+
+    fn choose_dimensions(&mut self) {
+        let shape = self.boundaries.dimensions();
+        self.active_dimensions = match self.update_mode {
+            UpdateMode::All => None,
+            UpdateMode::Cyclic { block } => {
+                let block = block.clamp(1, shape.max(1));
+                let start = self.iterations * block;
+                Some((start..start + block).map(|d| d % shape.max(1)).collect())
+            }
+            UpdateMode::Random { block } => {
+                Some(rand::seq::index::sample(&mut self.rng, shape, block.min(shape)).into_vec())
+            }
+        };
+    }
+
+    // Sends turtles that crawled into an archived basin to a fresh spot outside of the archive.
+    fn avoid_tabu(&mut self) {
+        if self.tabu.is_empty() {
+            return;
+        }
+        for turtle in 0..self.turtles.len() {
+            if self.tabu.contains(&self.turtles[turtle].position) {
+                self.turtles[turtle].position = self.untabu_position();
+                self.turtles[turtle].velocity =
+                    f64::EPSILON * Array1::ones(self.boundaries.dimensions());
             }
         }
     }
 
+    // A uniformly drawn position outside of every archived region. If the archive covers nearly
+    // everything the last draw is used anyway.
+    fn untabu_position(&mut self) -> Array1<f64> {
+        let mut position = self.boundaries.sample(&mut self.rng);
+        for _ in 0..100 {
+            if !self.tabu.contains(&position) {
+                break;
+            }
+            position = self.boundaries.sample(&mut self.rng);
+        }
+        position
+    }
+
+    /// Archives the basin around the best position within `radius` and scatters the turtles over
+    /// the rest of the space. Personal bests are forgotten; the overall best is kept.
+    pub fn restart(&mut self, radius: f64) {
+        self.tabu
+            .add(self.best_position.clone(), radius, self.best_score);
+        for turtle in 0..self.turtles.len() {
+            let position = self.untabu_position();
+            let turtle = &mut self.turtles[turtle];
+            turtle.position = position;
+            turtle.velocity = f64::EPSILON * Array1::ones(self.boundaries.dimensions());
+            turtle.best_score = self.direction.worst();
+            turtle.best_position = Array1::zeros(self.boundaries.dimensions());
+        }
+    }
+
     /// The optimize method iterates the TSO algorithm until the best observed score matches what the user
-    /// set as their goal.  
-    pub fn optimize(&mut self) {
-        // Instead of allowing the user of this library to terminate early based on the number of iterations
-        // we put the turtles to work until they reach our goal. No animals were harmed in the making of this.
-        while self.best_score > self.goal {
-            self.evaluate();
-            self.update_velocities();
-            self.update_positions();
-            self.iterations += 1;
+    /// set as their goal, or until `max_iterations` or `max_duration` have been spent when a limit
+    /// is set. A `stopping` criterion, when set, decides instead.
+    pub fn optimize(&mut self) -> OptimizationResult {
+        // Unless a limit is set we put the turtles to work until they reach our goal. No animals
+        // were harmed in the making of this.
+        #[cfg(feature = "tracing")]
+        let _span = self.run_span();
+        loop {
+            if let Some(termination) = self.termination() {
+                #[cfg(feature = "tracing")]
+                self.trace_termination(termination);
+                return self.result(termination);
+            }
+            self.iterate();
+        }
+    }
+
+    pub(crate) fn result(&self, termination: Termination) -> OptimizationResult {
+        OptimizationResult {
+            best_position: self.best_position.clone(),
+            best_score: self.best_score,
+            iterations: self.iterations,
+            evaluations: self.evaluations,
+            turtle_bests: self
+                .turtles
+                .iter()
+                .map(|turtle| (turtle.best_score, turtle.best_position.clone()))
+                .collect(),
+            termination,
+        }
+    }
+
+    // Why the run should stop now, if it should.
+    pub(crate) fn termination(&mut self) -> Option<Termination> {
+        if core::mem::take(&mut self.observer_stop) {
+            return Some(Termination::Observer);
+        }
+        if core::mem::take(&mut self.invalid_score) {
+            return Some(Termination::InvalidScore);
+        }
+        if let Some(reason) = self.check_stopping() {
+            return reason;
+        }
+        if self.direction.reaches(self.best_score, self.goal) {
+            Some(Termination::GoalReached)
+        } else if self
+            .max_iterations
+            .is_some_and(|limit| self.iterations >= limit)
+        {
+            Some(Termination::IterationLimit)
+        } else if self
+            .max_evaluations
+            .is_some_and(|limit| self.evaluations >= limit)
+        {
+            Some(Termination::EvaluationLimit)
+        } else {
+            #[cfg(feature = "std")]
+            if self.out_of_time() {
+                return Some(Termination::TimeLimit);
+            }
+            None
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn out_of_time(&mut self) -> bool {
+        let Some(limit) = self.max_duration else {
+            return false;
+        };
+        let started = *self.started.get_or_insert_with(std::time::Instant::now);
+        started.elapsed() >= limit
+    }
+
+    /// Runs a single iteration, scoring the turtles and moving them once, for driving the loop
+    /// yourself. Returns why `optimize` would stop here, or `None` while it would carry on.
+    pub fn step(&mut self) -> Option<Termination> {
+        self.iterate();
+        self.termination()
+    }
+
+    // A single pass of the TSO algorithm, shared by `optimize` and the budgeted drivers in this
+    // crate.
+    pub(crate) fn iterate(&mut self) {
+        #[cfg(feature = "tracing")]
+        let _span = self.iteration_span();
+        self.evaluate();
+        self.advance();
+    }
+
+    /// The mean distance of the turtles from their centroid, which shrinks as the swarm
+    /// converges.
+    pub fn diversity(&self) -> f64 {
+        if self.turtles.is_empty() {
+            return 0.;
+        }
+        let count = self.turtles.len() as f64;
+        let mut centroid = Array1::zeros(self.boundaries.dimensions());
+        for turtle in self.turtles.iter() {
+            centroid += &turtle.position;
+        }
+        centroid /= count;
+        self.turtles
+            .iter()
+            .map(|turtle| {
+                let offset = &turtle.position - &centroid;
+                sqrt(offset.dot(&offset))
+            })
+            .sum::<f64>()
+            / count
+    }
+
+    // Moves the turtles once their current positions have been scored.
+    fn advance(&mut self) {
+        self.run_middleware(Phase::PostEvaluate);
+        self.choose_dimensions();
+        self.update_velocities();
+        self.update_positions();
+        if let Some(deduplication) = self.deduplication.as_mut() {
+            deduplication.spread(&mut self.turtles, &self.boundaries, &mut self.rng);
+        }
+        self.avoid_tabu();
+        self.check_stagnation();
+        self.run_middleware(Phase::PostUpdate);
+        self.iterations += 1;
+        if let Some(history) = self.history.as_mut() {
+            history.record(self.iterations, self.best_score, &self.best_position);
+        }
+        #[cfg(feature = "std")]
+        if let Some(watch) = &self.watch {
+            watch.publish(self);
+        }
+        #[cfg(feature = "tracing")]
+        self.trace_iteration();
+        self.notify_observers();
+    }
+
+    // Iterates until the goal is reached or the optimizer has spent `iterations` in total.
+    #[cfg(feature = "std")]
+    pub(crate) fn advance_to(&mut self, iterations: usize) {
+        while self.iterations < iterations && !self.direction.reaches(self.best_score, self.goal) {
+            self.iterate();
         }
     }
 
-    /// Reports the results of a completed optimization to stdout.
+    /// Reports the results of a completed optimization to stdout, or as `tracing` events with
+    /// the `tracing` feature, see `telemetry`.
+    #[cfg(feature = "std")]
     pub fn report(&self) {
-        println!(
+        #[cfg(feature = "tracing")]
+        self.trace_report();
+        #[cfg(not(feature = "tracing"))]
+        self.report_to(std::io::stdout().lock())
+            .expect("the report is printed to stdout");
+    }
+
+    /// Writes the report `report` prints to `writer` instead, such as a file or a buffer.
+    #[cfg(feature = "std")]
+    pub fn report_to<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writeln!(
+            writer,
             "{} turtles performed {} optimizer iterations for you.",
             self.turtles.len(),
             self.iterations
-        );
-        println!(
+        )?;
+        writeln!(
+            writer,
+            "The objective was evaluated {} times.",
+            self.evaluations
+        )?;
+        writeln!(
+            writer,
             "The best score: {} was observed at position: {}",
             self.best_score, self.best_position
-        );
-        println!("Below is a complete run down of the best locations: ");
+        )?;
+        writeln!(
+            writer,
+            "Below is a complete run down of the best locations: "
+        )?;
 
         for (turtle_number, turtle) in self.turtles.iter().enumerate() {
-            println!(
+            writeln!(
+                writer,
                 "\t Turtle #{}'s best score {}, was observed at {} ",
                 turtle_number, turtle.best_score, turtle.best_position
-            );
+            )?;
+            writeln!(
+                writer,
+                "\t\t It was scored {} times, improved the best score {} times and crawled {}",
+                turtle.stats.evaluations, turtle.stats.improvements, turtle.stats.displacement
+            )?;
         }
+        if let Some(deduplication) = &self.deduplication {
+            writeln!(
+                writer,
+                "{} turtles sharing a position were nudged apart.",
+                deduplication.perturbed
+            )?;
+        }
+        Ok(())
+    }
+
+    /// The best score and position, the iterations and evaluations spent and every turtle's
+    /// personal best as JSON, for tooling downstream.
+    #[cfg(feature = "serde")]
+    pub fn report_json(&self) -> serde_json::Value {
+        let turtles: Vec<serde_json::Value> = self
+            .turtles
+            .iter()
+            .map(|turtle| {
+                serde_json::json!({
+                    "best_score": turtle.best_score,
+                    "best_position": turtle.best_position.to_vec(),
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "best_score": self.best_score,
+            "best_position": self.best_position.to_vec(),
+            "iterations": self.iterations,
+            "evaluations": self.evaluations,
+            "turtles": turtles,
+        })
     }
 }
 
@@ -174,12 +1251,12 @@ mod tests {
             (x * x).sum()
         }
         let boundaries = CubicBoundary::new(1, -1., 1.);
-        let mut optimizer = Optimizer::new(33, boundaries, &parabola, 1e-2);
+        let mut optimizer = Optimizer::new_seeded(33, boundaries, &parabola, 1e-2, 1);
 
         optimizer.evaluate();
 
         for turtle in optimizer.turtles.iter() {
-            assert_ne!(turtle.best_score, INFINITY);
+            assert_ne!(turtle.best_score, f64::INFINITY);
         }
 
         optimizer.update_velocities();
@@ -194,4 +1271,324 @@ mod tests {
         optimizer.optimize();
         optimizer.report();
     }
+
+    #[test]
+    fn coefficients_set_the_pace_of_the_swarm() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut turtles = Optimizer::new_seeded(10, boundaries, &parabola, 1e-4, 3);
+        let mut particles = Optimizer::new_seeded(10, boundaries, &parabola, 1e-4, 3);
+        particles.params = OptimizerParams {
+            inertia: 0.5,
+            cognitive: 0.7,
+            social: 0.7,
+        };
+        turtles.max_iterations = Some(200);
+        particles.max_iterations = Some(200);
+
+        assert_eq!(turtles.optimize().termination, Termination::IterationLimit);
+        assert_eq!(particles.optimize().termination, Termination::GoalReached);
+    }
+
+    #[test]
+    fn runs_stop_when_their_time_is_up() {
+        let slow = |x: &Array1<f64>| {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            x.mapv(|x| x * x).sum()
+        };
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(5, boundaries, &slow, -1., 3);
+        optimizer.max_duration = Some(std::time::Duration::from_millis(50));
+        let start = std::time::Instant::now();
+        let result = optimizer.optimize();
+
+        assert_eq!(result.termination, Termination::TimeLimit);
+        assert!(result.iterations > 0 && result.best_score.is_finite());
+        assert!(start.elapsed() < std::time::Duration::from_secs(1));
+    }
+
+    #[test]
+    fn runs_stop_when_their_evaluations_are_spent() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &parabola, -1., 3);
+        optimizer.max_evaluations = Some(95);
+        let result = optimizer.optimize();
+        assert_eq!(result.termination, Termination::EvaluationLimit);
+        assert_eq!((result.iterations, result.evaluations), (10, 100));
+
+        // Replicated evaluations count once each.
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &parabola, -1., 3);
+        optimizer.noise = Some(noise::NoiseHandling::new(3));
+        optimizer.max_iterations = Some(4);
+        let result = optimizer.optimize();
+        assert_eq!(result.evaluations, 120);
+        assert!(result.to_string().contains("evaluated 120 times"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn reports_come_as_json() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(3, boundaries, &parabola, -1., 3);
+        optimizer.max_iterations = Some(2);
+        optimizer.optimize();
+
+        let report = optimizer.report_json();
+        assert_eq!(report["iterations"], 2);
+        assert_eq!(report["evaluations"], 6);
+        assert_eq!(report["best_score"], optimizer.best_score);
+        assert_eq!(report["best_position"][1], optimizer.best_position[1]);
+        assert_eq!(report["turtles"].as_array().unwrap().len(), 3);
+        assert_eq!(
+            report["turtles"][2]["best_score"],
+            optimizer.turtles[2].best_score
+        );
+    }
+
+    #[test]
+    fn stepping_retraces_optimize() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut stepped = Optimizer::new_seeded(5, boundaries, &parabola, 1e-3, 7);
+        let mut optimized = Optimizer::new_seeded(5, boundaries, &parabola, 1e-3, 7);
+        stepped.max_iterations = Some(30);
+        optimized.max_iterations = Some(30);
+
+        let mut steps = 0;
+        let termination = loop {
+            steps += 1;
+            if let Some(termination) = stepped.step() {
+                break termination;
+            }
+        };
+        let result = optimized.optimize();
+        assert_eq!(termination, result.termination);
+        assert_eq!(steps, result.iterations);
+        assert_eq!(stepped.best_position, result.best_position);
+    }
+
+    #[test]
+    fn reports_can_be_captured() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(3, boundaries, &parabola, -1., 3);
+        optimizer.max_iterations = Some(2);
+        let result = optimizer.optimize();
+
+        let mut report = Vec::new();
+        optimizer.report_to(&mut report).unwrap();
+        let report = String::from_utf8(report).unwrap();
+        assert!(report.starts_with("3 turtles performed 2 optimizer iterations for you."));
+        assert_eq!(report.matches("Turtle #").count(), 3);
+        let summary = result.to_string();
+        assert!(summary.contains("until the iteration limit was spent"));
+        assert!(summary.contains(&format!("The best score: {}", result.best_score)));
+    }
+
+    #[test]
+    fn velocities_keep_under_the_limit() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = boundary::RectangularBoundary::from_bounds(&[(-1., 1.), (-10., 10.)]);
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &parabola, -1., 3);
+        optimizer.params = OptimizerParams {
+            inertia: 1.,
+            cognitive: 5.,
+            social: 5.,
+        };
+        optimizer.v_max = Some(VelocityLimit::Fraction(0.1));
+        for _ in 0..10 {
+            optimizer.iterate();
+            for turtle in optimizer.turtles.iter() {
+                assert!(turtle.velocity[0].abs() <= 0.2 && turtle.velocity[1].abs() <= 2.);
+            }
+        }
+        assert!(optimizer
+            .turtles
+            .iter()
+            .any(|turtle| turtle.velocity[1].abs() == 2.));
+        assert_eq!(
+            VelocityLimit::Absolute(-0.5).limits(&optimizer.boundaries),
+            Array1::from_elem(2, 0.5)
+        );
+    }
+
+    #[test]
+    fn constricted_turtles_settle_down() {
+        assert!((OptimizerParams::clerc().constriction() - 0.7298).abs() < 1e-4);
+        assert_eq!(OptimizerParams::default().constriction(), 1.);
+
+        let parabola = |x: &Array1<f64>| x.mapv(|x| (x - 0.3) * (x - 0.3)).sum();
+        let boundaries = CubicBoundary::new(3, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &parabola, 1e-8, 5);
+        optimizer.params = OptimizerParams::clerc();
+        optimizer.velocity_update = VelocityUpdate::Constriction;
+        optimizer.max_iterations = Some(300);
+        assert_eq!(optimizer.optimize().termination, Termination::GoalReached);
+        let speed = |turtle: &Turtle| turtle.velocity.mapv(|v| v * v).sum();
+        assert!(optimizer.turtles.iter().all(|turtle| speed(turtle) < 1e-2));
+    }
+
+    #[test]
+    fn seeded_runs_repeat_every_random_choice() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let run = |seed: u64| {
+            let boundaries = CubicBoundary::new(4, -1., 1.);
+            let mut optimizer = Optimizer::new_seeded(6, boundaries, &parabola, -1., seed);
+            optimizer.update_mode = UpdateMode::Random { block: 2 };
+            optimizer.deduplication = Some(duplicates::Deduplication::default());
+            for _ in 0..5 {
+                optimizer.iterate();
+            }
+            optimizer.restart(0.1);
+            optimizer.iterate();
+            optimizer.ask()
+        };
+        assert_eq!(run(11), run(11));
+        assert_ne!(run(11), run(12));
+    }
+
+    #[test]
+    fn maximizing_runs_climb_to_their_goal() {
+        let hill = |x: &Array1<f64>| 1. - x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &hill, 0.9, 3);
+        optimizer.params = OptimizerParams {
+            inertia: 0.5,
+            cognitive: 0.7,
+            social: 0.7,
+        };
+        optimizer.set_direction(Direction::Maximize);
+        assert_eq!(optimizer.best_score, f64::NEG_INFINITY);
+        optimizer.max_iterations = Some(200);
+
+        let result = optimizer.optimize();
+        assert_eq!(result.termination, Termination::GoalReached);
+        assert!(result.best_score >= 0.9);
+        assert!(result
+            .turtle_bests
+            .iter()
+            .all(|(score, _)| *score <= result.best_score));
+    }
+
+    #[test]
+    fn unreachable_goals_stop_at_the_iteration_limit() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(5, boundaries, &parabola, -1., 8);
+        optimizer.max_iterations = Some(25);
+        let result = optimizer.optimize();
+        assert_eq!(result.termination, Termination::IterationLimit);
+        assert_eq!(result.iterations, 25);
+        assert_eq!(result.best_score, optimizer.best_score);
+        assert_eq!(result.best_position, optimizer.best_position);
+        assert_eq!(result.turtle_bests.len(), 5);
+        assert!(result
+            .turtle_bests
+            .iter()
+            .all(|(score, position)| *score == parabola(position)));
+
+        optimizer.goal = 1.;
+        assert_eq!(optimizer.optimize().termination, Termination::GoalReached);
+        assert_eq!(optimizer.iterations, 25);
+    }
+
+    #[test]
+    fn ask_and_tell_match_internal_evaluation() {
+        fn parabola(x: &Array1<f64>) -> f64 {
+            (x * x).sum()
+        }
+        let boundaries = CubicBoundary::new(3, -1., 1.);
+        let mut internal = Optimizer::new_seeded(6, boundaries, &parabola, -1., 9);
+        let mut external = Optimizer::new_seeded(6, boundaries, &external_objective, -1., 9);
+        external.max_iterations = Some(3);
+
+        for iteration in 1..=3 {
+            internal.iterate();
+            let scores: Vec<f64> = external.ask().iter().map(parabola).collect();
+            let termination = external.tell(&scores);
+            assert_eq!(termination.is_some(), iteration == 3);
+        }
+        assert_eq!(internal.iterations, external.iterations);
+        assert_eq!(internal.best_score, external.best_score);
+        assert_eq!(internal.best_position, external.best_position);
+    }
+
+    #[test]
+    fn warm_starts_carry_the_swarm_into_a_new_objective() {
+        fn parabola(x: &Array1<f64>) -> f64 {
+            (x * x).sum()
+        }
+        fn shifted(x: &Array1<f64>) -> f64 {
+            (x - 0.1).mapv(|x| x * x).sum()
+        }
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut previous = Optimizer::new_seeded(6, boundaries, &parabola, -1., 8);
+        for _ in 0..3 {
+            previous.iterate();
+        }
+
+        let mut optimizer = Optimizer::from_previous(&previous, &shifted);
+        assert_eq!(optimizer.iterations, 0);
+        assert_eq!(optimizer.best_score, previous.best_score);
+        optimizer.reevaluate_bests();
+        for (old, turtle) in previous.turtles.iter().zip(optimizer.turtles.iter()) {
+            assert_eq!(old.position, turtle.position);
+            assert_eq!(turtle.best_score, shifted(&old.best_position));
+        }
+        let best = optimizer.turtles.iter().map(|turtle| turtle.best_score);
+        assert_eq!(optimizer.best_score, best.fold(f64::INFINITY, f64::min));
+    }
+
+    #[test]
+    fn turtles_keep_track_of_their_contributions() {
+        fn parabola(x: &Array1<f64>) -> f64 {
+            (x * x).sum()
+        }
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(5, boundaries, &parabola, -1., 6);
+        let mut crawled = vec![0.; 5];
+        for _ in 0..4 {
+            let before = optimizer.ask();
+            optimizer.iterate();
+            for (turtle, (old, new)) in before.iter().zip(optimizer.ask()).enumerate() {
+                crawled[turtle] += (&new - old).mapv(|x| x * x).sum().sqrt();
+            }
+        }
+
+        let stats: Vec<TurtleStats> = optimizer
+            .turtles
+            .iter()
+            .map(|turtle| turtle.stats)
+            .collect();
+        assert!(stats.iter().all(|stats| stats.evaluations == 4));
+        assert!(stats.iter().map(|stats| stats.improvements).sum::<usize>() >= 1);
+        for (stats, crawled) in stats.iter().zip(crawled) {
+            assert!((stats.displacement - crawled).abs() < 1e-18);
+            assert!(stats.displacement > 0.);
+        }
+    }
+
+    #[test]
+    fn cyclic_updates_move_one_dimension_at_a_time() {
+        fn parabola(x: &Array1<f64>) -> f64 {
+            (x * x).sum()
+        }
+        let boundaries = CubicBoundary::new(3, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(4, boundaries, &parabola, -1., 2);
+        optimizer.update_mode = UpdateMode::Cyclic { block: 1 };
+
+        for iteration in 0..4 {
+            let before = optimizer.ask();
+            optimizer.iterate();
+            for (old, turtle) in before.iter().zip(optimizer.turtles.iter()) {
+                for dimension in 0..3 {
+                    if dimension != iteration % 3 {
+                        assert_eq!(old[dimension], turtle.position[dimension]);
+                    }
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,220 @@
+//! A swarm stored as matrices, one row per turtle.
+//!
+//! `Optimizer` keeps every turtle as a `Turtle` of its own, which suits the many hooks that look
+//! at or move single turtles, but costs a handful of small allocations per turtle and iteration.
+//! A `MatrixOptimizer` keeps the positions, velocities and personal bests of the whole swarm in
+//! three `Array2`s instead, and moves all of it with whole-matrix expressions that allocate
+//! nothing. The objective still sees an `Array1`, a single buffer every row is copied into in
+//! turn.
+//!
+//! The update is that of an `Optimizer` left at its defaults, the inertia form with the star
+//! topology. After moving, each row goes through the buffer to `Boundary::project` and back, so
+//! any boundary keeps its turtles, a `BoxUnion` included, at the cost of two copies per turtle.
+//!
+//! The update has no BLAS-friendly form. Every coordinate of a velocity depends only on the same
+//! coordinate of the turtle's position, its personal best and the swarm's best, so the whole
+//! update is elementwise, with no matrix product or other level 2 or 3 operation a BLAS backend
+//! could take over. `benches/updates.rs` times it against the per-turtle optimizer.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ndarray::{Array1, Array2, Axis, Zip};
+#[cfg(feature = "std")]
+use rand::thread_rng;
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+
+use crate::boundary::Boundary;
+use crate::objective::ObjectiveFunction;
+use crate::{CubicBoundary, Direction, OptimizationResult, OptimizerParams, Termination};
+
+pub struct MatrixOptimizer<'a, B = CubicBoundary> {
+    /// Where every turtle is, one row per turtle.
+    pub positions: Array2<f64>,
+    velocities: Array2<f64>,
+    /// Every turtle's best score, in turtle order.
+    pub best_scores: Array1<f64>,
+    /// Where every turtle found its best score, one row per turtle.
+    pub best_positions: Array2<f64>,
+    pub boundaries: B,
+    pub iterations: usize,
+    /// How many times the objective has been evaluated.
+    pub evaluations: usize,
+    pub best_score: f64,
+    pub best_position: Array1<f64>,
+    pub objective_function: ObjectiveFunction<'a>,
+    pub goal: f64,
+    /// The most iterations `optimize` may spend before giving up on the goal.
+    pub max_iterations: Option<usize>,
+    pub params: OptimizerParams,
+    // The row handed to the objective and the boundary.
+    buffer: Array1<f64>,
+}
+
+impl<'a, B: Boundary> MatrixOptimizer<'a, B> {
+    #[cfg(feature = "std")]
+    pub fn new(
+        turtles: usize,
+        boundaries: B,
+        objective_function: impl Into<ObjectiveFunction<'a>>,
+        goal: f64,
+    ) -> Self {
+        let rng = ChaCha12Rng::from_rng(thread_rng()).expect("the rng provides a seed");
+        Self::with_rng(turtles, boundaries, objective_function.into(), goal, rng)
+    }
+
+    /// Like `new`, but the turtles are placed reproducibly from `seed`.
+    pub fn new_seeded(
+        turtles: usize,
+        boundaries: B,
+        objective_function: impl Into<ObjectiveFunction<'a>>,
+        goal: f64,
+        seed: u64,
+    ) -> Self {
+        let rng = ChaCha12Rng::seed_from_u64(seed);
+        Self::with_rng(turtles, boundaries, objective_function.into(), goal, rng)
+    }
+
+    fn with_rng(
+        turtles: usize,
+        boundaries: B,
+        objective_function: ObjectiveFunction<'a>,
+        goal: f64,
+        mut rng: ChaCha12Rng,
+    ) -> Self {
+        let shape = boundaries.dimensions();
+        let mut positions = Array2::zeros((turtles, shape));
+        for mut row in positions.axis_iter_mut(Axis(0)) {
+            row.assign(&boundaries.sample(&mut rng));
+        }
+        Self {
+            positions,
+            velocities: Array2::from_elem((turtles, shape), f64::EPSILON),
+            best_scores: Array1::from_elem(turtles, f64::INFINITY),
+            best_positions: Array2::zeros((turtles, shape)),
+            boundaries,
+            iterations: 0,
+            evaluations: 0,
+            best_score: f64::INFINITY,
+            best_position: Array1::zeros(shape),
+            objective_function,
+            goal,
+            max_iterations: None,
+            params: OptimizerParams::default(),
+            buffer: Array1::zeros(shape),
+        }
+    }
+
+    /// Iterates until the best score reaches the goal or `max_iterations` is spent.
+    pub fn optimize(&mut self) -> OptimizationResult {
+        loop {
+            if let Some(termination) = self.termination() {
+                return self.result(termination);
+            }
+            self.iterate();
+        }
+    }
+
+    /// Moves the turtles once. Returns why `optimize` would stop here, if it would.
+    pub fn step(&mut self) -> Option<Termination> {
+        self.iterate();
+        self.termination()
+    }
+
+    fn termination(&self) -> Option<Termination> {
+        if Direction::Minimize.reaches(self.best_score, self.goal) {
+            return Some(Termination::GoalReached);
+        }
+        self.max_iterations
+            .filter(|limit| self.iterations >= *limit)
+            .map(|_| Termination::IterationLimit)
+    }
+
+    fn result(&self, termination: Termination) -> OptimizationResult {
+        OptimizationResult {
+            best_position: self.best_position.clone(),
+            best_score: self.best_score,
+            iterations: self.iterations,
+            evaluations: self.evaluations,
+            turtle_bests: self
+                .best_scores
+                .iter()
+                .zip(self.best_positions.axis_iter(Axis(0)))
+                .map(|(score, position)| (*score, position.to_owned()))
+                .collect::<Vec<(f64, Array1<f64>)>>(),
+            termination,
+        }
+    }
+
+    fn evaluate(&mut self) {
+        for turtle in 0..self.positions.nrows() {
+            let position = self.positions.row(turtle);
+            self.buffer.assign(&position);
+            let score = (self.objective_function)(&self.buffer);
+            self.evaluations += 1;
+            if score < self.best_scores[turtle] {
+                self.best_scores[turtle] = score;
+                self.best_positions.row_mut(turtle).assign(&position);
+                if score < self.best_score {
+                    self.best_score = score;
+                    self.best_position.assign(&position);
+                }
+            }
+        }
+    }
+
+    fn update(&mut self) {
+        let params = self.params;
+        Zip::from(&mut self.velocities)
+            .and(&self.positions)
+            .and(&self.best_positions)
+            .and_broadcast(&self.best_position)
+            .for_each(|velocity, &position, &personal, &best| {
+                *velocity = params.velocity(*velocity, position, personal, best);
+            });
+        self.positions += &self.velocities;
+        for mut row in self.positions.axis_iter_mut(Axis(0)) {
+            self.buffer.assign(&row);
+            self.boundaries.project(&mut self.buffer);
+            row.assign(&self.buffer);
+        }
+    }
+
+    pub(crate) fn iterate(&mut self) {
+        self.evaluate();
+        self.update();
+        self.iterations += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boundary::BoxUnion;
+    use ndarray::array;
+
+    #[test]
+    fn rows_are_projected_into_the_boundary() {
+        // The unconstrained minimum lies in the gap between the boxes, so every turtle is pulled
+        // towards it and has to be projected back out.
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let union = BoxUnion::new(vec![
+            (array![-2., -2.], array![-1., -1.]),
+            (array![1., 1.], array![2., 2.]),
+        ]);
+        let mut matrix = MatrixOptimizer::new_seeded(20, union.clone(), &parabola, 2. + 1e-9, 7);
+        matrix.params = OptimizerParams {
+            inertia: 0.5,
+            cognitive: 0.7,
+            social: 0.7,
+        };
+        matrix.max_iterations = Some(500);
+        let result = matrix.optimize();
+
+        assert_eq!(result.termination, Termination::GoalReached);
+        assert!(matrix
+            .positions
+            .axis_iter(Axis(0))
+            .all(|row| union.contains(&row.to_owned())));
+    }
+}
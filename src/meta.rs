@@ -0,0 +1,309 @@
+//! Meta-optimization of the TSO's own hyperparameters.
+//!
+//! A second, outer swarm of turtles searches the hyperparameter space. Every outer turtle's
+//! position decodes into a [`Configuration`], which is scored by running short inner
+//! optimizations on the user's problems and averaging the best scores they reach.
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+use ndarray::Array1;
+
+use crate::inertia::InertiaSchedule;
+use crate::{CubicBoundary, Optimizer, OptimizerParams, Problem};
+
+/// The ranges each tunable hyperparameter may take.
+#[derive(Clone, Debug)]
+pub struct SearchSpace {
+    pub turtles: (usize, usize),
+    pub inertia: (f64, f64),
+    pub cognitive: (f64, f64),
+    pub social: (f64, f64),
+    /// The inertia schedules to pick from, `Constant` alone when empty. Only `Constant` uses the
+    /// tuned inertia.
+    pub schedules: Vec<Schedule>,
+}
+
+impl Default for SearchSpace {
+    fn default() -> Self {
+        Self {
+            turtles: (4, 64),
+            inertia: (0., 1.),
+            cognitive: (0., 2.),
+            social: (0., 2.),
+            schedules: vec![
+                Schedule::Constant,
+                Schedule::Linear,
+                Schedule::Chaotic,
+                Schedule::SuccessRate,
+            ],
+        }
+    }
+}
+
+impl SearchSpace {
+    // The outer swarm searches the unit cube, one dimension per hyperparameter.
+    const DIMENSIONS: usize = 5;
+
+    fn decode(&self, position: &Array1<f64>) -> Configuration {
+        let (lower, upper) = self.turtles;
+        let (lower, upper) = (lower.min(upper).max(1), lower.max(upper).max(1));
+        let span = (upper - lower) as f64;
+        let within = |(lower, upper): (f64, f64), x: f64| lower + (upper - lower) * x;
+        // A coordinate of exactly 1 would pick one past the last schedule.
+        let choice = (position[4] * self.schedules.len() as f64) as usize;
+        Configuration {
+            turtles: lower + (position[0] * span).round() as usize,
+            params: OptimizerParams {
+                inertia: within(self.inertia, position[1]),
+                cognitive: within(self.cognitive, position[2]),
+                social: within(self.social, position[3]),
+            },
+            schedule: self
+                .schedules
+                .get(choice.min(self.schedules.len().saturating_sub(1)))
+                .copied()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// An inertia schedule the search may pick, stretched over however many iterations the inner
+/// runs get.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Schedule {
+    #[default]
+    Constant,
+    Linear,
+    Chaotic,
+    SuccessRate,
+}
+
+impl Schedule {
+    fn inertia_schedule(self, iterations: usize) -> InertiaSchedule {
+        match self {
+            Schedule::Constant => InertiaSchedule::Constant,
+            Schedule::Linear => InertiaSchedule::linear(iterations),
+            Schedule::Chaotic => InertiaSchedule::chaotic(iterations),
+            Schedule::SuccessRate => InertiaSchedule::success_rate(),
+        }
+    }
+}
+
+/// One point in the hyperparameter space.
+#[derive(Clone, Copy, Debug)]
+pub struct Configuration {
+    pub turtles: usize,
+    pub params: OptimizerParams,
+    pub schedule: Schedule,
+}
+
+impl Configuration {
+    /// Builds an optimizer for `problem` that uses this configuration, with its inertia schedule
+    /// spread over `iterations` and its turtles placed from `seed`.
+    pub fn build<'a>(
+        &self,
+        problem: &Problem<'a>,
+        goal: f64,
+        iterations: usize,
+        seed: u64,
+    ) -> Optimizer<'a> {
+        let mut optimizer = Optimizer::new_seeded(
+            self.turtles,
+            problem.boundaries,
+            problem.objective_function,
+            goal,
+            seed,
+        );
+        optimizer.params = self.params;
+        optimizer.inertia_schedule = self.schedule.inertia_schedule(iterations);
+        optimizer
+    }
+
+    // Configurations are cached by the exact bits of their coefficients.
+    fn key(&self) -> (usize, [u64; 3], Schedule) {
+        let params = self.params;
+        let bits = [params.inertia, params.cognitive, params.social].map(f64::to_bits);
+        (self.turtles, bits, self.schedule)
+    }
+}
+
+impl PartialEq for Configuration {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Configuration {}
+
+impl Hash for Configuration {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+/// The best configuration found and the mean best score it achieved on the problem set.
+#[derive(Clone, Copy, Debug)]
+pub struct MetaResult {
+    pub configuration: Configuration,
+    pub score: f64,
+}
+
+/// Tunes the TSO's hyperparameters on a set of problems.
+pub struct MetaOptimizer<'a> {
+    pub problems: Vec<Problem<'a>>,
+    pub search_space: SearchSpace,
+    /// Objective evaluations each inner run may spend. Larger swarms get fewer iterations, so
+    /// configurations are compared at equal cost.
+    pub evaluations: usize,
+    /// Inner runs per problem, averaged to smooth out random initialization.
+    pub repetitions: usize,
+    /// Size of the outer swarm.
+    pub turtles: usize,
+    /// Iterations of the outer swarm.
+    pub iterations: usize,
+    /// The outer swarm's coefficients. The optimizer's defaults barely move a turtle, which would
+    /// leave the outer swarm sampling little beyond where it started.
+    pub params: OptimizerParams,
+    /// Places the outer turtles and every inner run, so tuning the same problems twice gives the
+    /// same configuration.
+    pub seed: u64,
+}
+
+impl<'a> MetaOptimizer<'a> {
+    pub fn new(problems: Vec<Problem<'a>>) -> Self {
+        Self {
+            problems,
+            search_space: SearchSpace::default(),
+            evaluations: 1_000,
+            repetitions: 3,
+            turtles: 8,
+            iterations: 4,
+            params: OptimizerParams {
+                inertia: 0.7,
+                cognitive: 1.5,
+                social: 1.5,
+            },
+            seed: 0,
+        }
+    }
+
+    /// Mean best score of `configuration` across every problem and repetition. Every repetition of
+    /// every problem has a seed of its own, shared by all configurations so they start alike.
+    pub fn score(&self, configuration: &Configuration) -> f64 {
+        let iterations = (self.evaluations / configuration.turtles).max(1);
+        let repetitions = self.repetitions.max(1);
+        let mut total = 0.0;
+        for (index, problem) in self.problems.iter().enumerate() {
+            for repetition in 0..repetitions {
+                let seed = self
+                    .seed
+                    .wrapping_add((index * repetitions + repetition) as u64);
+                let mut inner = configuration.build(problem, f64::NEG_INFINITY, iterations, seed);
+                inner.advance_to(iterations);
+                total += inner.best_score;
+            }
+        }
+        total / (self.problems.len() * repetitions) as f64
+    }
+
+    /// Runs the outer swarm and returns the best configuration it found.
+    pub fn tune(&self) -> MetaResult {
+        // Turtles pushed against the same faces of the cube decode to the same configuration.
+        let cache: Mutex<HashMap<Configuration, f64>> = Mutex::new(HashMap::new());
+        let meta_objective = |position: &Array1<f64>| {
+            let configuration = self.search_space.decode(position);
+            if let Some(score) = cache.lock().unwrap().get(&configuration) {
+                return *score;
+            }
+            let score = self.score(&configuration);
+            cache.lock().unwrap().insert(configuration, score);
+            score
+        };
+
+        let mut outer = self.outer(&meta_objective);
+        outer.advance_to(self.iterations.max(1));
+
+        MetaResult {
+            configuration: self.search_space.decode(&outer.best_position),
+            score: outer.best_score,
+        }
+    }
+
+    fn outer<'b>(&self, meta_objective: &'b (dyn Fn(&Array1<f64>) -> f64 + Sync)) -> Optimizer<'b> {
+        let boundaries = CubicBoundary::new(SearchSpace::DIMENSIONS, 0., 1.);
+        let mut outer = Optimizer::new_seeded(
+            self.turtles.max(1),
+            boundaries,
+            meta_objective,
+            f64::NEG_INFINITY,
+            self.seed,
+        );
+        outer.params = self.params;
+        outer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tuned_configuration_is_inside_the_search_space() {
+        fn parabola(x: &Array1<f64>) -> f64 {
+            (x * x).sum()
+        }
+        let problem = Problem {
+            objective_function: &parabola,
+            boundaries: CubicBoundary::new(2, -1., 1.),
+        };
+        let mut meta = MetaOptimizer::new(vec![problem]);
+        meta.search_space = SearchSpace {
+            turtles: (2, 10),
+            inertia: (0.4, 0.9),
+            schedules: vec![Schedule::Constant, Schedule::Linear],
+            ..SearchSpace::default()
+        };
+        meta.evaluations = 40;
+        meta.repetitions = 1;
+        meta.turtles = 4;
+        meta.iterations = 2;
+
+        let result = meta.tune();
+        let configuration = result.configuration;
+        assert!((2..=10).contains(&configuration.turtles));
+        assert!((0.4..=0.9).contains(&configuration.params.inertia));
+        assert!((0. ..=2.).contains(&configuration.params.social));
+        assert_ne!(configuration.schedule, Schedule::Chaotic);
+        assert!(result.score.is_finite());
+    }
+
+    #[test]
+    fn tuning_improves_on_the_first_samples() {
+        fn parabola(x: &Array1<f64>) -> f64 {
+            (x * x).sum()
+        }
+        let problem = Problem {
+            objective_function: &parabola,
+            boundaries: CubicBoundary::new(2, -1., 1.),
+        };
+        let mut meta = MetaOptimizer::new(vec![problem]);
+        meta.evaluations = 100;
+        meta.repetitions = 1;
+        meta.turtles = 4;
+        meta.iterations = 10;
+        meta.seed = 7;
+
+        let unused = |_: &Array1<f64>| 0.;
+        let first = meta
+            .outer(&unused)
+            .turtles
+            .iter()
+            .map(|turtle| meta.score(&meta.search_space.decode(&turtle.position)))
+            .fold(f64::INFINITY, f64::min);
+        let result = meta.tune();
+        assert!(result.score < first);
+        assert_eq!(meta.tune().configuration, result.configuration);
+    }
+}
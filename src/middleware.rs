@@ -0,0 +1,84 @@
+//! User hooks that run between the phases of an iteration.
+//!
+//! Middleware gets mutable access to the whole optimizer right after the turtles have been
+//! scored and again right after they have moved, which is enough for domain-specific repair of
+//! positions or injecting candidates from outside without forking the crate. Hooks run in the
+//! order they were pushed onto `Optimizer::middleware`.
+
+use crate::boundary::Boundary;
+use crate::{CubicBoundary, Optimizer};
+
+pub trait Middleware<B = CubicBoundary> {
+    /// Runs once the scores of the current positions have been recorded into the bests.
+    fn post_evaluate(&mut self, _optimizer: &mut Optimizer<'_, B>) {}
+
+    /// Runs once the turtles have moved, before their new positions are scored.
+    fn post_update(&mut self, _optimizer: &mut Optimizer<'_, B>) {}
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum Phase {
+    PostEvaluate,
+    PostUpdate,
+}
+
+impl<B: Boundary> Optimizer<'_, B> {
+    pub(crate) fn run_middleware(&mut self, phase: Phase) {
+        if self.middleware.is_empty() {
+            return;
+        }
+        // The hooks are taken out while they run so they can borrow the optimizer mutably.
+        let mut middleware = core::mem::take(&mut self.middleware);
+        for hook in middleware.iter_mut() {
+            match phase {
+                Phase::PostEvaluate => hook.post_evaluate(self),
+                Phase::PostUpdate => hook.post_update(self),
+            }
+        }
+        // Keep any hooks that were pushed while the others ran.
+        middleware.append(&mut self.middleware);
+        self.middleware = middleware;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{boxed::Box, sync::Arc};
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use ndarray::Array1;
+
+    struct Inject {
+        candidate: Array1<f64>,
+        evaluated: Arc<AtomicUsize>,
+    }
+
+    impl Middleware for Inject {
+        fn post_evaluate(&mut self, _optimizer: &mut Optimizer) {
+            self.evaluated.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn post_update(&mut self, optimizer: &mut Optimizer) {
+            optimizer.turtles[0].position = self.candidate.clone();
+        }
+    }
+
+    #[test]
+    fn hooks_can_inject_external_candidates() {
+        let shifted = |x: &Array1<f64>| x.mapv(|x| (x - 0.25).powi(2)).sum();
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(4, boundaries, &shifted, -1., 3);
+        let evaluated = Arc::new(AtomicUsize::new(0));
+        optimizer.middleware.push(Box::new(Inject {
+            candidate: Array1::from_elem(2, 0.25),
+            evaluated: evaluated.clone(),
+        }));
+        optimizer.iterate();
+        optimizer.iterate();
+
+        assert_eq!(optimizer.best_score, 0.);
+        assert_eq!(evaluated.load(Ordering::Relaxed), 2);
+        assert_eq!(optimizer.middleware.len(), 1);
+        assert_eq!(optimizer.turtles[0].position, Array1::from_elem(2, 0.25));
+    }
+}
@@ -0,0 +1,217 @@
+//! Independent restarts of the same problem.
+//!
+//! A single swarm says little about how reliable its answer is. A multi-start run solves the same
+//! problem several times from independent initializations and reports the best solution together
+//! with statistics over the runs.
+
+use std::thread;
+
+use ndarray::Array1;
+
+use crate::{CubicBoundary, Optimizer};
+
+/// Mean, median and sample standard deviation of a set of scores.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Statistics {
+    pub mean: f64,
+    pub median: f64,
+    pub std: f64,
+}
+
+impl Statistics {
+    pub fn from_scores(scores: &[f64]) -> Self {
+        if scores.is_empty() {
+            return Self {
+                mean: f64::NAN,
+                median: f64::NAN,
+                std: f64::NAN,
+            };
+        }
+        let n = scores.len() as f64;
+        let mean = scores.iter().sum::<f64>() / n;
+        let std = if scores.len() > 1 {
+            (scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1.)).sqrt()
+        } else {
+            0.
+        };
+
+        let mut sorted = scores.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2.
+        } else {
+            sorted[middle]
+        };
+
+        Self { mean, median, std }
+    }
+}
+
+/// The outcome of one of the independent runs.
+#[derive(Clone, Debug)]
+pub struct Run {
+    pub seed: Option<u64>,
+    pub best_score: f64,
+    pub best_position: Array1<f64>,
+    pub iterations: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct MultiStartResult {
+    pub best_score: f64,
+    pub best_position: Array1<f64>,
+    pub runs: Vec<Run>,
+    pub statistics: Statistics,
+    /// Fraction of runs whose best score reached the success threshold.
+    pub success_rate: f64,
+}
+
+/// Describes the problem every run solves and how the runs are carried out.
+pub struct MultiStart<'a> {
+    pub turtles: usize,
+    pub boundaries: CubicBoundary,
+    pub objective_function: &'a (dyn Fn(&Array1<f64>) -> f64 + Sync),
+    pub goal: f64,
+    /// Iterations each run may spend. `None` lets every run work until it reaches the goal.
+    pub iterations: Option<usize>,
+    /// A run counts as a success when its best score is at or below this value.
+    pub threshold: f64,
+    /// Spread the runs over the available cores.
+    pub parallel: bool,
+}
+
+impl<'a> MultiStart<'a> {
+    pub fn new(
+        turtles: usize,
+        boundaries: CubicBoundary,
+        objective_function: &'a (dyn Fn(&Array1<f64>) -> f64 + Sync),
+        goal: f64,
+    ) -> Self {
+        Self {
+            turtles,
+            boundaries,
+            objective_function,
+            goal,
+            iterations: None,
+            threshold: goal,
+            parallel: false,
+        }
+    }
+
+    /// Performs `n_runs` independent optimizations. Run `i` is initialized from `seeds[i]` when
+    /// one is given, and from the thread's random number generator otherwise.
+    pub fn multi_start(&self, n_runs: usize, seeds: &[u64]) -> MultiStartResult {
+        let seed_for = |run: usize| seeds.get(run).copied();
+
+        let runs: Vec<Run> = if self.parallel && n_runs > 1 {
+            let workers = thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+                .min(n_runs);
+            let mut runs: Vec<(usize, Run)> = thread::scope(|scope| {
+                let handles: Vec<_> = (0..workers)
+                    .map(|worker| {
+                        scope.spawn(move || {
+                            (worker..n_runs)
+                                .step_by(workers)
+                                .map(|run| (run, self.run(seed_for(run))))
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .flat_map(|handle| handle.join().expect("a multi-start run panicked"))
+                    .collect()
+            });
+            runs.sort_by_key(|(run, _)| *run);
+            runs.into_iter().map(|(_, run)| run).collect()
+        } else {
+            (0..n_runs).map(|run| self.run(seed_for(run))).collect()
+        };
+
+        let scores: Vec<f64> = runs.iter().map(|run| run.best_score).collect();
+        let successes = scores.iter().filter(|s| **s <= self.threshold).count();
+        let best = runs
+            .iter()
+            .min_by(|a, b| a.best_score.total_cmp(&b.best_score));
+
+        MultiStartResult {
+            best_score: best.map_or(f64::INFINITY, |run| run.best_score),
+            best_position: best.map_or_else(
+                || Array1::zeros(self.boundaries.shape),
+                |run| run.best_position.clone(),
+            ),
+            statistics: Statistics::from_scores(&scores),
+            success_rate: if runs.is_empty() {
+                0.
+            } else {
+                successes as f64 / runs.len() as f64
+            },
+            runs,
+        }
+    }
+
+    fn run(&self, seed: Option<u64>) -> Run {
+        let objective_function: &(dyn Fn(&Array1<f64>) -> f64 + Sync) = self.objective_function;
+        let mut optimizer = match seed {
+            Some(seed) => Optimizer::new_seeded(
+                self.turtles,
+                self.boundaries,
+                objective_function,
+                self.goal,
+                seed,
+            ),
+            None => Optimizer::new(self.turtles, self.boundaries, objective_function, self.goal),
+        };
+        match self.iterations {
+            Some(iterations) => optimizer.advance_to(iterations),
+            None => {
+                optimizer.optimize();
+            }
+        }
+        Run {
+            seed,
+            best_score: optimizer.best_score,
+            best_position: optimizer.best_position,
+            iterations: optimizer.iterations,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parabola(x: &Array1<f64>) -> f64 {
+        (x * x).sum()
+    }
+
+    #[test]
+    fn seeded_runs_are_reproducible_in_parallel() {
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut multi_start = MultiStart::new(5, boundaries, &parabola, f64::NEG_INFINITY);
+        multi_start.iterations = Some(3);
+        multi_start.threshold = 0.5;
+
+        let sequential = multi_start.multi_start(4, &[1, 2, 3, 4]);
+        multi_start.parallel = true;
+        let parallel = multi_start.multi_start(4, &[1, 2, 3, 4]);
+
+        assert_eq!(sequential.runs.len(), 4);
+        for (a, b) in sequential.runs.iter().zip(parallel.runs.iter()) {
+            assert_eq!(a.best_score, b.best_score);
+        }
+        assert!(sequential.best_score <= sequential.statistics.median);
+        assert!((0.0..=1.0).contains(&sequential.success_rate));
+    }
+
+    #[test]
+    fn statistics_of_known_scores() {
+        let statistics = Statistics::from_scores(&[1., 2., 3., 4.]);
+        assert_eq!(statistics.mean, 2.5);
+        assert_eq!(statistics.median, 2.5);
+        assert!((statistics.std - 1.2909944487358056).abs() < 1e-12);
+    }
+}
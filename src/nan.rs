@@ -0,0 +1,101 @@
+//! What to do with scores that are NaN or infinite.
+//!
+//! Every comparison against NaN is false, so a NaN score is silently never a best, and an infinite
+//! score in the better direction is a best nothing can displace. `Optimizer::nan_policy` decides
+//! what such a score from the objective means instead: the worst score of the direction, a fresh
+//! try at a position drawn anywhere in the boundary, or the end of the run with
+//! `Termination::InvalidScore`. Infinities in the worse direction are caught too, so objectives
+//! that return `f64::INFINITY` for positions they cannot score get the same treatment.
+//!
+//! Only positions the optimizer scores itself are screened; `tell` records scores as given.
+
+use crate::boundary::Boundary;
+use crate::Optimizer;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NanPolicy {
+    /// The score counts as the worst of the direction, so it is never a best.
+    #[default]
+    Worst,
+    /// The turtle is moved to a random position outside of the tabu archive and scored there, up
+    /// to this many times, and counts as the worst if every score is invalid.
+    Resample(usize),
+    /// The score counts as the worst, and the run stops at the end of the iteration with
+    /// `Termination::InvalidScore`.
+    Error,
+}
+
+impl<B: Boundary> Optimizer<'_, B> {
+    // Applies the policy to a score the objective returned for `turtle`, returning the score to
+    // record in its place.
+    pub(crate) fn screen_score(&mut self, turtle: usize, mut score: f64) -> f64 {
+        let mut resamples = 0;
+        while !score.is_finite() {
+            match self.nan_policy {
+                NanPolicy::Resample(attempts) if resamples < attempts => {
+                    resamples += 1;
+                    let mut position = self.untabu_position();
+                    if let Some(integer) = &self.integer_dimensions {
+                        integer.round(&self.boundaries, &mut position, &mut self.rng);
+                    }
+                    score = (self.objective_function)(&position);
+                    self.evaluations += 1;
+                    self.turtles[turtle].position = position;
+                }
+                NanPolicy::Worst | NanPolicy::Resample(_) => return self.direction.worst(),
+                NanPolicy::Error => {
+                    self.invalid_score = true;
+                    return self.direction.worst();
+                }
+            }
+        }
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CubicBoundary, Direction, Termination};
+    use ndarray::Array1;
+
+    #[test]
+    fn invalid_scores_follow_the_policy() {
+        // Undefined left of the origin, and unbounded below at its far edge.
+        let partial = |x: &Array1<f64>| {
+            if x[0] < 0. {
+                f64::NAN
+            } else if x[0] > 0.99 {
+                f64::NEG_INFINITY
+            } else {
+                x.mapv(|x| x * x).sum()
+            }
+        };
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &partial, -1., 4);
+        optimizer.max_iterations = Some(20);
+        optimizer.optimize();
+        assert!(optimizer.best_score.is_finite() && optimizer.best_position[0] >= 0.);
+        assert!(optimizer
+            .turtles
+            .iter()
+            .all(|turtle| !turtle.best_score.is_nan()));
+
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &partial, -1., 4);
+        optimizer.nan_policy = NanPolicy::Resample(20);
+        optimizer.iterate();
+        assert!(optimizer
+            .turtles
+            .iter()
+            .all(|turtle| turtle.best_score.is_finite() && turtle.best_position[0] >= 0.));
+
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &partial, 1., 4);
+        optimizer.set_direction(Direction::Maximize);
+        optimizer.nan_policy = NanPolicy::Error;
+        let result = optimizer.optimize();
+        assert_eq!(result.termination, Termination::InvalidScore);
+        assert_eq!(result.iterations, 1);
+    }
+}
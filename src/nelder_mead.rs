@@ -0,0 +1,222 @@
+//! A Nelder–Mead simplex polish of the swarm's best position.
+//!
+//! Swarms find the right basin quickly but take a long time to settle at its bottom. The simplex
+//! starts at the best position with a vertex `step` times the boundary's width along every
+//! dimension and reflects, expands, contracts and shrinks its way down, projecting every vertex
+//! into the boundary, until the scores across it agree within `tolerance` or `max_evaluations`
+//! are spent. `NelderMead::polish` runs it once, for instance at termination, and pushing a
+//! `PeriodicPolish` onto the optimizer's middleware runs it every few iterations.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ndarray::Array1;
+
+use crate::boundary::Boundary;
+use crate::middleware::Middleware;
+use crate::{Direction, Optimizer};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NelderMead {
+    /// The distance from the start to the other vertices, as a fraction of the boundary's width.
+    pub step: f64,
+    pub max_evaluations: usize,
+    /// The spread of the scores across the simplex at which it has converged.
+    pub tolerance: f64,
+    pub reflection: f64,
+    pub expansion: f64,
+    pub contraction: f64,
+    pub shrink: f64,
+}
+
+impl Default for NelderMead {
+    fn default() -> Self {
+        Self {
+            step: 0.05,
+            max_evaluations: 1000,
+            tolerance: 1e-12,
+            reflection: 1.,
+            expansion: 2.,
+            contraction: 0.5,
+            shrink: 0.5,
+        }
+    }
+}
+
+impl NelderMead {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Polishes `optimizer`'s best position in place and returns the number of evaluations spent,
+    /// which are added to `optimizer.evaluations` as well. An optimizer that has not scored
+    /// anything yet is left alone.
+    pub fn polish<B: Boundary>(&self, optimizer: &mut Optimizer<'_, B>) -> usize {
+        if !optimizer.best_score.is_finite() {
+            return 0;
+        }
+        let direction = optimizer.direction();
+        // The simplex always descends, so maximized scores are negated on the way in and out.
+        let sign = match direction {
+            Direction::Minimize => 1.,
+            Direction::Maximize => -1.,
+        };
+        let objective_function = optimizer.objective_function.clone();
+        let (position, score, evaluations) = self.search(
+            &|x: &Array1<f64>| sign * objective_function(x),
+            &optimizer.boundaries,
+            optimizer.best_position.clone(),
+            sign * optimizer.best_score,
+        );
+        if direction.is_better(sign * score, optimizer.best_score) {
+            optimizer.best_score = sign * score;
+            optimizer.best_position = position;
+        }
+        optimizer.evaluations += evaluations;
+        evaluations
+    }
+
+    fn search<B: Boundary>(
+        &self,
+        objective: &dyn Fn(&Array1<f64>) -> f64,
+        boundaries: &B,
+        start: Array1<f64>,
+        start_score: f64,
+    ) -> (Array1<f64>, f64, usize) {
+        let n = start.len();
+        let (lower, upper) = boundaries.bounding_box();
+        let mut evaluations = 0;
+        let score = |x: &Array1<f64>, evaluations: &mut usize| {
+            *evaluations += 1;
+            let score = objective(x);
+            if score.is_nan() {
+                f64::INFINITY
+            } else {
+                score
+            }
+        };
+
+        let mut simplex: Vec<(f64, Array1<f64>)> = Vec::with_capacity(n + 1);
+        for dimension in 0..n {
+            let offset = self.step * (upper[dimension] - lower[dimension]);
+            let mut vertex = start.clone();
+            vertex[dimension] += offset;
+            if vertex[dimension] > upper[dimension] {
+                vertex[dimension] = start[dimension] - offset;
+            }
+            boundaries.project(&mut vertex);
+            simplex.push((score(&vertex, &mut evaluations), vertex));
+        }
+        simplex.push((start_score, start));
+
+        while evaluations < self.max_evaluations {
+            simplex.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let (best, worst) = (simplex[0].0, simplex[n].0);
+            if worst - best <= self.tolerance {
+                break;
+            }
+            let mut centroid = Array1::zeros(n);
+            for (_, vertex) in simplex.iter().take(n) {
+                centroid += vertex;
+            }
+            centroid /= n.max(1) as f64;
+            let towards = |from: &Array1<f64>, factor: f64| {
+                let mut point = &centroid + &((from - &centroid) * factor);
+                boundaries.project(&mut point);
+                point
+            };
+
+            let reflected = towards(&simplex[n].1, -self.reflection);
+            let reflected_score = score(&reflected, &mut evaluations);
+            if reflected_score < best {
+                let expanded = towards(&reflected, self.expansion);
+                let expanded_score = score(&expanded, &mut evaluations);
+                simplex[n] = if expanded_score < reflected_score {
+                    (expanded_score, expanded)
+                } else {
+                    (reflected_score, reflected)
+                };
+                continue;
+            }
+            if reflected_score < simplex[n - 1].0 {
+                simplex[n] = (reflected_score, reflected);
+                continue;
+            }
+            let (contracted, limit) = if reflected_score < worst {
+                (towards(&reflected, self.contraction), reflected_score)
+            } else {
+                (towards(&simplex[n].1, self.contraction), worst)
+            };
+            let contracted_score = score(&contracted, &mut evaluations);
+            if contracted_score < limit {
+                simplex[n] = (contracted_score, contracted);
+                continue;
+            }
+            let anchor = simplex[0].1.clone();
+            for (vertex_score, vertex) in simplex.iter_mut().skip(1) {
+                *vertex = &anchor + &((&*vertex - &anchor) * self.shrink);
+                boundaries.project(vertex);
+                *vertex_score = score(vertex, &mut evaluations);
+            }
+        }
+        simplex.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let (best_score, best) = simplex.swap_remove(0);
+        (best, best_score, evaluations)
+    }
+}
+
+/// Middleware polishing the best position with `nelder_mead` once every `every` iterations.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PeriodicPolish {
+    pub nelder_mead: NelderMead,
+    pub every: usize,
+    /// How many polishes have run so far.
+    pub polishes: usize,
+}
+
+impl PeriodicPolish {
+    pub fn new(every: usize) -> Self {
+        Self {
+            nelder_mead: NelderMead::default(),
+            every,
+            polishes: 0,
+        }
+    }
+}
+
+impl<B: Boundary> Middleware<B> for PeriodicPolish {
+    fn post_evaluate(&mut self, optimizer: &mut Optimizer<'_, B>) {
+        if (optimizer.iterations + 1).is_multiple_of(self.every.max(1)) {
+            self.nelder_mead.polish(optimizer);
+            self.polishes += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CubicBoundary;
+
+    #[test]
+    fn polishing_settles_the_bottom_of_the_basin() {
+        let valley = |x: &Array1<f64>| (x[0] - 0.4).powi(2) + 10. * (x[1] + 0.2).powi(2);
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &valley, -1., 3);
+        optimizer.iterate();
+        let swarm_best = optimizer.best_score;
+        let swarm_evaluations = optimizer.evaluations;
+        let evaluations = NelderMead::new().polish(&mut optimizer);
+
+        assert!(evaluations > 0 && evaluations <= 1000);
+        assert_eq!(optimizer.evaluations, swarm_evaluations + evaluations);
+        assert!(optimizer.best_score < 1e-10 && optimizer.best_score < swarm_best);
+        assert_eq!(valley(&optimizer.best_position), optimizer.best_score);
+
+        let mut periodic = Optimizer::new_seeded(10, boundaries, &valley, 1e-10, 3);
+        periodic.middleware.push(Box::new(PeriodicPolish::new(3)));
+        periodic.max_iterations = Some(30);
+        let result = periodic.optimize();
+        assert_eq!(result.termination, crate::Termination::GoalReached);
+        assert_eq!(result.iterations, 3);
+    }
+}
@@ -0,0 +1,239 @@
+//! Bookkeeping for noisy objectives, such as simulations with a random seed of their own.
+//!
+//! A single evaluation of a noisy objective can come out far better than the position deserves,
+//! and once that lucky score is a best nothing ever displaces it. With `Optimizer::noise` set,
+//! every position is evaluated `replicates` times and compared on the mean, or on the pessimistic
+//! end of a confidence interval around it. Adaptive handling keeps evaluating, up to
+//! `max_replicates`, while the interval still straddles the overall best, so evaluations are spent
+//! where a comparison is close. The overall best can be re-evaluated every few iterations too,
+//! which lets a lucky best regress towards its true mean until a better one takes over.
+//!
+//! Only positions the optimizer scores itself are replicated; `tell` records scores as given.
+
+use ndarray::Array1;
+
+use crate::boundary::Boundary;
+use crate::{sqrt, Direction, Optimizer};
+
+/// A running mean and variance, updated one evaluation at a time with Welford's method.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Estimate {
+    count: usize,
+    #[cfg_attr(feature = "serde", serde(with = "crate::checkpoint::score"))]
+    mean: f64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::checkpoint::score"))]
+    m2: f64,
+}
+
+impl Estimate {
+    pub fn push(&mut self, score: f64) {
+        self.count += 1;
+        let delta = score - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (score - self.mean);
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// The standard error of the mean, infinite until there are two evaluations.
+    pub fn standard_error(&self) -> f64 {
+        if self.count < 2 {
+            return f64::INFINITY;
+        }
+        sqrt(self.m2 / (self.count - 1) as f64 / self.count as f64)
+    }
+
+    // The mean moved `z` standard errors towards the worse scores, or towards the better ones
+    // for a negative `z`. A single evaluation has no spread to move by.
+    fn bound(&self, z: f64, direction: Direction) -> f64 {
+        if self.count < 2 {
+            return self.mean;
+        }
+        match direction {
+            Direction::Minimize => self.mean + z * self.standard_error(),
+            Direction::Maximize => self.mean - z * self.standard_error(),
+        }
+    }
+}
+
+/// What noisy positions are compared on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Comparison {
+    #[default]
+    Mean,
+    /// The pessimistic end of the confidence interval, so positions are only preferred once the
+    /// evaluations back them up.
+    ConfidenceBound,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NoiseHandling {
+    /// How many times every position is evaluated.
+    pub replicates: usize,
+    /// How many times a position may be evaluated while its comparison with the overall best is
+    /// still undecided. No more than `replicates` turns adaptation off.
+    pub max_replicates: usize,
+    /// The width of the confidence interval in standard errors, 1.96 for about 95%.
+    pub confidence: f64,
+    pub comparison: Comparison,
+    /// Re-evaluates the overall best `replicates` more times every this many iterations.
+    pub reevaluate_every: Option<usize>,
+    /// How many times the objective has been evaluated in total.
+    pub evaluations: usize,
+    // The evaluations behind the overall best.
+    best: Estimate,
+}
+
+impl NoiseHandling {
+    pub fn new(replicates: usize) -> Self {
+        Self {
+            replicates,
+            max_replicates: replicates,
+            confidence: 1.96,
+            comparison: Comparison::Mean,
+            reevaluate_every: None,
+            evaluations: 0,
+            best: Estimate::default(),
+        }
+    }
+
+    /// Evaluates every position `replicates` times, and up to `max_replicates` times while its
+    /// comparison with the overall best is undecided.
+    pub fn adaptive(replicates: usize, max_replicates: usize) -> Self {
+        Self {
+            max_replicates,
+            ..Self::new(replicates)
+        }
+    }
+
+    /// The evaluations behind the overall best.
+    pub fn best_estimate(&self) -> &Estimate {
+        &self.best
+    }
+
+    fn score(&self, estimate: &Estimate, direction: Direction) -> f64 {
+        match self.comparison {
+            Comparison::Mean => estimate.mean(),
+            Comparison::ConfidenceBound => estimate.bound(self.confidence, direction),
+        }
+    }
+
+    // Evaluates `position` until the replicates are spent and, when adapting, the comparison with
+    // `best` is decided.
+    fn estimate(
+        &mut self,
+        objective_function: &dyn Fn(&Array1<f64>) -> f64,
+        position: &Array1<f64>,
+        best: f64,
+        direction: Direction,
+    ) -> Estimate {
+        let mut estimate = Estimate::default();
+        while estimate.count() < self.replicates.max(1)
+            || (estimate.count() < self.max_replicates
+                && best.is_finite()
+                && direction.is_better(estimate.bound(-self.confidence, direction), best)
+                && !direction.is_better(estimate.bound(self.confidence, direction), best))
+        {
+            estimate.push(objective_function(position));
+            self.evaluations += 1;
+        }
+        estimate
+    }
+}
+
+impl<B: Boundary> Optimizer<'_, B> {
+    // Scores a turtle from replicated evaluations and keeps the evaluations behind a new best.
+    pub(crate) fn evaluate_noisy(&mut self, turtle: usize) {
+        let Some(mut noise) = self.noise.take() else {
+            return;
+        };
+        let estimate = noise.estimate(
+            &*self.objective_function,
+            &self.turtles[turtle].position,
+            self.best_score,
+            self.direction,
+        );
+        self.evaluations += estimate.count();
+        let score = noise.score(&estimate, self.direction);
+        #[cfg(feature = "std")]
+        self.log_evaluation(turtle, score, None);
+        let before = self.best_score;
+        self.record(turtle, score);
+        if self.best_score != before {
+            noise.best = estimate;
+        }
+        self.noise = Some(noise);
+    }
+
+    // Adds evaluations to the overall best when it is due, and rescores it from all of them.
+    pub(crate) fn reevaluate_noisy_best(&mut self) {
+        let Some(mut noise) = self.noise.take() else {
+            return;
+        };
+        let due = noise.reevaluate_every.is_some_and(|every| {
+            self.iterations > 0 && self.iterations.is_multiple_of(every.max(1))
+        });
+        if due && noise.best.count() > 0 {
+            for _ in 0..noise.replicates.max(1) {
+                noise
+                    .best
+                    .push((self.objective_function)(&self.best_position));
+                noise.evaluations += 1;
+                self.evaluations += 1;
+            }
+            self.best_score = noise.score(&noise.best, self.direction);
+        }
+        self.noise = Some(noise);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CubicBoundary;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn replicates_see_through_the_noise() {
+        // Every other evaluation comes out one lower than it should, the rest one higher.
+        let calls = AtomicUsize::new(0);
+        let noisy = |x: &Array1<f64>| {
+            let calls = calls.fetch_add(1, Ordering::Relaxed) + 1;
+            let noise = if calls.is_multiple_of(2) { -1. } else { 1. };
+            x.mapv(|x| x * x).sum() + noise
+        };
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &noisy, -10., 4);
+        optimizer.max_iterations = Some(20);
+        optimizer.optimize();
+        assert!(optimizer.best_score < -0.5);
+
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &noisy, -10., 4);
+        optimizer.max_iterations = Some(20);
+        optimizer.noise = Some(NoiseHandling::new(2));
+        let result = optimizer.optimize();
+        assert!(result.best_score >= 0.);
+        assert_eq!(optimizer.noise.unwrap().evaluations, 400);
+
+        let mut adaptive = NoiseHandling::adaptive(2, 6);
+        adaptive.comparison = Comparison::ConfidenceBound;
+        adaptive.reevaluate_every = Some(5);
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &noisy, -10., 4);
+        optimizer.max_iterations = Some(20);
+        optimizer.noise = Some(adaptive);
+        optimizer.optimize();
+        let noise = optimizer.noise.unwrap();
+        assert!(noise.evaluations > 400);
+        assert!(noise.best_estimate().count() > 2);
+        assert!(optimizer.best_score >= noise.best_estimate().mean());
+    }
+}
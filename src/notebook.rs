@@ -0,0 +1,188 @@
+//! Rich output for evcxr based Rust notebooks.
+//!
+//! evcxr shows any value with an `evcxr_display` method as the content that method prints, so a
+//! `ConvergencePlot` or `SolutionTable` left as the last expression of a cell renders inline as
+//! an SVG plot or an HTML table.
+
+use std::fmt::Write;
+
+use ndarray::Array1;
+
+use crate::Optimizer;
+
+const WIDTH: f64 = 480.;
+const HEIGHT: f64 = 240.;
+const MARGIN: f64 = 40.;
+
+fn evcxr_print(mime: &str, content: &str) {
+    println!(
+        "EVCXR_BEGIN_CONTENT {}\n{}\nEVCXR_END_CONTENT",
+        mime, content
+    );
+}
+
+/// The best score after each recorded iteration.
+#[derive(Clone, Debug, Default)]
+pub struct ConvergencePlot {
+    pub scores: Vec<f64>,
+}
+
+impl ConvergencePlot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `optimizer`'s current best score. Call it after every iteration of a driven run.
+    pub fn record<B>(&mut self, optimizer: &Optimizer<'_, B>) {
+        self.scores.push(optimizer.best_score);
+    }
+
+    /// The best score against the iteration as a standalone SVG image. Unscored iterations are
+    /// left out.
+    pub fn svg(&self) -> String {
+        let points: Vec<(usize, f64)> = self
+            .scores
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, score)| score.is_finite())
+            .collect();
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{HEIGHT}\" \
+             viewBox=\"0 0 {WIDTH} {HEIGHT}\">"
+        );
+        let _ = write!(
+            svg,
+            "<rect x=\"{MARGIN}\" y=\"{}\" width=\"{}\" height=\"{}\" \
+             fill=\"none\" stroke=\"#888\"/>",
+            MARGIN / 2.,
+            WIDTH - 1.5 * MARGIN,
+            HEIGHT - 1.5 * MARGIN
+        );
+        if let (Some(first), Some(last)) = (points.first(), points.last()) {
+            let low = points
+                .iter()
+                .map(|(_, score)| *score)
+                .fold(f64::INFINITY, f64::min);
+            let high = points
+                .iter()
+                .map(|(_, score)| *score)
+                .fold(f64::NEG_INFINITY, f64::max);
+            let span = |low: f64, high: f64| if high > low { high - low } else { 1. };
+            let x = |iteration: usize| {
+                MARGIN
+                    + (iteration - first.0) as f64 / span(first.0 as f64, last.0 as f64)
+                        * (WIDTH - 1.5 * MARGIN)
+            };
+            let y = |score: f64| {
+                HEIGHT - MARGIN - (score - low) / span(low, high) * (HEIGHT - 1.5 * MARGIN)
+            };
+            let polyline: Vec<String> = points
+                .iter()
+                .map(|(iteration, score)| format!("{:.1},{:.1}", x(*iteration), y(*score)))
+                .collect();
+            let _ = write!(
+                svg,
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"#2a7\" stroke-width=\"2\"/>",
+                polyline.join(" ")
+            );
+            let _ = write!(
+                svg,
+                "<text x=\"2\" y=\"{}\" font-size=\"10\">{:.3e}</text>\
+                 <text x=\"2\" y=\"{}\" font-size=\"10\">{:.3e}</text>\
+                 <text x=\"{MARGIN}\" y=\"{}\" font-size=\"10\">{}</text>\
+                 <text x=\"{}\" y=\"{}\" font-size=\"10\" text-anchor=\"end\">{}</text>",
+                MARGIN / 2. + 10.,
+                high,
+                HEIGHT - MARGIN,
+                low,
+                HEIGHT - MARGIN / 4.,
+                first.0,
+                WIDTH - MARGIN / 2.,
+                HEIGHT - MARGIN / 4.,
+                last.0
+            );
+        }
+        svg.push_str("</svg>");
+        svg
+    }
+
+    pub fn evcxr_display(&self) {
+        evcxr_print("image/svg+xml", &self.svg());
+    }
+}
+
+/// The best personal bests of a swarm, best first.
+#[derive(Clone, Debug)]
+pub struct SolutionTable {
+    pub solutions: Vec<(f64, Array1<f64>)>,
+}
+
+impl SolutionTable {
+    /// The `k` best scored personal bests of `optimizer`'s turtles.
+    pub fn top<B>(optimizer: &Optimizer<'_, B>, k: usize) -> Self {
+        let mut solutions: Vec<(f64, Array1<f64>)> = optimizer
+            .turtles
+            .iter()
+            .filter(|turtle| turtle.best_score.is_finite())
+            .map(|turtle| (turtle.best_score, turtle.best_position.clone()))
+            .collect();
+        solutions.sort_by(|a, b| a.0.total_cmp(&b.0));
+        solutions.truncate(k);
+        Self { solutions }
+    }
+
+    pub fn html(&self) -> String {
+        let dimensions = self
+            .solutions
+            .first()
+            .map_or(0, |(_, position)| position.len());
+        let mut html = String::from("<table><thead><tr><th>rank</th><th>score</th>");
+        for dimension in 0..dimensions {
+            let _ = write!(html, "<th>x{}</th>", dimension);
+        }
+        html.push_str("</tr></thead><tbody>");
+        for (rank, (score, position)) in self.solutions.iter().enumerate() {
+            let _ = write!(html, "<tr><td>{}</td><td>{:.6e}</td>", rank + 1, score);
+            for x in position.iter() {
+                let _ = write!(html, "<td>{:.6}</td>", x);
+            }
+            html.push_str("</tr>");
+        }
+        html.push_str("</tbody></table>");
+        html
+    }
+
+    pub fn evcxr_display(&self) {
+        evcxr_print("text/html", &self.html());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CubicBoundary;
+
+    #[test]
+    fn runs_render_as_svg_and_html() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(6, boundaries, &parabola, -1., 5);
+        let mut plot = ConvergencePlot::new();
+        plot.record(&optimizer);
+        for _ in 0..4 {
+            optimizer.iterate();
+            plot.record(&optimizer);
+        }
+        let svg = plot.svg();
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+        assert_eq!(svg.matches("<polyline").count(), 1);
+
+        let table = SolutionTable::top(&optimizer, 3);
+        assert_eq!(table.solutions.len(), 3);
+        assert_eq!(table.solutions[0].0, optimizer.best_score);
+        let html = table.html();
+        assert_eq!(html.matches("<tr>").count(), 4);
+        assert!(html.contains("<th>x1</th>"));
+    }
+}
@@ -0,0 +1,172 @@
+//! How the optimizer holds its objective, and adapters for objectives over plain slices.
+//!
+//! Optimizers keep their objective as an `ObjectiveFunction`, a shared handle that is either
+//! borrowed for the optimizer's lifetime or owned by it. Anything that borrows or boxes a
+//! closure converts into one, so `Optimizer::new(20, b, &objective, 1e-6)` still works, while an
+//! owned closure such as `ObjectiveFunction::new(move |x| ...)` lets an `Optimizer<'static>` be
+//! stored in a struct of its own. Objectives are `Send + Sync`, so such an optimizer can also be
+//! moved to another thread. `ObjectiveFunction::from_mut` takes closures that keep state between
+//! evaluations, like a counter, behind a mutex.
+//!
+//! The optimizer scores positions as `Array1<f64>`, but models written against `&[f64]` or
+//! `Vec<f64>` need not depend on ndarray for that. `from_slice` wraps such a model into an
+//! objective the optimizer accepts, and `on_slices` goes the other way for handing the crate's
+//! own objectives, like the benchmarks, to code that only knows slices:
+//!
+//! `let model = from_slice(|x: &[f64]| x.iter().sum()); Optimizer::new(20, b, &model, 1e-6)`
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use core::ops::Deref;
+#[cfg(feature = "std")]
+use std::sync::Mutex;
+
+use ndarray::{Array1, ArrayView1};
+
+type Function<'a, T = f64> = dyn Fn(&Array1<T>) -> T + Send + Sync + 'a;
+
+/// A cheaply cloned handle on the objective, owned or borrowed. Swarms over other floats, see
+/// `float`, score positions in that float.
+pub struct ObjectiveFunction<'a, T = f64>(Arc<Function<'a, T>>);
+
+impl<T> Clone for ObjectiveFunction<'_, T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<'a, T> ObjectiveFunction<'a, T> {
+    pub fn new<F>(objective: F) -> Self
+    where
+        F: Fn(&Array1<T>) -> T + Send + Sync + 'a,
+    {
+        Self(Arc::new(objective))
+    }
+
+    /// An objective that may update its own state every evaluation. It must not score positions
+    /// through the same handle from inside an evaluation, and evaluations from several threads
+    /// take turns.
+    #[cfg(feature = "std")]
+    pub fn from_mut<F>(objective: F) -> Self
+    where
+        F: FnMut(&Array1<T>) -> T + Send + 'a,
+    {
+        let objective = Mutex::new(objective);
+        Self::new(move |position: &Array1<T>| {
+            let mut objective = objective
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            (objective)(position)
+        })
+    }
+}
+
+impl<'a, T> Deref for ObjectiveFunction<'a, T> {
+    type Target = Function<'a, T>;
+
+    fn deref(&self) -> &Self::Target {
+        &*self.0
+    }
+}
+
+impl<'a, T, F> From<&'a F> for ObjectiveFunction<'a, T>
+where
+    F: Fn(&Array1<T>) -> T + Sync + 'a,
+{
+    fn from(objective: &'a F) -> Self {
+        Self::new(objective)
+    }
+}
+
+impl<'a, T> From<&'a (dyn Fn(&Array1<T>) -> T + Sync)> for ObjectiveFunction<'a, T> {
+    fn from(objective: &'a (dyn Fn(&Array1<T>) -> T + Sync)) -> Self {
+        Self::new(objective)
+    }
+}
+
+impl<'a, T> From<Box<Function<'a, T>>> for ObjectiveFunction<'a, T> {
+    fn from(objective: Box<Function<'a, T>>) -> Self {
+        Self(Arc::from(objective))
+    }
+}
+
+impl<'a, T> From<Arc<Function<'a, T>>> for ObjectiveFunction<'a, T> {
+    fn from(objective: Arc<Function<'a, T>>) -> Self {
+        Self(objective)
+    }
+}
+
+/// An objective over `Array1<f64>` that scores each position with `objective`. Positions are
+/// always contiguous in practice, and copied into a slice when they are not.
+pub fn from_slice<F>(objective: F) -> impl Fn(&Array1<f64>) -> f64
+where
+    F: Fn(&[f64]) -> f64,
+{
+    move |position: &Array1<f64>| match position.as_slice() {
+        Some(slice) => objective(slice),
+        None => objective(&position.to_vec()),
+    }
+}
+
+/// An objective over plain slices that scores each one with `objective`.
+pub fn on_slices<F>(objective: F) -> impl Fn(&[f64]) -> f64
+where
+    F: Fn(&Array1<f64>) -> f64,
+{
+    move |slice: &[f64]| objective(&ArrayView1::from(slice).to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CubicBoundary, Optimizer, OptimizerParams, Termination};
+
+    #[test]
+    fn slice_objectives_drive_the_optimizer() {
+        let model = from_slice(|x: &[f64]| x.iter().map(|x| (x - 0.5) * (x - 0.5)).sum());
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(15, boundaries, &model, 1e-4, 3);
+        optimizer.params = OptimizerParams {
+            inertia: 0.6,
+            cognitive: 0.7,
+            social: 0.7,
+        };
+        optimizer.max_iterations = Some(1000);
+        let result = optimizer.optimize();
+        assert_eq!(result.termination, Termination::GoalReached);
+        assert!(result.best_position.iter().all(|x| (x - 0.5).abs() < 0.1));
+
+        let back = on_slices(&model);
+        let position = [0.5, 1.5];
+        assert_eq!(back(&position), model(&Array1::from(position.to_vec())));
+        assert_eq!(back(&position), 1.);
+    }
+
+    // Owns its optimizer, which is only possible with an owned objective.
+    struct Study {
+        optimizer: Optimizer<'static>,
+    }
+
+    #[test]
+    fn optimizers_own_stateful_objectives() {
+        let counter = Arc::new(core::sync::atomic::AtomicUsize::new(0));
+        let counted = counter.clone();
+        let mut calls = 0;
+        let objective = ObjectiveFunction::from_mut(move |x: &Array1<f64>| {
+            calls += 1;
+            counted.store(calls, core::sync::atomic::Ordering::Relaxed);
+            x.mapv(|x| x * x).sum()
+        });
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut study = Study {
+            optimizer: Optimizer::new_seeded(4, boundaries, objective, -1., 2),
+        };
+        study.optimizer.max_iterations = Some(3);
+        study.optimizer.optimize();
+        assert_eq!(counter.load(core::sync::atomic::Ordering::Relaxed), 4 * 3);
+
+        let boxed: Box<Function> = Box::new(|x: &Array1<f64>| x.sum());
+        let owned = Optimizer::new_seeded(4, boundaries, boxed, -1., 2);
+        assert_eq!((owned.objective_function)(&Array1::ones(2)), 2.);
+    }
+}
@@ -0,0 +1,94 @@
+//! Read-only callbacks after every iteration.
+//!
+//! Observers see the whole swarm once an iteration is complete, which is the place for progress
+//! logs, dashboards and early stopping rules of your own. An observer returning
+//! `ControlFlow::Break` ends the run before its next iteration, with
+//! `Termination::Observer`. Observers run in the order they were pushed onto
+//! `Optimizer::observers`, and all of them run even when an earlier one asked to stop. Like
+//! stopping criteria, observers are generic over the optimizer they look at, so the same one can
+//! follow a `BinaryOptimizer` when it only relies on `stopping::Progress`.
+
+use core::ops::ControlFlow;
+
+use crate::Optimizer;
+
+/// Looks at an `O`, such as an `Optimizer<'a, B>`, after every iteration.
+pub trait Observer<O: ?Sized> {
+    fn observe(&mut self, optimizer: &O) -> ControlFlow<()>;
+}
+
+impl<O: ?Sized, F> Observer<O> for F
+where
+    F: FnMut(&O) -> ControlFlow<()>,
+{
+    fn observe(&mut self, optimizer: &O) -> ControlFlow<()> {
+        self(optimizer)
+    }
+}
+
+impl<B> Optimizer<'_, B> {
+    pub(crate) fn notify_observers(&mut self) {
+        if self.observers.is_empty() {
+            return;
+        }
+        // The observers are taken out while they look at the optimizer.
+        let mut observers = core::mem::take(&mut self.observers);
+        for observer in observers.iter_mut() {
+            if observer.observe(self).is_break() {
+                self.observer_stop = true;
+            }
+        }
+        observers.append(&mut self.observers);
+        self.observers = observers;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CubicBoundary, Termination};
+    use alloc::{boxed::Box, sync::Arc};
+    use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use ndarray::Array1;
+
+    #[test]
+    fn observers_follow_the_run_and_can_end_it() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(5, boundaries, &parabola, -1., 6);
+        // Observers are `Send`, so what they see is shared through atomics.
+        let (observed, improving) = (
+            Arc::new(AtomicUsize::new(0)),
+            Arc::new(AtomicBool::new(true)),
+        );
+        let (count, monotone) = (observed.clone(), improving.clone());
+        let mut previous = f64::INFINITY;
+        optimizer
+            .observers
+            .push(Box::new(move |optimizer: &Optimizer| {
+                count.fetch_add(1, Ordering::Relaxed);
+                if optimizer.best_score > previous {
+                    monotone.store(false, Ordering::Relaxed);
+                }
+                previous = optimizer.best_score;
+                ControlFlow::Continue(())
+            }));
+        optimizer.observers.push(Box::new(|optimizer: &Optimizer| {
+            if optimizer.iterations >= 6 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        }));
+
+        let result = optimizer.optimize();
+        assert_eq!(result.termination, Termination::Observer);
+        assert_eq!(result.iterations, 6);
+        assert_eq!(observed.load(Ordering::Relaxed), 6);
+        assert!(improving.load(Ordering::Relaxed));
+
+        // The request to stop is used up, so the next run goes on until asked again.
+        optimizer.optimize();
+        assert_eq!(optimizer.iterations, 7);
+    }
+}
@@ -0,0 +1,103 @@
+//! Runs that survive a panicking objective.
+//!
+//! A panic in the objective unwinds through `optimize` and takes the swarm with it. With
+//! `Optimizer::panics` set, every evaluation is wrapped in `catch_unwind`, and a panic scores the
+//! turtle as the worst of the direction and is recorded as an incident, so the run carries on and
+//! the failing positions can be looked at afterwards. The panic hook still runs, so the message
+//! is printed to stderr as usual unless the hook is replaced.
+//!
+//! Objectives that panic halfway through changing shared state may leave it broken; catching the
+//! panic does not undo what the objective did. Replicated evaluations under `noise` are not
+//! wrapped.
+
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use ndarray::Array1;
+
+use crate::boundary::Boundary;
+use crate::Optimizer;
+
+/// A panic caught while scoring a turtle.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Incident {
+    pub iteration: usize,
+    pub turtle: usize,
+    pub position: Array1<f64>,
+    /// The panic message, when it was a string.
+    pub message: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PanicLog {
+    pub incidents: Vec<Incident>,
+}
+
+impl PanicLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn message(payload: &(dyn Any + Send)) -> Option<String> {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+}
+
+impl<B: Boundary> Optimizer<'_, B> {
+    // Scores `turtle`, or records the incident and returns `None` when the objective panics under
+    // a panic log.
+    pub(crate) fn evaluate_catching(&mut self, turtle: usize) -> Option<f64> {
+        let position = &self.turtles[turtle].position;
+        let Some(log) = self.panics.as_mut() else {
+            return Some((self.objective_function)(position));
+        };
+        let objective_function = &self.objective_function;
+        match catch_unwind(AssertUnwindSafe(|| objective_function(position))) {
+            Ok(score) => Some(score),
+            Err(payload) => {
+                log.incidents.push(Incident {
+                    iteration: self.iterations,
+                    turtle,
+                    position: position.clone(),
+                    message: message(&*payload),
+                });
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CubicBoundary;
+
+    #[test]
+    fn panics_are_recorded_and_the_run_goes_on() {
+        let fragile = |x: &Array1<f64>| {
+            if x[0] < -0.5 {
+                panic!("no solution at {}", x[0]);
+            }
+            x.mapv(|x| x * x).sum()
+        };
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &fragile, -1., 4);
+        optimizer.max_iterations = Some(10);
+        optimizer.panics = Some(PanicLog::new());
+        // The default hook prints every caught panic, which the test harness captures.
+        let result = optimizer.optimize();
+
+        assert_eq!(result.iterations, 10);
+        assert!(result.best_score.is_finite() && result.best_position[0] >= -0.5);
+        let incidents = &optimizer.panics.as_ref().unwrap().incidents;
+        assert!(!incidents.is_empty());
+        assert!(incidents.iter().all(|incident| incident.position[0] < -0.5
+            && incident
+                .message
+                .as_ref()
+                .is_some_and(|message| message.starts_with("no solution"))));
+    }
+}
@@ -0,0 +1,342 @@
+//! Multi-objective optimization, keeping the Pareto front instead of a single best.
+//!
+//! An objective that returns one score per objective has no single best position, only positions
+//! no other position beats in every score at once. `MultiObjectiveOptimizer` keeps those in a
+//! `ParetoArchive` as it goes. Each turtle is pulled towards its own nondominated best and a
+//! leader drawn from the archive, preferring members in sparse stretches of the front so the
+//! swarm spreads out along it, and a few turtles are knocked off course by `mutation` every
+//! iteration so the swarm keeps finding new stretches. A full archive drops the member with the
+//! smallest crowding distance, the one whose neighbours on the front are closest to it. Every
+//! score is minimized.
+
+use core::fmt;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ndarray::Array1;
+#[cfg(feature = "std")]
+use rand::thread_rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::boundary::Boundary;
+use crate::{CubicBoundary, OptimizerParams, Turtle};
+
+/// Whether `a` is at least as good as `b` in every score and better in at least one.
+pub fn dominates(a: &Array1<f64>, b: &Array1<f64>) -> bool {
+    let mut better = false;
+    for (a, b) in a.iter().zip(b.iter()) {
+        if a > b {
+            return false;
+        }
+        better |= a < b;
+    }
+    better
+}
+
+/// A position on the front and its scores.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParetoPoint {
+    pub position: Array1<f64>,
+    pub scores: Array1<f64>,
+}
+
+/// The nondominated positions found so far, at most `capacity` of them.
+#[derive(Clone, Debug)]
+pub struct ParetoArchive {
+    pub capacity: usize,
+    members: Vec<ParetoPoint>,
+}
+
+impl ParetoArchive {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            members: Vec::new(),
+        }
+    }
+
+    pub fn front(&self) -> &[ParetoPoint] {
+        &self.members
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Adds the position unless a member dominates it or already has its scores, dropping the
+    /// members it dominates. Returns whether it was added.
+    pub fn insert(&mut self, position: &Array1<f64>, scores: &Array1<f64>) -> bool {
+        if scores.iter().any(|score| score.is_nan())
+            || self
+                .members
+                .iter()
+                .any(|member| dominates(&member.scores, scores) || member.scores == scores)
+        {
+            return false;
+        }
+        self.members
+            .retain(|member| !dominates(scores, &member.scores));
+        self.members.push(ParetoPoint {
+            position: position.clone(),
+            scores: scores.clone(),
+        });
+        while self.members.len() > self.capacity.max(1) {
+            let distances = self.crowding_distances();
+            let crowded = (0..distances.len())
+                .min_by(|a, b| distances[*a].total_cmp(&distances[*b]))
+                .expect("a full archive has members");
+            self.members.swap_remove(crowded);
+        }
+        true
+    }
+
+    /// How far every member's neighbours along the front are from it, summed over the scores
+    /// and relative to each score's range. The members at either end of a score are infinitely
+    /// far from crowded.
+    pub fn crowding_distances(&self) -> Vec<f64> {
+        let mut distances = Vec::new();
+        distances.resize(self.members.len(), 0.);
+        let objectives = self.members.first().map_or(0, |member| member.scores.len());
+        let mut order: Vec<usize> = (0..self.members.len()).collect();
+        for objective in 0..objectives {
+            let score = |member: usize| self.members[member].scores[objective];
+            order.sort_by(|a, b| score(*a).total_cmp(&score(*b)));
+            let (Some(first), Some(last)) = (order.first(), order.last()) else {
+                continue;
+            };
+            let range = score(*last) - score(*first);
+            distances[*first] = f64::INFINITY;
+            distances[*last] = f64::INFINITY;
+            if range <= 0. {
+                continue;
+            }
+            for window in order.windows(3) {
+                distances[window[1]] += (score(window[2]) - score(window[0])) / range;
+            }
+        }
+        distances
+    }
+
+    // A leader for the swarm: the less crowded of two random members.
+    fn leader<R: Rng>(&self, distances: &[f64], rng: &mut R) -> &Array1<f64> {
+        let a = rng.gen_range(0..self.members.len());
+        let b = rng.gen_range(0..self.members.len());
+        let chosen = if distances[a] >= distances[b] { a } else { b };
+        &self.members[chosen].position
+    }
+}
+
+/// The outcome of a multi-objective run.
+#[derive(Clone, Debug)]
+pub struct ParetoResult {
+    pub front: Vec<ParetoPoint>,
+    pub iterations: usize,
+    pub turtles: usize,
+}
+
+impl fmt::Display for ParetoResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} turtles performed {} optimizer iterations for you, and found {} nondominated \
+             positions.",
+            self.turtles,
+            self.iterations,
+            self.front.len()
+        )?;
+        writeln!(f, "Below is the Pareto front: ")?;
+        for point in self.front.iter() {
+            writeln!(
+                f,
+                "\t The scores {}, were observed at {} ",
+                point.scores, point.position
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl ParetoResult {
+    #[cfg(feature = "std")]
+    pub fn report(&self) {
+        self.report_to(std::io::stdout().lock())
+            .expect("the report is printed to stdout");
+    }
+
+    /// Writes the report `report` prints to `writer` instead, such as a file or a buffer.
+    #[cfg(feature = "std")]
+    pub fn report_to<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        write!(writer, "{}", self)
+    }
+}
+
+pub struct MultiObjectiveOptimizer<'a, B = CubicBoundary> {
+    pub turtles: Vec<Turtle>,
+    pub boundaries: B,
+    pub iterations: usize,
+    pub max_iterations: usize,
+    pub params: OptimizerParams,
+    pub archive: ParetoArchive,
+    /// The chance a turtle has one of its dimensions moved at random after it moves, by up to
+    /// the boundary's width at first and less and less as the run goes on.
+    pub mutation: f64,
+    pub objective_function: &'a dyn Fn(&Array1<f64>) -> Array1<f64>,
+    // The scores at every turtle's best position, once it has been scored.
+    best_scores: Vec<Option<Array1<f64>>>,
+    rng: ChaCha12Rng,
+}
+
+impl<'a, B: Boundary> MultiObjectiveOptimizer<'a, B> {
+    /// Starts a run of `max_iterations` iterations with an archive of up to 100 positions.
+    #[cfg(feature = "std")]
+    pub fn new(
+        turtles: usize,
+        boundaries: B,
+        objective_function: &'a dyn Fn(&Array1<f64>) -> Array1<f64>,
+        max_iterations: usize,
+    ) -> Self {
+        let rng = ChaCha12Rng::from_rng(thread_rng()).expect("the rng provides a seed");
+        Self::with_rng(turtles, boundaries, objective_function, max_iterations, rng)
+    }
+
+    /// Like `new`, but the turtles are placed reproducibly from `seed`.
+    pub fn new_seeded(
+        turtles: usize,
+        boundaries: B,
+        objective_function: &'a dyn Fn(&Array1<f64>) -> Array1<f64>,
+        max_iterations: usize,
+        seed: u64,
+    ) -> Self {
+        let rng = ChaCha12Rng::seed_from_u64(seed);
+        Self::with_rng(turtles, boundaries, objective_function, max_iterations, rng)
+    }
+
+    fn with_rng(
+        turtles: usize,
+        boundaries: B,
+        objective_function: &'a dyn Fn(&Array1<f64>) -> Array1<f64>,
+        max_iterations: usize,
+        mut rng: ChaCha12Rng,
+    ) -> Self {
+        let turtles: Vec<Turtle> = (0..turtles)
+            .map(|_| Turtle::new_using(&boundaries, &mut rng))
+            .collect();
+        let mut best_scores = Vec::new();
+        best_scores.resize(turtles.len(), None);
+        Self {
+            turtles,
+            boundaries,
+            iterations: 0,
+            max_iterations,
+            params: OptimizerParams::default(),
+            archive: ParetoArchive::new(100),
+            mutation: 0.1,
+            objective_function,
+            best_scores,
+            rng,
+        }
+    }
+
+    /// Runs until `max_iterations` is spent.
+    pub fn optimize(&mut self) -> ParetoResult {
+        while self.iterations < self.max_iterations {
+            self.iterate();
+        }
+        self.result()
+    }
+
+    pub fn result(&self) -> ParetoResult {
+        ParetoResult {
+            front: self.archive.front().to_vec(),
+            iterations: self.iterations,
+            turtles: self.turtles.len(),
+        }
+    }
+
+    // Scores every turtle where it stands, updating its own best and the archive.
+    fn evaluate(&mut self) {
+        for (turtle, best) in self.turtles.iter_mut().zip(self.best_scores.iter_mut()) {
+            let scores = (self.objective_function)(&turtle.position);
+            turtle.stats.evaluations += 1;
+            // Between two positions neither dominates, a coin decides which is kept.
+            let replace = match best {
+                None => true,
+                Some(best) if dominates(&scores, best) => true,
+                Some(best) if dominates(best, &scores) => false,
+                Some(_) => self.rng.gen_bool(0.5),
+            };
+            if replace {
+                turtle.best_position = turtle.position.clone();
+                *best = Some(scores.clone());
+            }
+            if self.archive.insert(&turtle.position, &scores) {
+                turtle.stats.improvements += 1;
+            }
+        }
+    }
+
+    pub(crate) fn iterate(&mut self) {
+        if self.iterations == 0 {
+            self.evaluate();
+        }
+        if self.archive.is_empty() {
+            self.iterations += 1;
+            return;
+        }
+        let params = self.params;
+        let distances = self.archive.crowding_distances();
+        let (lower, upper) = self.boundaries.bounding_box();
+        let reach = 1. - self.iterations as f64 / self.max_iterations.max(1) as f64;
+        for turtle in self.turtles.iter_mut() {
+            let leader = self.archive.leader(&distances, &mut self.rng);
+            turtle.velocity = params.inertia * &turtle.velocity
+                + params.cognitive * (&turtle.best_position - &turtle.position)
+                + params.social * (leader - &turtle.position);
+            let previous = turtle.position.clone();
+            turtle.position = &turtle.position + &turtle.velocity;
+            if !turtle.position.is_empty() && self.rng.gen_bool(self.mutation.clamp(0., 1.)) {
+                let dimension = self.rng.gen_range(0..turtle.position.len());
+                let width = reach * (upper[dimension] - lower[dimension]);
+                turtle.position[dimension] += width * (self.rng.gen::<f64>() - 0.5);
+            }
+            self.boundaries.project(&mut turtle.position);
+            let step = (&turtle.position - &previous).mapv(|x| x * x).sum();
+            turtle.stats.displacement += crate::sqrt(step);
+        }
+        self.evaluate();
+        self.iterations += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_archive_spans_the_front() {
+        // Schaffer's problem: every position between 0 and 2 trades one score for the other.
+        let schaffer = |x: &Array1<f64>| Array1::from(vec![x[0] * x[0], (x[0] - 2.) * (x[0] - 2.)]);
+        let boundaries = CubicBoundary::new(1, -5., 5.);
+        let mut optimizer = MultiObjectiveOptimizer::new_seeded(20, boundaries, &schaffer, 50, 4);
+        optimizer.archive.capacity = 30;
+        let result = optimizer.optimize();
+
+        assert_eq!(result.iterations, 50);
+        assert!(result.front.len() > 10 && result.front.len() <= 30);
+        let along: Vec<f64> = result.front.iter().map(|point| point.position[0]).collect();
+        assert!(along.iter().all(|x| (-1e-6..=2. + 1e-6).contains(x)));
+        assert!(along.iter().any(|x| *x < 0.3) && along.iter().any(|x| *x > 1.7));
+        for a in result.front.iter() {
+            assert!(result
+                .front
+                .iter()
+                .all(|b| !dominates(&b.scores, &a.scores)));
+        }
+        assert!(result.to_string().contains("nondominated positions"));
+    }
+}
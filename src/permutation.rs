@@ -0,0 +1,347 @@
+//! Permutation turtles for ordering problems such as travelling salesmen and schedules.
+//!
+//! This follows Clerc's discrete particle swarm: a position is an ordering of `0..n`, and a
+//! velocity is a list of swaps that is applied to it. The swaps that turn a turtle's ordering into
+//! its personal best, and into the swarm's best, are each kept with a chance, so the turtle moves
+//! part of the way towards both. The old velocity is carried over swap by swap with the chance
+//! `inertia`.
+//!
+//! Runs end, and are reported, like those of the continuous optimizer, and the orderings are the
+//! positions of the `OptimizationResult`, one index per dimension.
+
+use ndarray::Array1;
+use ndarray_rand::rand::{rngs::StdRng, seq::SliceRandom, thread_rng, Rng, SeedableRng};
+
+use crate::observer::Observer;
+use crate::stopping::{Progress, StoppingCriterion};
+use crate::{Direction, OptimizationResult, Termination};
+
+/// A turtle carrying an ordering instead of a point.
+#[derive(Debug)]
+pub struct PermutationTurtle {
+    pub order: Vec<usize>,
+    velocity: Vec<(usize, usize)>,
+    pub best_score: f64,
+    pub best_order: Vec<usize>,
+}
+
+impl PermutationTurtle {
+    fn new<R: Rng + ?Sized>(length: usize, rng: &mut R) -> Self {
+        let mut order: Vec<usize> = (0..length).collect();
+        order.shuffle(rng);
+        Self {
+            best_order: order.clone(),
+            order,
+            velocity: Vec::new(),
+            best_score: f64::INFINITY,
+        }
+    }
+}
+
+/// The swaps that turn `from` into `to`, applied left to right.
+pub fn swaps_between(from: &[usize], to: &[usize]) -> Vec<(usize, usize)> {
+    let mut order = from.to_vec();
+    let mut swaps = Vec::new();
+    for i in 0..order.len() {
+        if order[i] == to[i] {
+            continue;
+        }
+        let j = (i + 1..order.len())
+            .find(|j| order[*j] == to[i])
+            .expect("both are orderings of the same items");
+        order.swap(i, j);
+        swaps.push((i, j));
+    }
+    swaps
+}
+
+fn as_array(order: &[usize]) -> Array1<f64> {
+    order.iter().map(|item| *item as f64).collect()
+}
+
+/// Minimizes an objective over the orderings of `0..length`.
+pub struct PermutationOptimizer<'a> {
+    pub turtles: Vec<PermutationTurtle>,
+    pub length: usize,
+    pub iterations: usize,
+    /// How many times the objective has been evaluated.
+    pub evaluations: usize,
+    pub best_score: f64,
+    pub best_order: Vec<usize>,
+    pub objective_function: &'a dyn Fn(&[usize]) -> f64,
+    pub goal: f64,
+    /// The most iterations `optimize` may spend in total before giving up on the goal.
+    pub max_iterations: Option<usize>,
+    /// Decides when `optimize` stops in place of the goal and `max_iterations`, see `stopping`.
+    pub stopping: Option<Box<dyn StoppingCriterion<PermutationOptimizer<'a>> + 'a>>,
+    /// Callbacks with read access at the end of every iteration, see `observer`.
+    pub observers: Vec<Box<dyn Observer<PermutationOptimizer<'a>> + 'a>>,
+    // Whether an observer has asked the run to stop.
+    observer_stop: bool,
+    /// The chance each swap of the previous velocity is kept.
+    pub inertia: f64,
+    /// The chance each swap towards the personal best is taken.
+    pub cognitive: f64,
+    /// The chance each swap towards the swarm's best is taken.
+    pub social: f64,
+    /// Velocities are cut to this many swaps, so no turtle leaps across the whole space.
+    pub max_swaps: usize,
+    rng: StdRng,
+}
+
+impl<'a> PermutationOptimizer<'a> {
+    pub fn new(
+        turtles: usize,
+        length: usize,
+        objective_function: &'a dyn Fn(&[usize]) -> f64,
+        goal: f64,
+    ) -> Self {
+        let rng = StdRng::from_rng(thread_rng()).expect("the thread rng does not fail");
+        Self::new_using(turtles, length, objective_function, goal, rng)
+    }
+
+    /// Like `new`, but the run is reproducible from `seed`.
+    pub fn new_seeded(
+        turtles: usize,
+        length: usize,
+        objective_function: &'a dyn Fn(&[usize]) -> f64,
+        goal: f64,
+        seed: u64,
+    ) -> Self {
+        let rng = StdRng::seed_from_u64(seed);
+        Self::new_using(turtles, length, objective_function, goal, rng)
+    }
+
+    fn new_using(
+        turtles: usize,
+        length: usize,
+        objective_function: &'a dyn Fn(&[usize]) -> f64,
+        goal: f64,
+        mut rng: StdRng,
+    ) -> Self {
+        Self {
+            turtles: (0..turtles)
+                .map(|_| PermutationTurtle::new(length, &mut rng))
+                .collect(),
+            length,
+            iterations: 0,
+            evaluations: 0,
+            best_score: f64::INFINITY,
+            best_order: (0..length).collect(),
+            objective_function,
+            goal,
+            max_iterations: None,
+            stopping: None,
+            observers: Vec::new(),
+            observer_stop: false,
+            inertia: 0.5,
+            cognitive: 0.5,
+            social: 0.5,
+            max_swaps: length,
+            rng,
+        }
+    }
+
+    fn evaluate(&mut self) {
+        for turtle in self.turtles.iter_mut() {
+            let score = (self.objective_function)(&turtle.order);
+            self.evaluations += 1;
+            if score < turtle.best_score {
+                turtle.best_score = score;
+                turtle.best_order = turtle.order.clone();
+                if score < self.best_score {
+                    self.best_score = score;
+                    self.best_order = turtle.order.clone();
+                }
+            }
+        }
+    }
+
+    fn update(&mut self) {
+        for turtle in self.turtles.iter_mut() {
+            let rng = &mut self.rng;
+            let mut velocity: Vec<(usize, usize)> = turtle
+                .velocity
+                .iter()
+                .copied()
+                .filter(|_| rng.gen_bool(self.inertia.clamp(0., 1.)))
+                .collect();
+            velocity.extend(
+                swaps_between(&turtle.order, &turtle.best_order)
+                    .into_iter()
+                    .filter(|_| rng.gen_bool(self.cognitive.clamp(0., 1.))),
+            );
+            velocity.extend(
+                swaps_between(&turtle.order, &self.best_order)
+                    .into_iter()
+                    .filter(|_| rng.gen_bool(self.social.clamp(0., 1.))),
+            );
+            // A turtle sitting on both bests without momentum takes one random swap instead.
+            if velocity.is_empty() && self.length > 1 {
+                let i = rng.gen_range(0..self.length);
+                let j = rng.gen_range(0..self.length);
+                velocity.push((i, j));
+            }
+            velocity.truncate(self.max_swaps);
+            for (i, j) in velocity.iter() {
+                turtle.order.swap(*i, *j);
+            }
+            turtle.velocity = velocity;
+        }
+    }
+
+    /// Iterates until the best observed score matches the goal, or the run is stopped otherwise.
+    pub fn optimize(&mut self) -> OptimizationResult {
+        loop {
+            if let Some(termination) = self.termination() {
+                return self.result(termination);
+            }
+            self.iterate();
+        }
+    }
+
+    /// Moves the turtles once. Returns why `optimize` would stop here, if it would.
+    pub fn step(&mut self) -> Option<Termination> {
+        self.iterate();
+        self.termination()
+    }
+
+    fn result(&self, termination: Termination) -> OptimizationResult {
+        OptimizationResult {
+            best_position: as_array(&self.best_order),
+            best_score: self.best_score,
+            iterations: self.iterations,
+            evaluations: self.evaluations,
+            turtle_bests: self
+                .turtles
+                .iter()
+                .map(|turtle| (turtle.best_score, as_array(&turtle.best_order)))
+                .collect(),
+            termination,
+        }
+    }
+
+    // Why the run should stop now, if it should, in the same order as the continuous optimizer.
+    fn termination(&mut self) -> Option<Termination> {
+        if core::mem::take(&mut self.observer_stop) {
+            return Some(Termination::Observer);
+        }
+        if let Some(mut criterion) = self.stopping.take() {
+            let reason = criterion.check(self);
+            self.stopping = Some(criterion);
+            return reason;
+        }
+        if Direction::Minimize.reaches(self.best_score, self.goal) {
+            Some(Termination::GoalReached)
+        } else if self
+            .max_iterations
+            .is_some_and(|limit| self.iterations >= limit)
+        {
+            Some(Termination::IterationLimit)
+        } else {
+            None
+        }
+    }
+
+    fn notify_observers(&mut self) {
+        let mut observers = core::mem::take(&mut self.observers);
+        for observer in observers.iter_mut() {
+            if observer.observe(self).is_break() {
+                self.observer_stop = true;
+            }
+        }
+        observers.append(&mut self.observers);
+        self.observers = observers;
+    }
+
+    pub(crate) fn iterate(&mut self) {
+        self.evaluate();
+        self.update();
+        self.iterations += 1;
+        self.notify_observers();
+    }
+
+    /// Reports the results of a completed optimization to stdout.
+    pub fn report(&self) {
+        self.report_to(std::io::stdout().lock())
+            .expect("the report is printed to stdout");
+    }
+
+    /// Writes the report `report` prints to `writer` instead, such as a file or a buffer.
+    pub fn report_to<W: std::io::Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writeln!(
+            writer,
+            "{} turtles performed {} optimizer iterations for you.",
+            self.turtles.len(),
+            self.iterations
+        )?;
+        writeln!(
+            writer,
+            "The objective was evaluated {} times.",
+            self.evaluations
+        )?;
+        writeln!(
+            writer,
+            "The best score: {} was observed in the order: {:?}",
+            self.best_score, self.best_order
+        )?;
+        writeln!(writer, "Below is a complete run down of the best orders: ")?;
+
+        for (turtle_number, turtle) in self.turtles.iter().enumerate() {
+            writeln!(
+                writer,
+                "\t Turtle #{}'s best score {}, was observed in {:?} ",
+                turtle_number, turtle.best_score, turtle.best_order
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Progress for PermutationOptimizer<'_> {
+    fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    fn best_score(&self) -> f64 {
+        self.best_score
+    }
+
+    fn goal(&self) -> f64 {
+        self.goal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn salesmen_find_the_round_trip() {
+        // Eight cities around a circle, visited in a scrambled order. The shortest round trip
+        // goes around the circle.
+        let scrambled = [3, 6, 0, 5, 2, 7, 4, 1];
+        let city = |i: usize| {
+            let angle = scrambled[i] as f64 * core::f64::consts::PI / 4.;
+            (angle.cos(), angle.sin())
+        };
+        let tour = |order: &[usize]| {
+            (0..order.len())
+                .map(|i| {
+                    let (a, b) = (city(order[i]), city(order[(i + 1) % order.len()]));
+                    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+                })
+                .sum::<f64>()
+        };
+        let shortest = 16. * (core::f64::consts::PI / 8.).sin();
+        let mut optimizer = PermutationOptimizer::new_seeded(20, 8, &tour, shortest + 1e-9, 4);
+        optimizer.max_iterations = Some(500);
+        let result = optimizer.optimize();
+
+        assert_eq!(result.termination, Termination::GoalReached);
+        let mut visited = optimizer.best_order.clone();
+        visited.sort();
+        assert_eq!(visited, (0..8).collect::<Vec<usize>>());
+        assert_eq!(swaps_between(&[2, 0, 1], &[0, 1, 2]), vec![(0, 1), (1, 2)]);
+    }
+}
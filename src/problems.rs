@@ -0,0 +1,315 @@
+//! Shifted, rotated and hybrid-composed test problems in the style of the CEC benchmark suites.
+//!
+//! Base functions are expected to have their global optimum at the origin. The generator moves that
+//! optimum to a random location, mixes the coordinates with a random rotation so the landscape is
+//! no longer separable, and can glue several base functions together. Every generated problem
+//! knows where its optimum lies and what it scores.
+
+use ndarray::{Array1, Array2};
+use ndarray_rand::rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use ndarray_rand::rand_distr::StandardNormal;
+
+use crate::CubicBoundary;
+
+/// A function with its global optimum at the origin.
+pub type BaseFunction<'a> = Box<dyn Fn(&Array1<f64>) -> f64 + Send + Sync + 'a>;
+
+/// One of the functions blended together by [`Generator::composition`].
+pub struct Component<'a> {
+    pub function: BaseFunction<'a>,
+    /// How far the component's basin of attraction reaches.
+    pub sigma: f64,
+    /// Scales the component's values relative to the others.
+    pub lambda: f64,
+    /// Added to the component's values. The component with the smallest bias holds the optimum.
+    pub bias: f64,
+}
+
+struct Transform {
+    shift: Array1<f64>,
+    rotation: Array2<f64>,
+}
+
+impl Transform {
+    fn apply(&self, position: &Array1<f64>) -> Array1<f64> {
+        self.rotation.dot(&(position - &self.shift))
+    }
+}
+
+enum Kind<'a> {
+    Transformed {
+        base: BaseFunction<'a>,
+        transform: Transform,
+    },
+    Hybrid {
+        parts: Vec<(BaseFunction<'a>, Vec<usize>)>,
+        transform: Transform,
+    },
+    Composition {
+        components: Vec<(Component<'a>, Transform)>,
+    },
+}
+
+/// A generated problem together with its known optimum.
+pub struct Generated<'a> {
+    kind: Kind<'a>,
+    bias: f64,
+    pub boundaries: CubicBoundary,
+    pub optimum_position: Array1<f64>,
+    pub optimum_value: f64,
+}
+
+impl Generated<'_> {
+    pub fn evaluate(&self, position: &Array1<f64>) -> f64 {
+        let value = match &self.kind {
+            Kind::Transformed { base, transform } => base(&transform.apply(position)),
+            Kind::Hybrid { parts, transform } => {
+                let z = transform.apply(position);
+                parts
+                    .iter()
+                    .map(|(base, dimensions)| base(&dimensions.iter().map(|d| z[*d]).collect()))
+                    .sum()
+            }
+            Kind::Composition { components } => composition(components, position),
+        };
+        value + self.bias
+    }
+}
+
+fn composition(components: &[(Component<'_>, Transform)], position: &Array1<f64>) -> f64 {
+    let dimensions = position.len() as f64;
+    let distances: Vec<f64> = components
+        .iter()
+        .map(|(_, transform)| (position - &transform.shift).mapv(|x| x * x).sum())
+        .collect();
+
+    // Exactly on a component's optimum the weights degenerate; that component alone counts.
+    let weights: Vec<f64> = match distances.iter().position(|distance| *distance == 0.) {
+        Some(exact) => (0..components.len())
+            .map(|i| if i == exact { 1. } else { 0. })
+            .collect(),
+        None => components
+            .iter()
+            .zip(distances.iter())
+            .map(|((component, _), distance)| {
+                (-distance / (2. * dimensions * component.sigma * component.sigma)).exp()
+                    / distance.sqrt()
+            })
+            .collect(),
+    };
+    let total: f64 = weights.iter().sum();
+    if total == 0. {
+        return f64::INFINITY;
+    }
+
+    components
+        .iter()
+        .zip(weights.iter())
+        .map(|((component, transform), weight)| {
+            let value = component.lambda * (component.function)(&transform.apply(position));
+            weight / total * (value + component.bias)
+        })
+        .sum()
+}
+
+/// Draws random shifts and rotations for problems inside `boundaries`.
+pub struct Generator {
+    boundaries: CubicBoundary,
+    rng: StdRng,
+}
+
+impl Generator {
+    pub fn new(boundaries: CubicBoundary, seed: u64) -> Self {
+        Self {
+            boundaries,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// A random optimum location, kept within the central 80% of the search space as the CEC
+    /// suites do so optima never sit on the boundary.
+    pub fn shift(&mut self) -> Array1<f64> {
+        let center = (self.boundaries.lower + self.boundaries.upper) / 2.;
+        let reach = 0.4 * (self.boundaries.upper - self.boundaries.lower);
+        (0..self.boundaries.shape)
+            .map(|_| center + self.rng.gen_range(-1.0..=1.0) * reach)
+            .collect()
+    }
+
+    /// A random orthogonal matrix, from Gram–Schmidt on Gaussian rows.
+    pub fn rotation(&mut self) -> Array2<f64> {
+        let n = self.boundaries.shape;
+        let mut rotation = Array2::<f64>::zeros((n, n));
+        let mut row = 0;
+        while row < n {
+            let mut candidate: Array1<f64> =
+                (0..n).map(|_| self.rng.sample(StandardNormal)).collect();
+            for previous in 0..row {
+                let basis = rotation.row(previous).to_owned();
+                candidate = &candidate - &(candidate.dot(&basis) * &basis);
+            }
+            let norm = candidate.dot(&candidate).sqrt();
+            // A nearly dependent draw is simply retried.
+            if norm > 1e-8 {
+                rotation.row_mut(row).assign(&(candidate / norm));
+                row += 1;
+            }
+        }
+        rotation
+    }
+
+    /// `base` with its optimum moved to a random location but no rotation.
+    pub fn shifted<'a>(&mut self, base: BaseFunction<'a>, bias: f64) -> Generated<'a> {
+        let transform = Transform {
+            shift: self.shift(),
+            rotation: Array2::eye(self.boundaries.shape),
+        };
+        self.finish(Kind::Transformed { base, transform }, bias)
+    }
+
+    /// `base` with a random optimum location and a random rotation.
+    pub fn shifted_rotated<'a>(&mut self, base: BaseFunction<'a>, bias: f64) -> Generated<'a> {
+        let transform = Transform {
+            shift: self.shift(),
+            rotation: self.rotation(),
+        };
+        self.finish(Kind::Transformed { base, transform }, bias)
+    }
+
+    /// Shuffles the shifted and rotated coordinates and hands consecutive groups of them to
+    /// different base functions. Each part is paired with the share of dimensions it receives.
+    pub fn hybrid<'a>(&mut self, parts: Vec<(BaseFunction<'a>, f64)>, bias: f64) -> Generated<'a> {
+        let n = self.boundaries.shape;
+        let mut permutation: Vec<usize> = (0..n).collect();
+        permutation.shuffle(&mut self.rng);
+
+        let total: f64 = parts.iter().map(|(_, share)| share.max(0.)).sum();
+        let count = parts.len();
+        let mut start = 0;
+        let mut grouped = Vec::with_capacity(count);
+        for (index, (base, share)) in parts.into_iter().enumerate() {
+            // The last part takes whatever rounding left over.
+            let end = if index + 1 == count {
+                n
+            } else {
+                (start + (share.max(0.) / total * n as f64).round() as usize).min(n)
+            };
+            grouped.push((base, permutation[start..end].to_vec()));
+            start = end;
+        }
+
+        let transform = Transform {
+            shift: self.shift(),
+            rotation: self.rotation(),
+        };
+        self.finish(
+            Kind::Hybrid {
+                parts: grouped,
+                transform,
+            },
+            bias,
+        )
+    }
+
+    /// Blends shifted and rotated components with distance-based weights, giving a landscape
+    /// with one funnel per component.
+    pub fn composition<'a>(&mut self, components: Vec<Component<'a>>, bias: f64) -> Generated<'a> {
+        let components = components
+            .into_iter()
+            .map(|component| {
+                let transform = Transform {
+                    shift: self.shift(),
+                    rotation: self.rotation(),
+                };
+                (component, transform)
+            })
+            .collect();
+        self.finish(Kind::Composition { components }, bias)
+    }
+
+    fn finish<'a>(&self, kind: Kind<'a>, bias: f64) -> Generated<'a> {
+        let optimum_position = match &kind {
+            Kind::Transformed { transform, .. } | Kind::Hybrid { transform, .. } => {
+                transform.shift.clone()
+            }
+            Kind::Composition { components } => components
+                .iter()
+                .min_by(|a, b| a.0.bias.total_cmp(&b.0.bias))
+                .map(|(_, transform)| transform.shift.clone())
+                .unwrap_or_else(|| Array1::zeros(self.boundaries.shape)),
+        };
+        let mut generated = Generated {
+            kind,
+            bias,
+            boundaries: self.boundaries,
+            optimum_position,
+            optimum_value: 0.,
+        };
+        generated.optimum_value = generated.evaluate(&generated.optimum_position);
+        generated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sphere() -> BaseFunction<'static> {
+        Box::new(|x: &Array1<f64>| x.mapv(|x| x * x).sum())
+    }
+
+    fn rastrigin() -> BaseFunction<'static> {
+        Box::new(|x: &Array1<f64>| {
+            x.mapv(|x| x * x - 10. * (2. * std::f64::consts::PI * x).cos() + 10.)
+                .sum()
+        })
+    }
+
+    #[test]
+    fn optima_are_known_and_minimal() {
+        let boundaries = CubicBoundary::new(6, -100., 100.);
+        let mut generator = Generator::new(boundaries, 3);
+        let problems = [
+            generator.shifted_rotated(sphere(), 100.),
+            generator.hybrid(vec![(sphere(), 0.5), (rastrigin(), 0.5)], 200.),
+            generator.composition(
+                vec![
+                    Component {
+                        function: sphere(),
+                        sigma: 10.,
+                        lambda: 1.,
+                        bias: 0.,
+                    },
+                    Component {
+                        function: rastrigin(),
+                        sigma: 20.,
+                        lambda: 1.,
+                        bias: 100.,
+                    },
+                ],
+                300.,
+            ),
+        ];
+
+        let mut rng = StdRng::seed_from_u64(1);
+        for (problem, bias) in problems.iter().zip([100., 200., 300.]) {
+            assert!((problem.optimum_value - bias).abs() < 1e-9);
+            assert!(problem.optimum_position.iter().all(|x| x.abs() <= 80.));
+            for _ in 0..50 {
+                let probe: Array1<f64> = (0..6).map(|_| rng.gen_range(-100.0..100.0)).collect();
+                assert!(problem.evaluate(&probe) >= problem.optimum_value);
+            }
+        }
+    }
+
+    #[test]
+    fn rotations_are_orthogonal() {
+        let mut generator = Generator::new(CubicBoundary::new(5, -1., 1.), 8);
+        let rotation = generator.rotation();
+        let identity = rotation.dot(&rotation.t());
+        for ((i, j), value) in identity.indexed_iter() {
+            let expected = if i == j { 1. } else { 0. };
+            assert!((value - expected).abs() < 1e-12);
+        }
+    }
+}
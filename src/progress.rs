@@ -0,0 +1,102 @@
+//! Live progress of a run, sent over a channel.
+//!
+//! A GUI or a TUI rendering convergence wants every iteration as it happens, without polling a
+//! `Watch` and without missing any. `Optimizer::progress_channel` installs an observer that sends
+//! a `ProgressEvent` at the end of every iteration and returns the receiving end, which can live
+//! on another thread. Runs on an `OptimizationHandle` set it up in their `build` closure with
+//! `send_progress`. A receiver that hangs up does not stop the run; the events are dropped.
+
+use std::ops::ControlFlow;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use ndarray::Array1;
+
+use crate::boundary::Boundary;
+use crate::observer::Observer;
+use crate::Optimizer;
+
+/// The state of a run at the end of an iteration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProgressEvent {
+    pub iteration: usize,
+    pub best_score: f64,
+    pub best_position: Array1<f64>,
+    pub evaluations: usize,
+    /// See `Optimizer::diversity`.
+    pub diversity: f64,
+}
+
+/// An observer sending a `ProgressEvent` after every iteration.
+#[derive(Clone, Debug)]
+pub struct ProgressSender(pub Sender<ProgressEvent>);
+
+impl<B: Boundary> Observer<Optimizer<'_, B>> for ProgressSender {
+    fn observe(&mut self, optimizer: &Optimizer<'_, B>) -> ControlFlow<()> {
+        let _ = self.0.send(ProgressEvent {
+            iteration: optimizer.iterations,
+            best_score: optimizer.best_score,
+            best_position: optimizer.best_position.clone(),
+            evaluations: optimizer.evaluations,
+            diversity: optimizer.diversity(),
+        });
+        ControlFlow::Continue(())
+    }
+}
+
+impl<'a, B: Boundary + 'a> Optimizer<'a, B> {
+    /// Sends a `ProgressEvent` to `sender` after every iteration from now on.
+    pub fn send_progress(&mut self, sender: Sender<ProgressEvent>) {
+        self.observers.push(Box::new(ProgressSender(sender)));
+    }
+
+    /// A receiver of a `ProgressEvent` after every iteration from now on.
+    pub fn progress_channel(&mut self) -> Receiver<ProgressEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.send_progress(sender);
+        receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handle::OptimizationHandle;
+    use crate::CubicBoundary;
+
+    fn parabola(x: &Array1<f64>) -> f64 {
+        x.mapv(|x| x * x).sum()
+    }
+
+    #[test]
+    fn every_iteration_is_sent() {
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(5, boundaries, &parabola, -1., 3);
+        optimizer.max_iterations = Some(6);
+        let receiver = optimizer.progress_channel();
+        optimizer.optimize();
+        let events: Vec<ProgressEvent> = receiver.try_iter().collect();
+        assert_eq!(
+            events
+                .iter()
+                .map(|event| event.iteration)
+                .collect::<Vec<_>>(),
+            (1..=6).collect::<Vec<_>>()
+        );
+        assert!(events
+            .windows(2)
+            .all(|pair| pair[1].best_score <= pair[0].best_score));
+        assert_eq!(events[5].best_position, optimizer.best_position);
+
+        // A spawned run sends from its own thread until it is stopped.
+        let (sender, receiver) = mpsc::channel();
+        let mut optimizer = Optimizer::new_seeded(5, boundaries, &parabola, -1., 3);
+        optimizer.send_progress(sender);
+        let handle = OptimizationHandle::spawn(optimizer);
+        let first = receiver.recv().unwrap();
+        assert_eq!(first.iteration, 1);
+        handle.cancel();
+        let result = handle.join().unwrap();
+        let last = receiver.iter().last().unwrap_or(first);
+        assert_eq!(last.iteration, result.iterations);
+    }
+}
@@ -0,0 +1,132 @@
+//! Python bindings, enabled with the `python` feature.
+//!
+//! The objective is any Python callable taking a numpy array and returning a float. Results come
+//! back as plain dictionaries so they can be inspected from a notebook without extra wrappers.
+//! The extension module is built from the `bindings` crate with `maturin develop`.
+
+use ndarray::Array1;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+
+use crate::{external_objective, CubicBoundary, Optimizer, OptimizerParams};
+
+/// optimize(objective, dimensions, lower, upper, goal, turtles=32, max_iterations=None, seed=None,
+///          params=None)
+/// --
+///
+/// Minimizes `objective` inside the cube `[lower, upper] ** dimensions` until the best score
+/// reaches `goal`, or until `max_iterations` have been spent when it is given. `params` is an
+/// `(inertia, cognitive, social)` tuple of coefficients for the velocity update.
+#[pyfunction]
+#[pyo3(signature = (
+    objective,
+    dimensions,
+    lower,
+    upper,
+    goal,
+    turtles = 32,
+    max_iterations = None,
+    seed = None,
+    params = None,
+))]
+#[allow(clippy::too_many_arguments)]
+fn optimize<'py>(
+    py: Python<'py>,
+    objective: Bound<'py, PyAny>,
+    dimensions: usize,
+    lower: f64,
+    upper: f64,
+    goal: f64,
+    turtles: usize,
+    max_iterations: Option<usize>,
+    seed: Option<u64>,
+    params: Option<(f64, f64, f64)>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let numpy = py.import("numpy")?;
+    let boundaries = CubicBoundary::new(dimensions, lower, upper);
+    let mut optimizer = match seed {
+        Some(seed) => Optimizer::new_seeded(turtles, boundaries, &external_objective, goal, seed),
+        None => Optimizer::new(turtles, boundaries, &external_objective, goal),
+    };
+    optimizer.max_iterations = max_iterations;
+    if let Some((inertia, cognitive, social)) = params {
+        optimizer.params = OptimizerParams {
+            inertia,
+            cognitive,
+            social,
+        };
+    }
+    // Python objects are tied to the interpreter's thread, so the positions are scored through ask
+    // and tell. The first Python exception, or a pending signal, stops the run and is re-raised.
+    let outcome = optimizer.optimize_with(|position| {
+        py.check_signals()?;
+        to_numpy(&numpy, position)
+            .and_then(|array| objective.call1((array,)))
+            .and_then(|score| score.extract::<f64>())
+    })?;
+
+    let result = PyDict::new(py);
+    result.set_item("best_score", outcome.best_score)?;
+    result.set_item("best_position", to_numpy(&numpy, &outcome.best_position)?)?;
+    result.set_item("iterations", outcome.iterations)?;
+    result.set_item("evaluations", outcome.evaluations)?;
+    result.set_item("termination", outcome.termination.to_string())?;
+    let turtle_bests = PyList::empty(py);
+    for (best_score, best_position) in outcome.turtle_bests.iter() {
+        let entry = PyDict::new(py);
+        entry.set_item("best_score", best_score)?;
+        entry.set_item("best_position", to_numpy(&numpy, best_position)?)?;
+        turtle_bests.append(entry)?;
+    }
+    result.set_item("turtles", turtle_bests)?;
+    Ok(result)
+}
+
+fn to_numpy<'py>(
+    numpy: &Bound<'py, PyModule>,
+    values: &Array1<f64>,
+) -> PyResult<Bound<'py, PyAny>> {
+    numpy.call_method1("array", (values.to_vec(),))
+}
+
+#[pymodule]
+fn turtle_swarm_optimizer(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(optimize, module)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Termination;
+    use pyo3::ffi::c_str;
+
+    // The bindings hand numpy arrays to the objective, so this needs an interpreter with numpy:
+    // `cargo test --features python -- --ignored`.
+    #[test]
+    #[ignore = "needs numpy"]
+    fn python_objectives_are_minimized() {
+        Python::initialize();
+        Python::attach(|py| {
+            let objective = py
+                .eval(c_str!("lambda x: float((x * x).sum())"), None, None)
+                .unwrap();
+            let result =
+                optimize(py, objective, 2, -1., 1., 0., 8, Some(3), Some(1), None).unwrap();
+            let iterations: usize = result
+                .get_item("iterations")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(iterations, 3);
+            let termination: String = result
+                .get_item("termination")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(termination, Termination::IterationLimit.to_string());
+        });
+    }
+}
@@ -0,0 +1,102 @@
+//! Rate limiting for objectives backed by throttled or paid APIs.
+//!
+//! A `RateLimited` objective lets at most `max_evaluations` calls through in any window of
+//! `period`. Calls over the limit wait their turn instead of failing, so every turtle is still
+//! scored in order and an iteration means what it always does, it just takes longer. The limiter
+//! is shared safely between threads, which queue on it together.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use ndarray::Array1;
+
+pub struct RateLimited<'a> {
+    objective_function: &'a (dyn Fn(&Array1<f64>) -> f64 + Sync),
+    pub max_evaluations: usize,
+    pub period: Duration,
+    // When the calls in the current window started, oldest first.
+    calls: Mutex<VecDeque<Instant>>,
+}
+
+impl<'a> RateLimited<'a> {
+    pub fn new(
+        objective_function: &'a (dyn Fn(&Array1<f64>) -> f64 + Sync),
+        max_evaluations: usize,
+        period: Duration,
+    ) -> Self {
+        Self {
+            objective_function,
+            max_evaluations: max_evaluations.max(1),
+            period,
+            calls: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn per_second(
+        objective_function: &'a (dyn Fn(&Array1<f64>) -> f64 + Sync),
+        max_evaluations: usize,
+    ) -> Self {
+        Self::new(objective_function, max_evaluations, Duration::from_secs(1))
+    }
+
+    pub fn per_minute(
+        objective_function: &'a (dyn Fn(&Array1<f64>) -> f64 + Sync),
+        max_evaluations: usize,
+    ) -> Self {
+        Self::new(objective_function, max_evaluations, Duration::from_secs(60))
+    }
+
+    // Blocks until another call fits in the window and claims its slot.
+    fn acquire(&self) {
+        // A poisoned lock only means another caller panicked; the timestamps are still usable.
+        let mut calls = self
+            .calls
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        loop {
+            let now = Instant::now();
+            while calls
+                .front()
+                .is_some_and(|start| now.duration_since(*start) >= self.period)
+            {
+                calls.pop_front();
+            }
+            if calls.len() < self.max_evaluations.max(1) {
+                calls.push_back(now);
+                return;
+            }
+            // Holding the lock while sleeping keeps waiting callers in line.
+            let oldest = calls[0];
+            thread::sleep(self.period.saturating_sub(now.duration_since(oldest)));
+        }
+    }
+
+    pub fn evaluate(&self, position: &Array1<f64>) -> f64 {
+        self.acquire();
+        (self.objective_function)(position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calls_over_the_limit_wait_for_the_window() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let limited = RateLimited::new(&parabola, 4, Duration::from_millis(50));
+        let position = Array1::ones(2);
+
+        let start = Instant::now();
+        for _ in 0..4 {
+            assert_eq!(limited.evaluate(&position), 2.);
+        }
+        assert!(start.elapsed() < Duration::from_millis(50));
+        for _ in 0..5 {
+            limited.evaluate(&position);
+        }
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+}
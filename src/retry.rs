@@ -0,0 +1,170 @@
+//! Retries with exponential backoff for objectives that fail transiently.
+//!
+//! Flaky simulators and remote services fail now and then for reasons that have nothing to do
+//! with the position they were asked about. A `Retrying` objective calls a fallible objective
+//! again after a growing, jittered pause until it succeeds or the attempts run out, and only
+//! then falls back to treating the position as infinitely bad.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use ndarray::Array1;
+use ndarray_rand::rand::{thread_rng, Rng};
+
+/// How often and how patiently to retry.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// Calls in total, including the first one.
+    pub max_attempts: usize,
+    /// The pause before the first retry.
+    pub initial_backoff: Duration,
+    /// How much the pause grows after each failed retry.
+    pub multiplier: f64,
+    pub max_backoff: Duration,
+    /// The share of each pause that is randomized away, between 0 and 1, so a fleet of workers
+    /// does not retry in lockstep.
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            multiplier: 2.,
+            max_backoff: Duration::from_secs(10),
+            jitter: 0.5,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The pause after `failures` failed attempts, before jitter.
+    pub fn backoff(&self, failures: usize) -> Duration {
+        let exponent = failures.saturating_sub(1).min(i32::MAX as usize) as i32;
+        let seconds = self.initial_backoff.as_secs_f64() * self.multiplier.max(1.).powi(exponent);
+        Duration::try_from_secs_f64(seconds)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+
+    fn pause(&self, failures: usize) {
+        let backoff = self.backoff(failures);
+        let jitter = self.jitter.clamp(0., 1.);
+        let keep = if jitter > 0. {
+            1. - jitter * thread_rng().gen::<f64>()
+        } else {
+            1.
+        };
+        thread::sleep(backoff.mul_f64(keep));
+    }
+}
+
+type Transient<'a, E> = Box<dyn Fn(&E) -> bool + Send + Sync + 'a>;
+
+pub struct Retrying<'a, E> {
+    objective_function: &'a (dyn Fn(&Array1<f64>) -> Result<f64, E> + Sync),
+    pub policy: RetryPolicy,
+    transient: Transient<'a, E>,
+    retries: AtomicUsize,
+    failures: AtomicUsize,
+}
+
+impl<'a, E> Retrying<'a, E> {
+    /// Retries every error by `policy`.
+    pub fn new(
+        objective_function: &'a (dyn Fn(&Array1<f64>) -> Result<f64, E> + Sync),
+        policy: RetryPolicy,
+    ) -> Self {
+        Self {
+            objective_function,
+            policy,
+            transient: Box::new(|_| true),
+            retries: AtomicUsize::new(0),
+            failures: AtomicUsize::new(0),
+        }
+    }
+
+    /// Only errors for which `transient` holds are retried; others fail straight away.
+    pub fn set_transient(&mut self, transient: impl Fn(&E) -> bool + Send + Sync + 'a) {
+        self.transient = Box::new(transient);
+    }
+
+    /// The objective's score, or the last error once retrying has given up.
+    pub fn try_evaluate(&self, position: &Array1<f64>) -> Result<f64, E> {
+        let mut failures = 0;
+        loop {
+            match (self.objective_function)(position) {
+                Ok(score) => return Ok(score),
+                Err(error) => {
+                    failures += 1;
+                    if failures >= self.policy.max_attempts || !(self.transient)(&error) {
+                        self.failures.fetch_add(1, Ordering::Relaxed);
+                        return Err(error);
+                    }
+                    self.retries.fetch_add(1, Ordering::Relaxed);
+                    self.policy.pause(failures);
+                }
+            }
+        }
+    }
+
+    /// The objective's score. Positions that could not be scored are infinitely bad.
+    pub fn evaluate(&self, position: &Array1<f64>) -> f64 {
+        self.try_evaluate(position).unwrap_or(f64::INFINITY)
+    }
+
+    /// How many calls were repeated after an error.
+    pub fn retries(&self) -> usize {
+        self.retries.load(Ordering::Relaxed)
+    }
+
+    /// How many positions could not be scored at all.
+    pub fn failures(&self) -> usize {
+        self.failures.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transient_failures_are_retried_with_growing_pauses() {
+        let policy = RetryPolicy {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(1),
+            multiplier: 3.,
+            max_backoff: Duration::from_millis(5),
+            jitter: 0.5,
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(1));
+        assert_eq!(policy.backoff(2), Duration::from_millis(3));
+        assert_eq!(policy.backoff(3), Duration::from_millis(5));
+
+        // Fails twice per position, then succeeds; negative positions fail for good.
+        let calls = AtomicUsize::new(0);
+        let flaky = |x: &Array1<f64>| -> Result<f64, &'static str> {
+            if x[0] < 0. {
+                return Err("bad input");
+            }
+            match calls.fetch_add(1, Ordering::Relaxed) % 3 {
+                2 => Ok(x[0]),
+                _ => Err("timeout"),
+            }
+        };
+        let mut retrying = Retrying::new(&flaky, policy);
+        retrying.set_transient(|error| *error == "timeout");
+
+        assert_eq!(retrying.evaluate(&Array1::ones(1)), 1.);
+        assert_eq!(retrying.retries(), 2);
+        assert_eq!(retrying.try_evaluate(&-Array1::ones(1)), Err("bad input"));
+        assert_eq!(retrying.retries(), 2);
+        assert_eq!(retrying.failures(), 1);
+    }
+}
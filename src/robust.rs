@@ -0,0 +1,95 @@
+//! Robust optimization, where a position is only as good as its neighbourhood.
+//!
+//! A `Robust` objective scores each position by the worst case, or a high quantile, of the
+//! objective over a set of perturbations around it, so the design the turtles return tolerates
+//! manufacturing tolerances or noise on its inputs. Every score costs one evaluation per
+//! perturbation.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ndarray::Array1;
+
+use crate::aggregation::Aggregation;
+
+pub struct Robust<'a> {
+    objective_function: &'a (dyn Fn(&Array1<f64>) -> f64 + Sync),
+    /// Offsets added to a position before it is evaluated.
+    pub perturbations: Vec<Array1<f64>>,
+    pub aggregation: Aggregation,
+}
+
+impl<'a> Robust<'a> {
+    /// A minimax objective over `perturbations`.
+    pub fn new(
+        objective_function: &'a (dyn Fn(&Array1<f64>) -> f64 + Sync),
+        perturbations: Vec<Array1<f64>>,
+    ) -> Self {
+        Self {
+            objective_function,
+            perturbations,
+            aggregation: Aggregation::Worst,
+        }
+    }
+
+    /// The unperturbed position and every corner of the box `position ± deltas`, which is
+    /// `2^d + 1` perturbations for `d` dimensions.
+    pub fn corners(deltas: &[f64]) -> Vec<Array1<f64>> {
+        let mut perturbations = Vec::with_capacity((1 << deltas.len()) + 1);
+        perturbations.push(Array1::zeros(deltas.len()));
+        for corner in 0..1usize << deltas.len() {
+            perturbations.push(
+                deltas
+                    .iter()
+                    .enumerate()
+                    .map(|(dimension, delta)| {
+                        if corner & (1 << dimension) == 0 {
+                            -delta
+                        } else {
+                            *delta
+                        }
+                    })
+                    .collect(),
+            );
+        }
+        perturbations
+    }
+
+    pub fn evaluate(&self, position: &Array1<f64>) -> f64 {
+        let mut scores: Vec<f64> = self
+            .perturbations
+            .iter()
+            .map(|perturbation| (self.objective_function)(&(position + perturbation)))
+            .collect();
+        self.aggregation.apply(&mut scores)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CubicBoundary, Optimizer};
+
+    #[test]
+    fn robust_optima_prefer_wide_basins() {
+        // A deep but narrow well at -0.5 and a shallower, wide one at 0.5.
+        let wells = |x: &Array1<f64>| {
+            let position = x[0];
+            if (position + 0.5).abs() < 0.02 {
+                -10.
+            } else {
+                (position - 0.5).powi(2) - 1.
+            }
+        };
+        let robust = Robust::new(&wells, Robust::corners(&[0.05]));
+        assert_eq!(robust.perturbations.len(), 3);
+        assert!(robust.evaluate(&Array1::from_elem(1, -0.5)) > robust.evaluate(&Array1::zeros(1)));
+
+        let objective = |x: &Array1<f64>| robust.evaluate(x);
+        let boundaries = CubicBoundary::new(1, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(40, boundaries, &objective, -1., 1);
+        for _ in 0..3 {
+            optimizer.iterate();
+        }
+        assert!((optimizer.best_position[0] - 0.5).abs() < 0.2);
+    }
+}
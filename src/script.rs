@@ -0,0 +1,83 @@
+//! Objective functions written in [Rhai](https://rhai.rs), enabled with the `script` feature.
+//!
+//! A script defines `fn objective(x)`, where `x` is an array of floats, and returns a number:
+//!
+//! ```rhai
+//! fn objective(x) {
+//!     let total = 0.0;
+//!     for value in x { total += value * value; }
+//!     total
+//! }
+//! ```
+//!
+//! Scripts may come from anyone who can reach a service, so the engine is limited: an evaluation
+//! that runs more than `MAX_OPERATIONS` operations, or recurses or nests deeper than a few dozen
+//! levels, fails instead of hanging the process.
+
+use ndarray::Array1;
+use rhai::{Array, Dynamic, Engine, Scope, AST};
+
+/// How many operations a single evaluation may run before it fails.
+pub const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// A compiled objective script.
+pub struct Script {
+    engine: Engine,
+    ast: AST,
+}
+
+impl Script {
+    /// Compiles `source`, which must define `fn objective(x)`.
+    pub fn compile(source: &str) -> Result<Self, String> {
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(MAX_OPERATIONS)
+            .set_max_call_levels(32)
+            .set_max_expr_depths(64, 32)
+            .set_max_string_size(1 << 16)
+            .set_max_array_size(1 << 20)
+            .set_max_map_size(1 << 16);
+        let ast = engine
+            .compile(source)
+            .map_err(|error| format!("invalid script: {}", error))?;
+        if !ast
+            .iter_functions()
+            .any(|function| function.name == "objective" && function.params.len() == 1)
+        {
+            return Err("the script does not define `fn objective(x)`".to_string());
+        }
+        Ok(Self { engine, ast })
+    }
+
+    /// Scores `position`. Integer results are accepted as well as floats.
+    pub fn evaluate(&self, position: &Array1<f64>) -> Result<f64, String> {
+        let x: Array = position.iter().map(|value| Dynamic::from(*value)).collect();
+        let score: Dynamic = self
+            .engine
+            .call_fn(&mut Scope::new(), &self.ast, "objective", (x,))
+            .map_err(|error| format!("evaluating the script: {}", error))?;
+        score
+            .as_float()
+            .or_else(|_| score.as_int().map(|score| score as f64))
+            .map_err(|kind| format!("the script returned a {} instead of a number", kind))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripts_score_positions() {
+        let script = Script::compile(
+            "fn objective(x) { let total = 0; for value in x { total += value * value; } total }",
+        )
+        .unwrap();
+        let score = script.evaluate(&Array1::from(vec![1., 2.])).unwrap();
+        assert_eq!(score, 5.);
+        assert!(Script::compile("fn score(x) { 0 }").is_err());
+
+        let forever = Script::compile("fn objective(x) { loop {} }").unwrap();
+        assert!(forever.evaluate(&Array1::from(vec![1.])).is_err());
+    }
+}
@@ -0,0 +1,126 @@
+//! Post-hoc sensitivity of a solution to its individual parameters.
+//!
+//! Every dimension of the solution is nudged down and up by its delta, and every pair of dimensions
+//! is nudged up together, which gives finite-difference slopes, curvatures and interaction effects
+//! at the cost of `2 d + d (d - 1) / 2 + 1` evaluations.
+
+use ndarray::{Array1, Array2};
+
+use crate::Optimizer;
+
+/// How the objective responds to one dimension of the solution.
+#[derive(Clone, Copy, Debug)]
+pub struct DimensionSensitivity {
+    pub dimension: usize,
+    pub delta: f64,
+    /// The scores with the dimension moved down and up by `delta`.
+    pub minus: f64,
+    pub plus: f64,
+    /// The central difference slope.
+    pub gradient: f64,
+    /// The central second difference.
+    pub curvature: f64,
+    /// The largest change in score caused by either nudge.
+    pub response: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct Sensitivity {
+    pub score: f64,
+    pub dimensions: Vec<DimensionSensitivity>,
+    /// Mixed second differences between pairs of dimensions. Zero for dimensions whose effects
+    /// simply add up. The matrix is symmetric with a zero diagonal.
+    pub interactions: Array2<f64>,
+}
+
+impl Sensitivity {
+    /// The dimensions ordered from the most to the least critical.
+    pub fn ranking(&self) -> Vec<usize> {
+        let mut ranking: Vec<&DimensionSensitivity> = self.dimensions.iter().collect();
+        ranking.sort_by(|a, b| b.response.total_cmp(&a.response));
+        ranking
+            .iter()
+            .map(|dimension| dimension.dimension)
+            .collect()
+    }
+}
+
+/// Perturbs `best` by `deltas`, one per dimension. A single delta is used for every dimension.
+pub fn sensitivity(
+    objective_function: &dyn Fn(&Array1<f64>) -> f64,
+    best: &Array1<f64>,
+    deltas: &[f64],
+) -> Sensitivity {
+    let n = best.len();
+    assert!(
+        deltas.len() == n || deltas.len() == 1,
+        "sensitivity expects one delta per dimension or a single shared delta"
+    );
+    let delta = |dimension: usize| deltas[if deltas.len() == 1 { 0 } else { dimension }];
+    let nudged = |moves: &[(usize, f64)]| {
+        let mut position = best.clone();
+        for (dimension, step) in moves {
+            position[*dimension] += step;
+        }
+        objective_function(&position)
+    };
+
+    let score = objective_function(best);
+    let dimensions: Vec<DimensionSensitivity> = (0..n)
+        .map(|dimension| {
+            let delta = delta(dimension);
+            let minus = nudged(&[(dimension, -delta)]);
+            let plus = nudged(&[(dimension, delta)]);
+            DimensionSensitivity {
+                dimension,
+                delta,
+                minus,
+                plus,
+                gradient: (plus - minus) / (2. * delta),
+                curvature: (plus - 2. * score + minus) / (delta * delta),
+                response: (plus - score).abs().max((minus - score).abs()),
+            }
+        })
+        .collect();
+
+    let mut interactions = Array2::zeros((n, n));
+    for i in 0..n {
+        for j in i + 1..n {
+            let both = nudged(&[(i, delta(i)), (j, delta(j))]);
+            let effect =
+                (both - dimensions[i].plus - dimensions[j].plus + score) / (delta(i) * delta(j));
+            interactions[[i, j]] = effect;
+            interactions[[j, i]] = effect;
+        }
+    }
+
+    Sensitivity {
+        score,
+        dimensions,
+        interactions,
+    }
+}
+
+impl Optimizer<'_> {
+    /// The sensitivity of the objective around the best position found so far.
+    pub fn sensitivity(&self, deltas: &[f64]) -> Sensitivity {
+        sensitivity(&*self.objective_function, &self.best_position, deltas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn critical_dimensions_and_interactions_are_found() {
+        let objective = |x: &Array1<f64>| 100. * x[0] * x[0] + x[1] * x[1] + 3. * x[1] * x[2];
+        let result = sensitivity(&objective, &Array1::zeros(3), &[1e-3]);
+
+        assert_eq!(result.ranking()[0], 0);
+        assert!((result.dimensions[0].curvature - 200.).abs() < 1e-6);
+        assert!(result.dimensions[0].gradient.abs() < 1e-9);
+        assert!((result.interactions[[1, 2]] - 3.).abs() < 1e-6);
+        assert!(result.interactions[[0, 1]].abs() < 1e-6);
+    }
+}
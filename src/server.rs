@@ -0,0 +1,539 @@
+//! An HTTP optimization service, enabled with the `server` feature.
+//!
+//! Clients in any language create runs, ask for candidate positions, evaluate them however they
+//! like, and tell the service the scores. Only HTTP with JSON bodies is spoken; there is no gRPC
+//! transport.
+//!
+//! | request                   | body                                                       |
+//! |---------------------------|------------------------------------------------------------|
+//! | `POST /runs`              | `{turtles, dimensions, lower, upper, goal}`, see below     |
+//! | `GET /runs`               |                                                            |
+//! | `GET /runs/{id}`          |                                                            |
+//! | `GET /runs/{id}/ask`      |                                                            |
+//! | `POST /runs/{id}/tell`    | `{scores: [...]}`, one score per asked position            |
+//! | `GET /runs/{id}/result`   |                                                            |
+//! | `DELETE /runs/{id}`       |                                                            |
+//! | `POST /runs/{id}/advance` | `{iterations}`, optional and capped, see below             |
+//!
+//! A new run may also be given a `max_iterations` limit and a `seed`. A run's `state` is checked
+//! after every tell. It is `running` until the optimizer terminates, then `goal_reached`,
+//! `budget_exhausted` when a limit ran out, or `stopped` for anything else.
+//!
+//! With the `script` feature a run may be created with a `script` holding the source of a Rhai
+//! objective (see [`crate::script`]). Only such runs can be advanced: the service then evaluates
+//! positions itself, and ask and tell are still available alongside. The service answers one
+//! request at a time, so a single advance runs at most `MAX_ADVANCE_ITERATIONS` iterations, fewer
+//! when `iterations` asks for less, and stops early once it has taken `MAX_ADVANCE_TIME`; a run
+//! that is still `running` is advanced again.
+//!
+//! Nothing a client sends may take the service down. Bodies are read up to `MAX_BODY_BYTES`, runs
+//! are refused with 400 unless their bounds are finite with `lower < upper` and they fit within
+//! `MAX_TURTLES` and `MAX_DIMENSIONS`, and a request that panics anyway answers 500 and leaves
+//! the other runs as they were.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::io::Read;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+#[cfg(feature = "script")]
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{external_objective, CubicBoundary, Optimizer, Termination};
+
+#[derive(Deserialize)]
+struct CreateRun {
+    #[serde(default = "default_turtles")]
+    turtles: usize,
+    dimensions: usize,
+    lower: f64,
+    upper: f64,
+    goal: f64,
+    max_iterations: Option<usize>,
+    seed: Option<u64>,
+    #[cfg(feature = "script")]
+    script: Option<String>,
+}
+
+fn default_turtles() -> usize {
+    32
+}
+
+impl CreateRun {
+    fn validate(&self) -> Result<(), String> {
+        if !(1..=MAX_TURTLES).contains(&self.turtles) {
+            return Err(format!("turtles must be between 1 and {MAX_TURTLES}"));
+        }
+        if !(1..=MAX_DIMENSIONS).contains(&self.dimensions) {
+            return Err(format!("dimensions must be between 1 and {MAX_DIMENSIONS}"));
+        }
+        if !self.lower.is_finite() || !self.upper.is_finite() || self.lower >= self.upper {
+            return Err("lower and upper must be finite with lower below upper".to_string());
+        }
+        if self.goal.is_nan() {
+            return Err("goal must be a number".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct Tell {
+    scores: Vec<f64>,
+}
+
+/// The largest request body read, in bytes.
+pub const MAX_BODY_BYTES: usize = 1 << 20;
+
+/// The most turtles a run may have.
+pub const MAX_TURTLES: usize = 1000;
+
+/// The most dimensions a run may search.
+pub const MAX_DIMENSIONS: usize = 1000;
+
+/// The most iterations a single advance runs, whatever it asks for.
+#[cfg(feature = "script")]
+pub const MAX_ADVANCE_ITERATIONS: usize = 1000;
+
+/// How long a single advance may keep the service busy before it returns.
+#[cfg(feature = "script")]
+pub const MAX_ADVANCE_TIME: Duration = Duration::from_secs(10);
+
+#[cfg(feature = "script")]
+#[derive(Default, Deserialize)]
+struct Advance {
+    iterations: Option<usize>,
+}
+
+struct Run {
+    optimizer: Optimizer<'static>,
+    // Why the optimizer stopped, asked after every tell since asking may take a stop request.
+    termination: Option<Termination>,
+    #[cfg(feature = "script")]
+    script: Option<crate::script::Script>,
+}
+
+impl Run {
+    fn new(
+        mut optimizer: Optimizer<'static>,
+        #[cfg(feature = "script")] script: Option<crate::script::Script>,
+    ) -> Self {
+        let termination = optimizer.termination();
+        Self {
+            optimizer,
+            termination,
+            #[cfg(feature = "script")]
+            script,
+        }
+    }
+
+    fn tell(&mut self, scores: &[f64]) {
+        self.optimizer.tell(scores);
+        self.termination = self.optimizer.termination();
+    }
+
+    fn state(&self) -> &'static str {
+        match self.termination {
+            None => "running",
+            Some(Termination::GoalReached) => "goal_reached",
+            Some(
+                Termination::IterationLimit | Termination::EvaluationLimit | Termination::TimeLimit,
+            ) => "budget_exhausted",
+            Some(_) => "stopped",
+        }
+    }
+
+    fn status(&self, id: u64) -> Value {
+        json!({
+            "id": id,
+            "state": self.state(),
+            "iterations": self.optimizer.iterations,
+            "best_score": finite_or_null(self.optimizer.best_score),
+            "best_position": self.optimizer.best_position.to_vec(),
+        })
+    }
+}
+
+// JSON has no infinity, and a run without scores has an infinite best.
+fn finite_or_null(value: f64) -> Value {
+    if value.is_finite() {
+        json!(value)
+    } else {
+        Value::Null
+    }
+}
+
+/// A response produced by the [`Service`].
+#[derive(Debug)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message }),
+        }
+    }
+}
+
+/// The state of every run, independent of the HTTP transport.
+#[derive(Default)]
+pub struct Service {
+    runs: BTreeMap<u64, Run>,
+    next_id: u64,
+}
+
+impl Service {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Routes a single request.
+    pub fn handle(&mut self, method: &str, path: &str, body: &str) -> Response {
+        let segments: Vec<&str> = path
+            .split('?')
+            .next()
+            .unwrap_or_default()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect();
+
+        match (method, segments.as_slice()) {
+            ("POST", ["runs"]) => self.create(body),
+            ("GET", ["runs"]) => Response::ok(Value::Array(
+                self.runs.iter().map(|(id, run)| run.status(*id)).collect(),
+            )),
+            (_, ["runs", id, rest @ ..]) => {
+                let Ok(id) = id.parse::<u64>() else {
+                    return Response::error(404, "no such run");
+                };
+                if method == "DELETE" && rest.is_empty() {
+                    return match self.runs.remove(&id) {
+                        Some(run) => Response::ok(run.status(id)),
+                        None => Response::error(404, "no such run"),
+                    };
+                }
+                let Some(run) = self.runs.get_mut(&id) else {
+                    return Response::error(404, "no such run");
+                };
+                match (method, rest) {
+                    ("GET", []) => Response::ok(run.status(id)),
+                    ("GET", ["ask"]) => ask(run),
+                    ("POST", ["tell"]) => tell(run, id, body),
+                    ("GET", ["result"]) => result(run, id),
+                    #[cfg(feature = "script")]
+                    ("POST", ["advance"]) => advance(run, id, body),
+                    _ => Response::error(405, "method not allowed"),
+                }
+            }
+            _ => Response::error(404, "not found"),
+        }
+    }
+
+    fn create(&mut self, body: &str) -> Response {
+        let request: CreateRun = match serde_json::from_str(body) {
+            Ok(request) => request,
+            Err(error) => return Response::error(400, &error.to_string()),
+        };
+        if let Err(message) = request.validate() {
+            return Response::error(400, &message);
+        }
+        #[cfg(feature = "script")]
+        let script = match request
+            .script
+            .as_deref()
+            .map(crate::script::Script::compile)
+        {
+            Some(Err(error)) => return Response::error(400, &error),
+            compiled => compiled.and_then(Result::ok),
+        };
+        let boundaries = CubicBoundary::new(request.dimensions, request.lower, request.upper);
+        let mut optimizer = match request.seed {
+            Some(seed) => Optimizer::new_seeded(
+                request.turtles,
+                boundaries,
+                &external_objective,
+                request.goal,
+                seed,
+            ),
+            None => Optimizer::new(
+                request.turtles,
+                boundaries,
+                &external_objective,
+                request.goal,
+            ),
+        };
+        optimizer.max_iterations = request.max_iterations;
+        let id = self.next_id;
+        self.next_id += 1;
+        let run = Run::new(
+            optimizer,
+            #[cfg(feature = "script")]
+            script,
+        );
+        let status = run.status(id);
+        self.runs.insert(id, run);
+        Response {
+            status: 201,
+            body: status,
+        }
+    }
+}
+
+fn ask(run: &Run) -> Response {
+    if run.state() != "running" {
+        return Response::error(409, "the run is finished");
+    }
+    let positions: Vec<Vec<f64>> = run
+        .optimizer
+        .ask()
+        .into_iter()
+        .map(|position| position.to_vec())
+        .collect();
+    Response::ok(json!({
+        "iteration": run.optimizer.iterations,
+        "positions": positions,
+    }))
+}
+
+fn tell(run: &mut Run, id: u64, body: &str) -> Response {
+    if run.state() != "running" {
+        return Response::error(409, "the run is finished");
+    }
+    let request: Tell = match serde_json::from_str(body) {
+        Ok(request) => request,
+        Err(error) => return Response::error(400, &error.to_string()),
+    };
+    if request.scores.len() != run.optimizer.turtles.len() {
+        return Response::error(422, "expected one score per asked position");
+    }
+    run.tell(&request.scores);
+    Response::ok(run.status(id))
+}
+
+#[cfg(feature = "script")]
+fn advance(run: &mut Run, id: u64, body: &str) -> Response {
+    let request: Advance = if body.trim().is_empty() {
+        Advance::default()
+    } else {
+        match serde_json::from_str(body) {
+            Ok(request) => request,
+            Err(error) => return Response::error(400, &error.to_string()),
+        }
+    };
+    let Some(script) = &run.script else {
+        return Response::error(409, "the run was created without a script");
+    };
+    let iterations = request
+        .iterations
+        .map_or(MAX_ADVANCE_ITERATIONS, |iterations| {
+            iterations.min(MAX_ADVANCE_ITERATIONS)
+        });
+    let stop = run.optimizer.iterations + iterations;
+    let started = Instant::now();
+    while run.state() == "running"
+        && run.optimizer.iterations < stop
+        && started.elapsed() < MAX_ADVANCE_TIME
+    {
+        let scores: Result<Vec<f64>, String> = run
+            .optimizer
+            .ask()
+            .iter()
+            .map(|position| script.evaluate(position))
+            .collect();
+        match scores {
+            // The script stays borrowed, so this is `Run::tell` spelled out field by field.
+            Ok(scores) => {
+                run.optimizer.tell(&scores);
+                run.termination = run.optimizer.termination();
+            }
+            Err(error) => return Response::error(422, &error),
+        }
+    }
+    Response::ok(run.status(id))
+}
+
+fn result(run: &Run, id: u64) -> Response {
+    let turtles: Vec<Value> = run
+        .optimizer
+        .turtles
+        .iter()
+        .map(|turtle| {
+            json!({
+                "best_score": finite_or_null(turtle.best_score),
+                "best_position": turtle.best_position.to_vec(),
+            })
+        })
+        .collect();
+    let mut body = run.status(id);
+    body["turtles"] = Value::Array(turtles);
+    Response::ok(body)
+}
+
+// Reads at most `MAX_BODY_BYTES` of a request body, one more if the client sent more, to tell.
+fn read_body(reader: impl Read) -> Result<String, Response> {
+    let mut body = String::new();
+    match reader
+        .take(MAX_BODY_BYTES as u64 + 1)
+        .read_to_string(&mut body)
+    {
+        Ok(read) if read > MAX_BODY_BYTES => Err(Response::error(400, "the body is too large")),
+        Ok(_) => Ok(body),
+        Err(error) => Err(Response::error(400, &error.to_string())),
+    }
+}
+
+/// Serves the optimization service on `address` (for example `127.0.0.1:8080`). Requests are
+/// handled one at a time, in the order they arrive.
+pub fn serve(address: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let server = tiny_http::Server::http(address)?;
+    let mut service = Service::new();
+    for mut request in server.incoming_requests() {
+        let response = match read_body(request.as_reader()) {
+            Ok(body) => {
+                let (method, url) = (request.method().as_str(), request.url());
+                catch_unwind(AssertUnwindSafe(|| service.handle(method, url, &body)))
+                    .unwrap_or_else(|_| Response::error(500, "the request could not be handled"))
+            }
+            Err(response) => response,
+        };
+        let header = tiny_http::Header::from_bytes("Content-Type", "application/json")
+            .expect("a static header is valid");
+        let _ = request.respond(
+            tiny_http::Response::from_string(response.body.to_string())
+                .with_status_code(response.status)
+                .with_header(header),
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clients_drive_runs_with_ask_and_tell() {
+        let mut service = Service::new();
+        let created = service.handle(
+            "POST",
+            "/runs",
+            &json!({
+                "turtles": 4, "dimensions": 2, "lower": -1, "upper": 1, "goal": -1,
+                "max_iterations": 2, "seed": 5,
+            })
+            .to_string(),
+        );
+        assert_eq!(created.status, 201);
+        let id = created.body["id"].as_u64().unwrap();
+
+        for _ in 0..2 {
+            let asked = service.handle("GET", &format!("/runs/{}/ask", id), "");
+            let scores: Vec<f64> = asked.body["positions"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|position| {
+                    position
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|x| x.as_f64().unwrap().powi(2))
+                        .sum()
+                })
+                .collect();
+            let told = service.handle(
+                "POST",
+                &format!("/runs/{}/tell", id),
+                &json!({ "scores": scores }).to_string(),
+            );
+            assert_eq!(told.status, 200);
+        }
+
+        let status = service.handle("GET", &format!("/runs/{}", id), "");
+        assert_eq!(status.body["state"], "budget_exhausted");
+        assert_eq!(status.body["iterations"], 2);
+        assert_eq!(
+            service
+                .handle("GET", &format!("/runs/{}/ask", id), "")
+                .status,
+            409
+        );
+
+        let result = service.handle("GET", &format!("/runs/{}/result", id), "");
+        assert_eq!(result.body["turtles"].as_array().unwrap().len(), 4);
+        assert_eq!(
+            service
+                .handle("DELETE", &format!("/runs/{}", id), "")
+                .status,
+            200
+        );
+        assert_eq!(
+            service.handle("GET", &format!("/runs/{}", id), "").status,
+            404
+        );
+    }
+
+    #[test]
+    fn malformed_runs_are_refused() {
+        let mut service = Service::new();
+        let create = |service: &mut Service, turtles: usize, dimensions: usize, lower, upper| {
+            let body = json!({
+                "turtles": turtles, "dimensions": dimensions,
+                "lower": lower, "upper": upper, "goal": 0,
+            });
+            service.handle("POST", "/runs", &body.to_string()).status
+        };
+        assert_eq!(create(&mut service, 4, 2, 1., 1.), 400);
+        assert_eq!(create(&mut service, 4, 2, 1., -1.), 400);
+        let overflowing = r#"{"dimensions": 2, "lower": -1, "upper": 1e309, "goal": 0}"#;
+        assert_eq!(service.handle("POST", "/runs", overflowing).status, 400);
+        assert_eq!(create(&mut service, 0, 2, -1., 1.), 400);
+        assert_eq!(create(&mut service, MAX_TURTLES + 1, 2, -1., 1.), 400);
+        assert_eq!(create(&mut service, 4, MAX_DIMENSIONS + 1, -1., 1.), 400);
+        assert!(service.runs.is_empty());
+        assert_eq!(create(&mut service, 4, 2, -1., 1.), 201);
+
+        let large = vec![b' '; MAX_BODY_BYTES + 1];
+        assert_eq!(read_body(&large[..]).unwrap_err().status, 400);
+        assert_eq!(read_body(&large[1..]).unwrap().len(), MAX_BODY_BYTES);
+    }
+
+    #[cfg(feature = "script")]
+    #[test]
+    fn scripted_runs_evaluate_themselves() {
+        let mut service = Service::new();
+        let created = service.handle(
+            "POST",
+            "/runs",
+            &json!({
+                "turtles": 4, "dimensions": 2, "lower": -1, "upper": 1, "goal": -1, "seed": 5,
+                "script": "fn objective(x) { x[0] * x[0] + x[1] * x[1] }",
+            })
+            .to_string(),
+        );
+        let id = created.body["id"].as_u64().unwrap();
+        let advanced = service.handle(
+            "POST",
+            &format!("/runs/{}/advance", id),
+            r#"{"iterations": 3}"#,
+        );
+        assert_eq!(advanced.status, 200);
+        assert_eq!(advanced.body["iterations"], 3);
+        assert!(advanced.body["best_score"].as_f64().unwrap() >= 0.);
+
+        // Without a budget or a reachable goal, an advance still returns.
+        let advanced = service.handle("POST", &format!("/runs/{}/advance", id), "");
+        assert_eq!(advanced.status, 200);
+        assert_eq!(advanced.body["iterations"], 3 + MAX_ADVANCE_ITERATIONS);
+    }
+}
@@ -0,0 +1,235 @@
+//! Simulated annealing, a single walker over the same objectives and boundaries as the turtles.
+//!
+//! Each iteration the walker proposes a random neighbour within `step` times the width of the
+//! boundary along every dimension. Better neighbours are always taken and worse ones with
+//! probability `exp(-worsening / temperature)`, where the temperature falls from
+//! `initial_temperature` by the `Cooling` schedule. With so little machinery it makes a useful
+//! baseline for low-dimensional or noisy problems, and it finishes with the same
+//! `OptimizationResult` as an `Optimizer`.
+
+use ndarray::Array1;
+use ndarray_rand::rand::{thread_rng, Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::boundary::Boundary;
+use crate::objective::ObjectiveFunction;
+use crate::{CubicBoundary, Direction, OptimizationResult, Termination};
+
+/// How the temperature falls with the iterations spent.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Cooling {
+    /// Multiplies the temperature by `rate` every iteration.
+    Exponential { rate: f64 },
+    /// Lowers the temperature evenly to zero over `iterations`.
+    Linear { iterations: usize },
+    /// Divides the initial temperature by `1 + ln(1 + iteration)`, slow enough to keep escaping.
+    Logarithmic,
+}
+
+impl Default for Cooling {
+    fn default() -> Self {
+        Cooling::Exponential { rate: 0.99 }
+    }
+}
+
+impl Cooling {
+    pub fn temperature(&self, initial: f64, iteration: usize) -> f64 {
+        match *self {
+            Cooling::Exponential { rate } => {
+                initial * rate.powi(iteration.min(i32::MAX as usize) as i32)
+            }
+            Cooling::Linear { iterations } => {
+                initial * (1. - iteration as f64 / iterations.max(1) as f64).max(0.)
+            }
+            Cooling::Logarithmic => initial / (1. + (1. + iteration as f64).ln()),
+        }
+    }
+}
+
+pub struct SimulatedAnnealing<'a, B = CubicBoundary> {
+    /// Where the walker is and what it scored there, `None` before the first evaluation.
+    pub position: Array1<f64>,
+    pub score: Option<f64>,
+    pub boundaries: B,
+    pub iterations: usize,
+    /// How many times the objective has been evaluated.
+    pub evaluations: usize,
+    pub best_score: f64,
+    pub best_position: Array1<f64>,
+    pub objective_function: ObjectiveFunction<'a>,
+    pub goal: f64,
+    /// The most iterations `optimize` may spend before giving up on the goal.
+    pub max_iterations: Option<usize>,
+    pub direction: Direction,
+    pub initial_temperature: f64,
+    pub cooling: Cooling,
+    /// The largest move along each dimension, as a fraction of the boundary's width.
+    pub step: f64,
+    /// How many worse neighbours were taken.
+    pub uphill: usize,
+    rng: ChaCha12Rng,
+}
+
+impl<'a, B: Boundary> SimulatedAnnealing<'a, B> {
+    pub fn new(
+        boundaries: B,
+        objective_function: impl Into<ObjectiveFunction<'a>>,
+        goal: f64,
+    ) -> Self {
+        let rng = ChaCha12Rng::from_rng(thread_rng()).expect("the rng provides a seed");
+        Self::with_rng(boundaries, objective_function.into(), goal, rng)
+    }
+
+    /// Like `new`, but the walk is drawn reproducibly from `seed`.
+    pub fn new_seeded(
+        boundaries: B,
+        objective_function: impl Into<ObjectiveFunction<'a>>,
+        goal: f64,
+        seed: u64,
+    ) -> Self {
+        let rng = ChaCha12Rng::seed_from_u64(seed);
+        Self::with_rng(boundaries, objective_function.into(), goal, rng)
+    }
+
+    fn with_rng(
+        boundaries: B,
+        objective_function: ObjectiveFunction<'a>,
+        goal: f64,
+        mut rng: ChaCha12Rng,
+    ) -> Self {
+        let position = boundaries.sample(&mut rng);
+        Self {
+            best_position: position.clone(),
+            position,
+            score: None,
+            boundaries,
+            iterations: 0,
+            evaluations: 0,
+            best_score: f64::INFINITY,
+            objective_function,
+            goal,
+            max_iterations: None,
+            direction: Direction::Minimize,
+            initial_temperature: 1.,
+            cooling: Cooling::default(),
+            step: 0.1,
+            uphill: 0,
+            rng,
+        }
+    }
+
+    /// The temperature of the next iteration.
+    pub fn temperature(&self) -> f64 {
+        self.cooling
+            .temperature(self.initial_temperature, self.iterations)
+    }
+
+    /// Walks until the best score reaches the goal or `max_iterations` is spent.
+    pub fn optimize(&mut self) -> OptimizationResult {
+        loop {
+            if let Some(termination) = self.termination() {
+                return self.result(termination);
+            }
+            self.iterate();
+        }
+    }
+
+    /// Takes a single step. Returns why `optimize` would stop here, if it would.
+    pub fn step(&mut self) -> Option<Termination> {
+        self.iterate();
+        self.termination()
+    }
+
+    fn termination(&self) -> Option<Termination> {
+        self.score?;
+        if self.direction.reaches(self.best_score, self.goal) {
+            return Some(Termination::GoalReached);
+        }
+        self.max_iterations
+            .filter(|limit| self.iterations >= *limit)
+            .map(|_| Termination::IterationLimit)
+    }
+
+    fn result(&self, termination: Termination) -> OptimizationResult {
+        OptimizationResult {
+            best_position: self.best_position.clone(),
+            best_score: self.best_score,
+            iterations: self.iterations,
+            evaluations: self.evaluations,
+            turtle_bests: vec![(self.best_score, self.best_position.clone())],
+            termination,
+        }
+    }
+
+    pub(crate) fn iterate(&mut self) {
+        let current = match self.score {
+            Some(score) => score,
+            None => {
+                let score = (self.objective_function)(&self.position);
+                self.evaluations += 1;
+                self.best_score = self.direction.worst();
+                if self.direction.is_better(score, self.best_score) {
+                    self.best_score = score;
+                }
+                self.score = Some(score);
+                score
+            }
+        };
+        let (lower, upper) = self.boundaries.bounding_box();
+        let mut neighbour = ndarray::Zip::from(&self.position)
+            .and(&lower)
+            .and(&upper)
+            .map_collect(|x, low, high| {
+                x + self.rng.gen_range(-1.0..=1.0) * self.step * (high - low)
+            });
+        self.boundaries.project(&mut neighbour);
+        let score = (self.objective_function)(&neighbour);
+        self.evaluations += 1;
+
+        let worsening = match self.direction {
+            Direction::Minimize => score - current,
+            Direction::Maximize => current - score,
+        };
+        let temperature = self.temperature();
+        let accept = if worsening <= 0. || current.is_nan() {
+            true
+        } else {
+            temperature > 0. && self.rng.gen::<f64>() < (-worsening / temperature).exp()
+        };
+        if accept && !score.is_nan() {
+            if worsening > 0. {
+                self.uphill += 1;
+            }
+            if self.direction.is_better(score, self.best_score) {
+                self.best_score = score;
+                self.best_position = neighbour.clone();
+            }
+            self.position = neighbour;
+            self.score = Some(score);
+        }
+        self.iterations += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_walker_cools_into_the_minimum() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| (x - 0.3) * (x - 0.3)).sum();
+        let boundaries = CubicBoundary::new(2, -2., 2.);
+        let mut annealing = SimulatedAnnealing::new_seeded(boundaries, &parabola, 1e-4, 5);
+        annealing.step = 0.05;
+        annealing.max_iterations = Some(5000);
+        let result = annealing.optimize();
+
+        assert_eq!(result.termination, Termination::GoalReached);
+        assert!(annealing.uphill > 0);
+        assert_eq!(parabola(&result.best_position), result.best_score);
+        assert_eq!(Cooling::Linear { iterations: 10 }.temperature(2., 5), 1.);
+        assert_eq!(Cooling::Linear { iterations: 10 }.temperature(2., 20), 0.);
+        assert!(annealing.temperature() < annealing.initial_temperature);
+    }
+}
@@ -0,0 +1,265 @@
+//! Turtles with a dimension fixed at compile time, for small problems evaluated in hot loops.
+//!
+//! Positions and velocities are `[f64; N]` rather than `Array1`, so a turtle lives in one piece
+//! and moving it allocates nothing, and the update loops over `0..N` can be unrolled by the
+//! compiler. For the handful of dimensions where the allocations of the general optimizer cost
+//! more than the objective itself, this is the faster swarm. It runs the same update, the inertia
+//! form with the star topology, clamped to the boundary, and places and moves its turtles exactly
+//! like an `Optimizer` over the same cube and seed would.
+//!
+//! Only the results, `OptimizationResult` and its `Array1` positions, are allocated.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ndarray::Array1;
+use rand::distributions::Uniform;
+#[cfg(feature = "std")]
+use rand::thread_rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+use crate::{Direction, OptimizationResult, OptimizerParams, Termination};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StackTurtle<const N: usize> {
+    pub position: [f64; N],
+    velocity: [f64; N],
+    pub best_score: f64,
+    pub best_position: [f64; N],
+}
+
+/// The box between `lower` and `upper` in every dimension, like `RectangularBoundary`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StackBoundary<const N: usize> {
+    pub lower: [f64; N],
+    pub upper: [f64; N],
+}
+
+impl<const N: usize> StackBoundary<N> {
+    /// A dimension given the wrong way round is swapped, as in `RectangularBoundary::new`.
+    pub fn new(mut lower: [f64; N], mut upper: [f64; N]) -> Self {
+        for (lower, upper) in lower.iter_mut().zip(upper.iter_mut()) {
+            if *lower > *upper {
+                core::mem::swap(lower, upper);
+            }
+        }
+        Self { lower, upper }
+    }
+
+    /// The cube `[lower, upper]^N`, like `CubicBoundary`.
+    pub fn cube(lower: f64, upper: f64) -> Self {
+        Self::new([lower; N], [upper; N])
+    }
+
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> [f64; N] {
+        let mut position = self.lower;
+        for (x, upper) in position.iter_mut().zip(self.upper.iter()) {
+            if *x < *upper {
+                *x = rng.sample(Uniform::new(*x, *upper));
+            }
+        }
+        position
+    }
+
+    fn project(&self, position: &mut [f64; N]) {
+        for ((x, lower), upper) in position.iter_mut().zip(self.lower).zip(self.upper) {
+            if *x > upper {
+                *x = upper;
+            } else if *x < lower {
+                *x = lower;
+            }
+        }
+    }
+}
+
+/// Minimizes an objective over `N` dimensions without allocating as it goes.
+pub struct StackOptimizer<'a, const N: usize> {
+    pub turtles: Vec<StackTurtle<N>>,
+    pub boundaries: StackBoundary<N>,
+    pub iterations: usize,
+    /// How many times the objective has been evaluated.
+    pub evaluations: usize,
+    pub best_score: f64,
+    pub best_position: [f64; N],
+    pub objective_function: &'a dyn Fn(&[f64; N]) -> f64,
+    pub goal: f64,
+    /// The most iterations `optimize` may spend before giving up on the goal.
+    pub max_iterations: Option<usize>,
+    pub params: OptimizerParams,
+}
+
+impl<'a, const N: usize> StackOptimizer<'a, N> {
+    #[cfg(feature = "std")]
+    pub fn new(
+        turtles: usize,
+        boundaries: StackBoundary<N>,
+        objective_function: &'a dyn Fn(&[f64; N]) -> f64,
+        goal: f64,
+    ) -> Self {
+        let rng = ChaCha12Rng::from_rng(thread_rng()).expect("the rng provides a seed");
+        Self::with_rng(turtles, boundaries, objective_function, goal, rng)
+    }
+
+    /// Like `new`, but the turtles are placed reproducibly from `seed`.
+    pub fn new_seeded(
+        turtles: usize,
+        boundaries: StackBoundary<N>,
+        objective_function: &'a dyn Fn(&[f64; N]) -> f64,
+        goal: f64,
+        seed: u64,
+    ) -> Self {
+        let rng = ChaCha12Rng::seed_from_u64(seed);
+        Self::with_rng(turtles, boundaries, objective_function, goal, rng)
+    }
+
+    fn with_rng(
+        turtles: usize,
+        boundaries: StackBoundary<N>,
+        objective_function: &'a dyn Fn(&[f64; N]) -> f64,
+        goal: f64,
+        mut rng: ChaCha12Rng,
+    ) -> Self {
+        Self {
+            turtles: (0..turtles)
+                .map(|_| StackTurtle {
+                    position: boundaries.sample(&mut rng),
+                    velocity: [f64::EPSILON; N],
+                    best_score: f64::INFINITY,
+                    best_position: [0.; N],
+                })
+                .collect(),
+            boundaries,
+            iterations: 0,
+            evaluations: 0,
+            best_score: f64::INFINITY,
+            best_position: [0.; N],
+            objective_function,
+            goal,
+            max_iterations: None,
+            params: OptimizerParams::default(),
+        }
+    }
+
+    /// Iterates until the best score reaches the goal or `max_iterations` is spent.
+    pub fn optimize(&mut self) -> OptimizationResult {
+        loop {
+            if let Some(termination) = self.termination() {
+                return self.result(termination);
+            }
+            self.iterate();
+        }
+    }
+
+    /// Moves the turtles once. Returns why `optimize` would stop here, if it would.
+    pub fn step(&mut self) -> Option<Termination> {
+        self.iterate();
+        self.termination()
+    }
+
+    fn termination(&self) -> Option<Termination> {
+        if Direction::Minimize.reaches(self.best_score, self.goal) {
+            return Some(Termination::GoalReached);
+        }
+        self.max_iterations
+            .filter(|limit| self.iterations >= *limit)
+            .map(|_| Termination::IterationLimit)
+    }
+
+    fn result(&self, termination: Termination) -> OptimizationResult {
+        OptimizationResult {
+            best_position: Array1::from(self.best_position.to_vec()),
+            best_score: self.best_score,
+            iterations: self.iterations,
+            evaluations: self.evaluations,
+            turtle_bests: self
+                .turtles
+                .iter()
+                .map(|turtle| {
+                    (
+                        turtle.best_score,
+                        Array1::from(turtle.best_position.to_vec()),
+                    )
+                })
+                .collect(),
+            termination,
+        }
+    }
+
+    fn evaluate(&mut self) {
+        for turtle in self.turtles.iter_mut() {
+            let score = (self.objective_function)(&turtle.position);
+            self.evaluations += 1;
+            if score < turtle.best_score {
+                turtle.best_score = score;
+                turtle.best_position = turtle.position;
+                if score < self.best_score {
+                    self.best_score = score;
+                    self.best_position = turtle.position;
+                }
+            }
+        }
+    }
+
+    fn update(&mut self) {
+        let params = self.params;
+        for turtle in self.turtles.iter_mut() {
+            for dimension in 0..N {
+                let position = turtle.position[dimension];
+                turtle.velocity[dimension] = params.velocity(
+                    turtle.velocity[dimension],
+                    position,
+                    turtle.best_position[dimension],
+                    self.best_position[dimension],
+                );
+                turtle.position[dimension] = position + turtle.velocity[dimension];
+            }
+            self.boundaries.project(&mut turtle.position);
+        }
+    }
+
+    pub(crate) fn iterate(&mut self) {
+        self.evaluate();
+        self.update();
+        self.iterations += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CubicBoundary, Optimizer};
+
+    #[test]
+    fn reversed_bounds_are_swapped() {
+        let boundary = StackBoundary::new([1., -1.], [-1., 2.]);
+        assert_eq!(boundary.lower, [-1., -1.]);
+        assert_eq!(boundary.upper, [1., 2.]);
+        assert_eq!(
+            StackBoundary::<2>::cube(1., -1.),
+            StackBoundary::cube(-1., 1.)
+        );
+    }
+
+    #[test]
+    fn stack_turtles_retrace_the_optimizer() {
+        let parabola = |x: &[f64; 3]| x.iter().map(|x| x * x).sum::<f64>();
+        let params = OptimizerParams {
+            inertia: 0.5,
+            cognitive: 0.7,
+            social: 0.7,
+        };
+        let mut stack =
+            StackOptimizer::new_seeded(10, StackBoundary::cube(-1., 1.), &parabola, 1e-6, 3);
+        stack.params = params;
+        stack.max_iterations = Some(300);
+        let result = stack.optimize();
+        assert_eq!(result.termination, Termination::GoalReached);
+
+        let array = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(3, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &array, 1e-6, 3);
+        optimizer.params = params;
+        optimizer.max_iterations = Some(300);
+        assert_eq!(optimizer.optimize(), result);
+    }
+}
@@ -0,0 +1,126 @@
+//! Partial restarts of a swarm that has stopped improving.
+//!
+//! A swarm that collapses onto a local minimum keeps scoring the same few positions forever. With
+//! `Optimizer::stagnation` set, a run whose best has not improved for `window` iterations sends
+//! the worst `fraction` of its turtles, ranked by their personal bests, to fresh positions
+//! anywhere in the boundary outside of the tabu archive. The better turtles keep their place and
+//! their memory, so what the swarm already knows is not lost.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ndarray::Array1;
+
+use crate::boundary::Boundary;
+use crate::Optimizer;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StagnationRestart {
+    /// How many iterations without a better overall best count as stagnation.
+    pub window: usize,
+    /// The share of the turtles re-placed on a restart, between 0 and 1.
+    pub fraction: f64,
+    /// How many partial restarts have happened so far.
+    pub restarts: usize,
+    // The best score when the current streak began, and the iterations since.
+    #[cfg_attr(feature = "serde", serde(with = "crate::checkpoint::score::option"))]
+    best: Option<f64>,
+    stalled: usize,
+}
+
+impl StagnationRestart {
+    pub fn new(window: usize, fraction: f64) -> Self {
+        Self {
+            window,
+            fraction,
+            restarts: 0,
+            best: None,
+            stalled: 0,
+        }
+    }
+
+    /// How many iterations in a row the best has not improved.
+    pub fn stalled(&self) -> usize {
+        self.stalled
+    }
+}
+
+impl<B: Boundary> Optimizer<'_, B> {
+    // Counts the iterations since the best last improved and restarts the worst turtles once
+    // they fill the window.
+    pub(crate) fn check_stagnation(&mut self) {
+        let Some(mut stagnation) = self.stagnation else {
+            return;
+        };
+        let improved = stagnation
+            .best
+            .is_none_or(|best| self.direction.is_better(self.best_score, best));
+        if improved {
+            stagnation.best = Some(self.best_score);
+            stagnation.stalled = 0;
+        } else {
+            stagnation.stalled += 1;
+        }
+        if stagnation.stalled >= stagnation.window.max(1) {
+            let restarted = ((stagnation.fraction.clamp(0., 1.) * self.turtles.len() as f64)
+                as usize)
+                .min(self.turtles.len());
+            let mut ranked: Vec<usize> = (0..self.turtles.len()).collect();
+            // Worst first; turtles that were never scored count as the worst of all.
+            ranked.sort_by(|a, b| {
+                let (a, b) = (self.turtles[*a].best_score, self.turtles[*b].best_score);
+                if self.direction.is_better(b, a) {
+                    core::cmp::Ordering::Less
+                } else if self.direction.is_better(a, b) {
+                    core::cmp::Ordering::Greater
+                } else {
+                    core::cmp::Ordering::Equal
+                }
+            });
+            let shape = self.boundaries.dimensions();
+            for turtle in ranked.into_iter().take(restarted) {
+                let position = self.untabu_position();
+                let turtle = &mut self.turtles[turtle];
+                turtle.position = position;
+                turtle.velocity = f64::EPSILON * Array1::ones(shape);
+                turtle.best_score = self.direction.worst();
+                turtle.best_position = Array1::zeros(shape);
+            }
+            stagnation.restarts += 1;
+            stagnation.stalled = 0;
+        }
+        self.stagnation = Some(stagnation);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CubicBoundary;
+
+    #[test]
+    fn stalled_swarms_restart_their_worst_turtles() {
+        let flat = |_: &Array1<f64>| 1.;
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &flat, -1., 4);
+        optimizer.stagnation = Some(StagnationRestart::new(5, 0.3));
+        for _ in 0..6 {
+            optimizer.iterate();
+        }
+
+        let stagnation = optimizer.stagnation.unwrap();
+        assert_eq!(stagnation.restarts, 1);
+        assert_eq!(stagnation.stalled(), 0);
+        let unscored = optimizer
+            .turtles
+            .iter()
+            .filter(|turtle| turtle.best_score == f64::INFINITY)
+            .count();
+        assert_eq!(unscored, 3);
+        assert_eq!(optimizer.best_score, 1.);
+        // Every turtle ties on a flat objective, so the first ones are the ones sent away.
+        assert!(optimizer.turtles[..3]
+            .iter()
+            .all(|turtle| turtle.best_score == f64::INFINITY));
+    }
+}
@@ -0,0 +1,106 @@
+//! Per-dimension step sizes learned from how the objective responds along each axis.
+//!
+//! Every time a turtle is scored, the change in its score since its previous evaluation relative
+//! to the squared length of its step is credited to the dimensions it moved along, in proportion
+//! to how far it moved along each. That gives a running estimate of how stiff the objective is
+//! per axis. Steps along stiff dimensions are then shrunk and steps along flat ones stretched, so
+//! a badly scaled problem behaves more like a well scaled one.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ndarray::Array1;
+
+use crate::sqrt;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StepScaling {
+    /// How much of the running stiffness estimate is kept per observation, between 0 and 1.
+    pub smoothing: f64,
+    /// The bounds on the factor a step is scaled by.
+    pub min_scale: f64,
+    pub max_scale: f64,
+    // The running stiffness per dimension.
+    stiffness: Array1<f64>,
+    // Each turtle's previously scored position and its score.
+    previous: Vec<Option<(Array1<f64>, f64)>>,
+}
+
+impl StepScaling {
+    pub fn new(dimensions: usize) -> Self {
+        Self {
+            smoothing: 0.9,
+            min_scale: 0.1,
+            max_scale: 10.,
+            stiffness: Array1::zeros(dimensions),
+            previous: Vec::new(),
+        }
+    }
+
+    /// The running change in score per squared step length along each dimension.
+    pub fn stiffness(&self) -> &Array1<f64> {
+        &self.stiffness
+    }
+
+    pub(crate) fn observe(&mut self, turtle: usize, position: &Array1<f64>, score: f64) {
+        if !score.is_finite() {
+            return;
+        }
+        if self.previous.len() <= turtle {
+            self.previous.resize(turtle + 1, None);
+        }
+        if let Some((before, previous_score)) = &self.previous[turtle] {
+            let moved = position - before;
+            let distance = moved.mapv(|x| x * x).sum();
+            if distance > 0. {
+                let change = score - previous_score;
+                let change = if change < 0. { -change } else { change };
+                let estimate = change / distance;
+                for (stiffness, step) in self.stiffness.iter_mut().zip(moved.iter()) {
+                    let weight = (1. - self.smoothing) * step * step / distance;
+                    *stiffness += weight * (estimate - *stiffness);
+                }
+            }
+        }
+        self.previous[turtle] = Some((position.clone(), score));
+    }
+
+    /// The factor each dimension's step is multiplied by: the square root of the mean stiffness
+    /// over the dimension's own, within `min_scale` and `max_scale`. All ones until something has
+    /// been observed.
+    pub fn scales(&self) -> Array1<f64> {
+        let mean = self.stiffness.mean().unwrap_or(0.);
+        if mean <= 0. {
+            return Array1::ones(self.stiffness.len());
+        }
+        self.stiffness.mapv(|stiffness| {
+            if stiffness > 0. {
+                sqrt(mean / stiffness).clamp(self.min_scale, self.max_scale)
+            } else {
+                self.max_scale
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CubicBoundary, Optimizer};
+
+    #[test]
+    fn stiff_dimensions_take_smaller_steps() {
+        let valley = |x: &Array1<f64>| 1000. * x[0] * x[0] + x[1] * x[1];
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &valley, -1., 4);
+        optimizer.step_scaling = Some(StepScaling::new(2));
+        for _ in 0..20 {
+            optimizer.iterate();
+        }
+
+        let scaling = optimizer.step_scaling.as_ref().unwrap();
+        let scales = scaling.scales();
+        assert!(scaling.stiffness()[0] > scaling.stiffness()[1]);
+        assert!(scales[0] < 1. && scales[1] > 1.);
+    }
+}
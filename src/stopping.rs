@@ -0,0 +1,237 @@
+//! Composable conditions for ending a run.
+//!
+//! Out of the box `optimize` stops at the goal or at `max_iterations`. Setting
+//! `Optimizer::stopping` hands that decision over to a `StoppingCriterion` instead, checked
+//! before every iteration. Criteria combine with `Any` and `All`, the crate's own are `Goal`,
+//! `MaxIterations` and `NoImprovement`, and any closure over the optimizer that returns whether
+//! to stop is one too. Criteria are generic over the optimizer they look at, and the crate's own
+//! work with every optimizer implementing `Progress`, the binary one included.
+
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, collections::VecDeque, vec::Vec};
+#[cfg(feature = "std")]
+use std::collections::VecDeque;
+
+use crate::boundary::Boundary;
+use crate::{Direction, Optimizer, Termination};
+
+/// How far a run has come, which is all the crate's own criteria need to know about it.
+pub trait Progress {
+    fn iterations(&self) -> usize;
+    fn best_score(&self) -> f64;
+    fn goal(&self) -> f64;
+    fn direction(&self) -> Direction {
+        Direction::Minimize
+    }
+}
+
+impl<B> Progress for Optimizer<'_, B> {
+    fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    fn best_score(&self) -> f64 {
+        self.best_score
+    }
+
+    fn goal(&self) -> f64 {
+        self.goal
+    }
+
+    fn direction(&self) -> Direction {
+        self.direction
+    }
+}
+
+/// Decides whether the run on an `O`, such as an `Optimizer<'a, B>`, should stop.
+pub trait StoppingCriterion<O: ?Sized> {
+    /// Why the run should stop before its next iteration, or `None` to carry on.
+    fn check(&mut self, optimizer: &O) -> Option<Termination>;
+}
+
+/// Closures stop the run by returning true, which is reported as `Termination::Criterion`.
+impl<O: ?Sized, F> StoppingCriterion<O> for F
+where
+    F: FnMut(&O) -> bool,
+{
+    fn check(&mut self, optimizer: &O) -> Option<Termination> {
+        self(optimizer).then_some(Termination::Criterion)
+    }
+}
+
+/// Stops once the best score reaches the optimizer's goal.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Goal;
+
+impl<O: Progress + ?Sized> StoppingCriterion<O> for Goal {
+    fn check(&mut self, optimizer: &O) -> Option<Termination> {
+        optimizer
+            .direction()
+            .reaches(optimizer.best_score(), optimizer.goal())
+            .then_some(Termination::GoalReached)
+    }
+}
+
+/// Stops once the optimizer has spent this many iterations in total.
+#[derive(Clone, Copy, Debug)]
+pub struct MaxIterations(pub usize);
+
+impl<O: Progress + ?Sized> StoppingCriterion<O> for MaxIterations {
+    fn check(&mut self, optimizer: &O) -> Option<Termination> {
+        (optimizer.iterations() >= self.0).then_some(Termination::IterationLimit)
+    }
+}
+
+/// Stops once the best score has not improved by more than `epsilon` over the last `window`
+/// iterations. Alongside `Goal` it ends runs on objectives whose minimum lies short of the goal.
+#[derive(Clone, Debug)]
+pub struct NoImprovement {
+    pub window: usize,
+    pub epsilon: f64,
+    // The best score at each of the last `window` checks, and at the one before them.
+    bests: VecDeque<f64>,
+}
+
+impl NoImprovement {
+    pub fn new(window: usize, epsilon: f64) -> Self {
+        Self {
+            window,
+            epsilon,
+            bests: VecDeque::new(),
+        }
+    }
+}
+
+impl<O: Progress + ?Sized> StoppingCriterion<O> for NoImprovement {
+    fn check(&mut self, optimizer: &O) -> Option<Termination> {
+        self.bests.push_back(optimizer.best_score());
+        if self.bests.len() <= self.window.max(1) {
+            return None;
+        }
+        let before = self.bests.pop_front()?;
+        let best = optimizer.best_score();
+        let improvement = match optimizer.direction() {
+            Direction::Minimize => before - best,
+            Direction::Maximize => best - before,
+        };
+        (improvement <= self.epsilon).then_some(Termination::Stagnated)
+    }
+}
+
+type Criteria<'a, O> = Vec<Box<dyn StoppingCriterion<O> + Send + 'a>>;
+
+/// Stops as soon as any of its criteria would, for the reason of the first one that does.
+/// Every criterion is checked every time, so the ones that keep track of the run see all of it.
+pub struct Any<'a, O: ?Sized>(pub Criteria<'a, O>);
+
+/// Stops once all of its criteria would at the same time, for the reason of the first one.
+pub struct All<'a, O: ?Sized>(pub Criteria<'a, O>);
+
+impl<'a, O: ?Sized> Any<'a, O> {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn or(mut self, criterion: impl StoppingCriterion<O> + Send + 'a) -> Self {
+        self.0.push(Box::new(criterion));
+        self
+    }
+}
+
+impl<O: ?Sized> Default for Any<'_, O> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, O: ?Sized> All<'a, O> {
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn and(mut self, criterion: impl StoppingCriterion<O> + Send + 'a) -> Self {
+        self.0.push(Box::new(criterion));
+        self
+    }
+}
+
+impl<O: ?Sized> Default for All<'_, O> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<O: ?Sized> StoppingCriterion<O> for Any<'_, O> {
+    fn check(&mut self, optimizer: &O) -> Option<Termination> {
+        let mut reason = None;
+        for criterion in self.0.iter_mut() {
+            let stop = criterion.check(optimizer);
+            reason = reason.or(stop);
+        }
+        reason
+    }
+}
+
+impl<O: ?Sized> StoppingCriterion<O> for All<'_, O> {
+    fn check(&mut self, optimizer: &O) -> Option<Termination> {
+        let mut reason = None;
+        let mut all = !self.0.is_empty();
+        for criterion in self.0.iter_mut() {
+            match criterion.check(optimizer) {
+                Some(stop) => reason = reason.or(Some(stop)),
+                None => all = false,
+            }
+        }
+        reason.filter(|_| all)
+    }
+}
+
+impl<B: Boundary> Optimizer<'_, B> {
+    // Asks the user's criterion, which is taken out while it looks at the optimizer.
+    pub(crate) fn check_stopping(&mut self) -> Option<Option<Termination>> {
+        let mut criterion = self.stopping.take()?;
+        let reason = criterion.check(self);
+        self.stopping = Some(criterion);
+        Some(reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CubicBoundary;
+    use ndarray::Array1;
+
+    #[test]
+    fn criteria_compose() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(5, boundaries, &parabola, -1., 2);
+        optimizer.stopping = Some(Box::new(
+            Any::new().or(Goal).or(All::new()
+                .and(MaxIterations(3))
+                .and(|optimizer: &Optimizer| optimizer.iterations.is_multiple_of(4))),
+        ));
+        let result = optimizer.optimize();
+        assert_eq!(result.termination, Termination::IterationLimit);
+        assert_eq!(result.iterations, 4);
+
+        // The criterion alone decides, so the iteration limit no longer applies.
+        optimizer.max_iterations = Some(5);
+        optimizer.stopping = Some(Box::new(|optimizer: &Optimizer| optimizer.iterations >= 7));
+        let result = optimizer.optimize();
+        assert_eq!(result.termination, Termination::Criterion);
+        assert_eq!(result.iterations, 7);
+
+        // The parabola bottoms out at zero, short of the goal.
+        let mut optimizer = Optimizer::new_seeded(5, boundaries, &parabola, -1., 2);
+        optimizer.params = crate::OptimizerParams::clerc();
+        optimizer.velocity_update = crate::VelocityUpdate::Constriction;
+        optimizer.stopping = Some(Box::new(
+            Any::new().or(Goal).or(NoImprovement::new(10, 1e-12)),
+        ));
+        let result = optimizer.optimize();
+        assert_eq!(result.termination, Termination::Stagnated);
+        assert!(result.best_score < 1e-3 && result.iterations > 10);
+    }
+}
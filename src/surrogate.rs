@@ -0,0 +1,213 @@
+//! Pretrained ONNX surrogate models, enabled with the `onnx` feature.
+//!
+//! A surrogate takes a `[1, dimensions]` float tensor and predicts a single score. It can be the
+//! objective itself, or it can stand in front of an expensive objective with [`Screened`], so only
+//! positions the network believes are promising are truly evaluated. Models run on
+//! [tract](https://github.com/sonos/tract), so no native ONNX runtime is needed.
+
+use std::error::Error;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use ndarray::Array1;
+use tract_onnx::prelude::*;
+
+/// A loaded, optimized surrogate network.
+pub struct Surrogate {
+    model: Arc<TypedRunnableModel>,
+    dimensions: usize,
+}
+
+impl Surrogate {
+    /// Loads the model stored at `path`.
+    pub fn load(path: impl AsRef<Path>, dimensions: usize) -> Result<Self, Box<dyn Error>> {
+        Self::prepare(tract_onnx::onnx().model_for_path(path)?, dimensions)
+    }
+
+    /// Loads a model from the bytes of an `.onnx` file.
+    pub fn from_bytes(bytes: &[u8], dimensions: usize) -> Result<Self, Box<dyn Error>> {
+        Self::prepare(
+            tract_onnx::onnx().model_for_read(&mut &bytes[..])?,
+            dimensions,
+        )
+    }
+
+    fn prepare(model: InferenceModel, dimensions: usize) -> Result<Self, Box<dyn Error>> {
+        let model = model
+            .with_input_fact(0, f32::fact([1, dimensions]).into())?
+            .into_optimized()?
+            .into_runnable()?;
+        Ok(Self { model, dimensions })
+    }
+
+    /// The network's prediction for `position`.
+    pub fn predict(&self, position: &Array1<f64>) -> Result<f64, Box<dyn Error>> {
+        if position.len() != self.dimensions {
+            return Err(format!(
+                "the surrogate expects {} dimensions, not {}",
+                self.dimensions,
+                position.len()
+            )
+            .into());
+        }
+        let values: Vec<f32> = position.iter().map(|x| *x as f32).collect();
+        let input = Tensor::from_shape(&[1, self.dimensions], &values)?;
+        let outputs = self.model.run(tvec!(input.into()))?;
+        let output = outputs.first().ok_or("the surrogate produced no output")?;
+        // Models exported in double precision are accepted too.
+        let prediction = output.cast_to_scalar::<f64>()?;
+        Ok(prediction)
+    }
+
+    /// The prediction as an objective value. Positions the model cannot score are infinitely bad.
+    pub fn evaluate(&self, position: &Array1<f64>) -> f64 {
+        self.predict(position).unwrap_or(f64::INFINITY)
+    }
+}
+
+/// An expensive objective screened by a surrogate. Positions predicted to land within
+/// `tolerance` of the best true score so far are evaluated for real; the rest are given the
+/// surrogate's prediction. Its counters are atomic, so a screened objective can be handed to an
+/// [`Optimizer`](crate::Optimizer).
+pub struct Screened<'a> {
+    surrogate: &'a Surrogate,
+    objective_function: &'a (dyn Fn(&Array1<f64>) -> f64 + Sync),
+    pub tolerance: f64,
+    // The bits of the best true score.
+    best: AtomicU64,
+    evaluations: AtomicUsize,
+}
+
+impl<'a> Screened<'a> {
+    pub fn new(
+        surrogate: &'a Surrogate,
+        objective_function: &'a (dyn Fn(&Array1<f64>) -> f64 + Sync),
+    ) -> Self {
+        Self {
+            surrogate,
+            objective_function,
+            tolerance: 0.,
+            best: AtomicU64::new(f64::INFINITY.to_bits()),
+            evaluations: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn evaluate(&self, position: &Array1<f64>) -> f64 {
+        match self.surrogate.predict(position) {
+            Ok(prediction) if prediction > self.best() + self.tolerance => prediction,
+            // Unpredictable positions are evaluated rather than thrown away.
+            _ => {
+                self.evaluations.fetch_add(1, Ordering::Relaxed);
+                let score = (self.objective_function)(position);
+                let _ = self
+                    .best
+                    .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |best| {
+                        (score < f64::from_bits(best)).then_some(score.to_bits())
+                    });
+                score
+            }
+        }
+    }
+
+    /// How many times the true objective has been called.
+    pub fn evaluations(&self) -> usize {
+        self.evaluations.load(Ordering::Relaxed)
+    }
+
+    fn best(&self) -> f64 {
+        f64::from_bits(self.best.load(Ordering::Relaxed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CubicBoundary, Optimizer, OptimizerParams};
+    use prost::Message;
+    use tract_onnx::pb::{
+        type_proto, GraphProto, ModelProto, NodeProto, OperatorSetIdProto, TensorProto, TypeProto,
+        ValueInfoProto,
+    };
+
+    // y = (x * x) · 1, the sphere function as a three-dimensional network.
+    fn sphere() -> Vec<u8> {
+        let node = |op_type: &str, input: &[&str], output: &str| NodeProto {
+            op_type: op_type.to_string(),
+            input: input.iter().map(|name| name.to_string()).collect(),
+            output: vec![output.to_string()],
+            ..Default::default()
+        };
+        let value = |name: &str| ValueInfoProto {
+            name: name.to_string(),
+            r#type: Some(TypeProto {
+                value: Some(type_proto::Value::TensorType(type_proto::Tensor {
+                    elem_type: 1,
+                    shape: None,
+                })),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let model = ModelProto {
+            ir_version: 7,
+            opset_import: vec![OperatorSetIdProto {
+                domain: String::new(),
+                version: 13,
+            }],
+            graph: Some(GraphProto {
+                node: vec![
+                    node("Mul", &["x", "x"], "squares"),
+                    node("MatMul", &["squares", "w"], "y"),
+                ],
+                initializer: vec![TensorProto {
+                    name: "w".to_string(),
+                    dims: vec![3, 1],
+                    data_type: 1,
+                    float_data: vec![1.; 3],
+                    ..Default::default()
+                }],
+                input: vec![value("x")],
+                output: vec![value("y")],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        model.encode_to_vec()
+    }
+
+    #[test]
+    fn surrogates_predict_and_screen() {
+        let surrogate = Surrogate::from_bytes(&sphere(), 3).unwrap();
+        let position = Array1::from(vec![1., 2., -2.]);
+        assert!((surrogate.predict(&position).unwrap() - 9.).abs() < 1e-5);
+        assert!(surrogate.predict(&Array1::zeros(2)).is_err());
+
+        let objective = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let screened = Screened::new(&surrogate, &objective);
+        screened.evaluate(&Array1::from(vec![0.5, 0., 0.]));
+        screened.evaluate(&position);
+        screened.evaluate(&Array1::from(vec![0.1, 0., 0.]));
+        assert_eq!(screened.evaluations(), 2);
+    }
+
+    #[test]
+    fn optimizers_run_on_screened_objectives() {
+        let surrogate = Surrogate::from_bytes(&sphere(), 3).unwrap();
+        let sphere = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let screened = Screened::new(&surrogate, &sphere);
+        let objective = |x: &Array1<f64>| screened.evaluate(x);
+
+        let boundaries = CubicBoundary::new(3, -5., 5.);
+        let mut optimizer = Optimizer::new_seeded(20, boundaries, &objective, 1e-3, 3);
+        optimizer.max_iterations = Some(200);
+        optimizer.params = OptimizerParams {
+            inertia: 0.7,
+            cognitive: 1.5,
+            social: 1.5,
+        };
+        let result = optimizer.optimize();
+        assert!(result.best_score < 1e-2);
+        assert!(screened.evaluations() < result.evaluations);
+    }
+}
@@ -0,0 +1,81 @@
+//! An archive of basins that have already been searched to convergence.
+//!
+//! Turtles that wander into an archived region are sent somewhere else, so restarts spend their
+//! evaluations on parts of the space nobody has looked at yet.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ndarray::Array1;
+
+/// A ball around a basin that has been searched out.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TabuRegion {
+    pub center: Array1<f64>,
+    pub radius: f64,
+    /// The best score found in the basin.
+    pub score: f64,
+}
+
+impl TabuRegion {
+    pub fn contains(&self, position: &Array1<f64>) -> bool {
+        // Squared distances keep this free of floating point functions for `no_std` builds.
+        let distance = (position - &self.center).mapv(|x| x * x).sum();
+        distance < self.radius * self.radius
+    }
+}
+
+/// The regions turtles should stay out of. An empty archive, the default, forbids nothing.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TabuArchive {
+    pub regions: Vec<TabuRegion>,
+}
+
+impl TabuArchive {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, center: Array1<f64>, radius: f64, score: f64) {
+        self.regions.push(TabuRegion {
+            center,
+            radius: if radius < 0. { -radius } else { radius },
+            score,
+        });
+    }
+
+    /// Whether `position` lies inside any archived region.
+    pub fn contains(&self, position: &Array1<f64>) -> bool {
+        self.regions.iter().any(|region| region.contains(position))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CubicBoundary, Optimizer};
+
+    #[test]
+    fn restarts_keep_turtles_out_of_searched_basins() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(20, boundaries, &parabola, -1., 6);
+        for _ in 0..3 {
+            optimizer.iterate();
+        }
+        let best_score = optimizer.best_score;
+        optimizer.restart(0.5);
+
+        assert_eq!(optimizer.tabu.regions.len(), 1);
+        assert_eq!(optimizer.best_score, best_score);
+        for turtle in optimizer.turtles.iter() {
+            assert!(!optimizer.tabu.contains(&turtle.position));
+            assert_eq!(turtle.best_score, f64::INFINITY);
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! `tracing` instrumentation of a run, enabled with the `tracing` feature.
+//!
+//! Every iteration runs inside an `iteration` span and ends with an event carrying the iteration
+//! number, the best score, the diversity of the swarm and the evaluations spent so far. `optimize`
+//! runs inside an `optimize` span and ends with an event saying why it stopped. All of it is
+//! emitted under the `turtle_swarm_optimizer` target, at `debug` per iteration and `info` per run,
+//! so the telemetry lands in whatever subscriber the application has installed.
+//!
+//! With the feature on, `Optimizer::report` emits its run down as `info` events, one per line of
+//! the report, instead of printing to stdout. `report_to` writes to its writer either way.
+
+use tracing::{debug, debug_span, info, info_span, span::EnteredSpan};
+
+use crate::boundary::Boundary;
+use crate::{Optimizer, Termination};
+
+impl<B: Boundary> Optimizer<'_, B> {
+    pub(crate) fn run_span(&self) -> EnteredSpan {
+        info_span!(
+            target: "turtle_swarm_optimizer",
+            "optimize",
+            turtles = self.turtles.len(),
+            dimensions = self.boundaries.dimensions(),
+        )
+        .entered()
+    }
+
+    pub(crate) fn iteration_span(&self) -> EnteredSpan {
+        debug_span!(
+            target: "turtle_swarm_optimizer",
+            "iteration",
+            iteration = self.iterations,
+        )
+        .entered()
+    }
+
+    pub(crate) fn trace_iteration(&self) {
+        debug!(
+            target: "turtle_swarm_optimizer",
+            iteration = self.iterations,
+            best_score = self.best_score,
+            diversity = self.diversity(),
+            evaluations = self.evaluations,
+            "iteration done"
+        );
+    }
+
+    pub(crate) fn trace_termination(&self, termination: Termination) {
+        info!(
+            target: "turtle_swarm_optimizer",
+            iterations = self.iterations,
+            best_score = self.best_score,
+            evaluations = self.evaluations,
+            termination = %termination,
+            "run done"
+        );
+    }
+
+    pub(crate) fn trace_report(&self) {
+        let mut report = Vec::new();
+        self.report_to(&mut report)
+            .expect("reports are written to a vec");
+        for line in String::from_utf8_lossy(&report).lines() {
+            info!(target: "turtle_swarm_optimizer", "{}", line.trim_end());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CubicBoundary;
+    use ndarray::Array1;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    // Keeps the best score of every `iteration done` event and the names of the spans entered.
+    #[derive(Clone, Default)]
+    struct Recorder {
+        scores: Arc<Mutex<Vec<f64>>>,
+        spans: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    struct BestScore(Option<f64>);
+
+    impl Visit for BestScore {
+        fn record_f64(&mut self, field: &Field, value: f64) {
+            if field.name() == "best_score" {
+                self.0 = Some(value);
+            }
+        }
+
+        fn record_debug(&mut self, _: &Field, _: &dyn std::fmt::Debug) {}
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.target() == "turtle_swarm_optimizer"
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            self.spans.lock().unwrap().push(span.metadata().name());
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut best = BestScore(None);
+            event.record(&mut best);
+            if let Some(score) = best
+                .0
+                .filter(|_| event.fields().any(|f| f.name() == "diversity"))
+            {
+                self.scores.lock().unwrap().push(score);
+            }
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn iterations_are_traced() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(5, boundaries, &parabola, -1., 3);
+        optimizer.max_iterations = Some(4);
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || optimizer.optimize());
+
+        assert_eq!(
+            *recorder.scores.lock().unwrap().last().unwrap(),
+            optimizer.best_score
+        );
+        assert_eq!(recorder.scores.lock().unwrap().len(), 4);
+        let spans = recorder.spans.lock().unwrap();
+        assert_eq!(spans.first(), Some(&"optimize"));
+        assert_eq!(spans.iter().filter(|name| **name == "iteration").count(), 4);
+    }
+}
@@ -0,0 +1,141 @@
+//! Who a turtle looks up to.
+//!
+//! With the default `Star` topology every turtle is drawn towards the best position of the whole
+//! swarm, which converges quickly but can pull everyone into the first good basin found. The
+//! other topologies draw each turtle towards the best personal best among a few neighbours
+//! instead, so good news spreads through the swarm slowly and different parts of it can keep
+//! exploring different basins.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use rand::RngCore;
+
+use crate::{Direction, Turtle};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Topology {
+    /// Every turtle follows the swarm's best.
+    #[default]
+    Star,
+    /// Turtles sit on a ring and follow the best of the `radius` turtles on either side.
+    Ring { radius: usize },
+    /// Turtles sit on a wrapped grid about as wide as it is tall and follow the best of the
+    /// turtles to their left, right, top and bottom.
+    VonNeumann,
+    /// Each turtle follows the best of `k` other turtles drawn anew every iteration.
+    Random { k: usize },
+}
+
+impl Topology {
+    /// The turtles that turtle `turtle` of `count` takes into account, itself included.
+    pub fn neighbours(self, turtle: usize, count: usize, rng: &mut dyn RngCore) -> Vec<usize> {
+        let mut neighbours = Vec::new();
+        neighbours.push(turtle);
+        if count == 0 {
+            return neighbours;
+        }
+        match self {
+            Topology::Star => neighbours.extend((0..count).filter(|other| *other != turtle)),
+            Topology::Ring { radius } => {
+                for offset in 1..=radius.min(count / 2) {
+                    neighbours.push((turtle + offset) % count);
+                    neighbours.push((turtle + count - offset) % count);
+                }
+            }
+            Topology::VonNeumann => {
+                let mut columns = 1;
+                while columns * columns < count {
+                    columns += 1;
+                }
+                let columns = columns % count;
+                neighbours.push((turtle + 1) % count);
+                neighbours.push((turtle + count - 1) % count);
+                neighbours.push((turtle + columns) % count);
+                neighbours.push((turtle + count - columns) % count);
+            }
+            Topology::Random { k } => {
+                let drawn = rand::seq::index::sample(rng, count, (k + 1).min(count));
+                neighbours.extend(drawn.into_iter().filter(|other| *other != turtle).take(k));
+            }
+        }
+        neighbours.sort_unstable();
+        neighbours.dedup();
+        neighbours
+    }
+
+    /// The turtle whose personal best each turtle follows, or `None` for the star, where
+    /// everyone follows the swarm's best. Turtles whose neighbours have not been scored yet
+    /// follow their own.
+    pub(crate) fn leaders(
+        self,
+        turtles: &[Turtle],
+        direction: Direction,
+        rng: &mut dyn RngCore,
+    ) -> Option<Vec<usize>> {
+        if self == Topology::Star {
+            return None;
+        }
+        let leaders = (0..turtles.len())
+            .map(|turtle| {
+                self.neighbours(turtle, turtles.len(), rng)
+                    .into_iter()
+                    .fold(turtle, |leader, other| {
+                        if direction
+                            .is_better(turtles[other].best_score, turtles[leader].best_score)
+                        {
+                            other
+                        } else {
+                            leader
+                        }
+                    })
+            })
+            .collect();
+        Some(leaders)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CubicBoundary, Optimizer, OptimizerParams};
+    use ndarray::Array1;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha12Rng;
+
+    #[test]
+    fn neighbourhoods_follow_the_topology() {
+        let mut rng = ChaCha12Rng::seed_from_u64(1);
+        let ring = Topology::Ring { radius: 1 };
+        assert_eq!(ring.neighbours(0, 10, &mut rng), vec![0, 1, 9]);
+        assert_eq!(
+            Topology::VonNeumann.neighbours(5, 9, &mut rng),
+            vec![2, 4, 5, 6, 8]
+        );
+        let random = Topology::Random { k: 3 }.neighbours(4, 10, &mut rng);
+        assert_eq!(random.len(), 4);
+        assert!(random.contains(&4));
+
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(12, boundaries, &parabola, 1e-3, 2);
+        optimizer.params = OptimizerParams {
+            inertia: 0.6,
+            cognitive: 0.6,
+            social: 0.6,
+        };
+        optimizer.topology = ring;
+        optimizer.max_iterations = Some(300);
+        // Each turtle follows the best of its ring neighbours, so in the first iteration the
+        // leaders are spread around the swarm rather than all pointing at the single best.
+        optimizer.iterate();
+        let leaders = ring
+            .leaders(&optimizer.turtles, optimizer.direction(), &mut rng)
+            .unwrap();
+        let mut distinct = leaders.clone();
+        distinct.sort_unstable();
+        distinct.dedup();
+        assert!(distinct.len() > 1);
+        assert!(optimizer.optimize().best_score <= 1e-3);
+    }
+}
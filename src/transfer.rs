@@ -0,0 +1,167 @@
+//! Moving solutions between related search spaces.
+//!
+//! When a problem is refined or extended, a swarm that converged on the old definition is a better
+//! start than a uniform scatter. A `Transfer` describes each dimension of the new space as an
+//! affine rescaling of an old dimension or as a fixed default, and maps positions, personal bests
+//! and whole optimizers across.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use ndarray::Array1;
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+
+use crate::boundary::Boundary;
+use crate::{CubicBoundary, Optimizer, Turtle, TurtleStats};
+
+/// Where one dimension of the new space comes from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mapping {
+    /// Dimension `source` of the old space, rescaled so `from` lands on `to`.
+    Rescale {
+        source: usize,
+        from: (f64, f64),
+        to: (f64, f64),
+    },
+    /// A dimension the old space did not have, set to a default.
+    Fixed(f64),
+}
+
+impl Mapping {
+    fn map(&self, position: &Array1<f64>) -> f64 {
+        match *self {
+            Mapping::Rescale { source, from, to } => {
+                to.0 + (position[source] - from.0) * scale(from, to)
+            }
+            Mapping::Fixed(value) => value,
+        }
+    }
+}
+
+// How much a rescaling stretches distances. Collapsed source intervals map onto `to.0`.
+fn scale(from: (f64, f64), to: (f64, f64)) -> f64 {
+    if from.1 == from.0 {
+        0.
+    } else {
+        (to.1 - to.0) / (from.1 - from.0)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transfer {
+    /// One mapping per dimension of the new space.
+    pub mappings: Vec<Mapping>,
+}
+
+impl Transfer {
+    pub fn new(mappings: Vec<Mapping>) -> Self {
+        Self { mappings }
+    }
+
+    /// Rescales the shared dimensions of two cubes onto each other. Dimensions only the new cube
+    /// has are set to its center; dimensions only the old cube has are dropped.
+    pub fn between(from: &CubicBoundary, to: &CubicBoundary) -> Self {
+        let center = (to.lower + to.upper) / 2.;
+        Self::new(
+            (0..to.shape)
+                .map(|dimension| {
+                    if dimension < from.shape {
+                        Mapping::Rescale {
+                            source: dimension,
+                            from: (from.lower, from.upper),
+                            to: (to.lower, to.upper),
+                        }
+                    } else {
+                        Mapping::Fixed(center)
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    pub fn map(&self, position: &Array1<f64>) -> Array1<f64> {
+        self.mappings
+            .iter()
+            .map(|mapping| mapping.map(position))
+            .collect()
+    }
+
+    fn map_velocity(&self, velocity: &Array1<f64>) -> Array1<f64> {
+        self.mappings
+            .iter()
+            .map(|mapping| match *mapping {
+                Mapping::Rescale { source, from, to } => velocity[source] * scale(from, to),
+                Mapping::Fixed(_) => f64::EPSILON,
+            })
+            .collect()
+    }
+
+    /// Starts a new run on `boundaries` and `objective_function` with `previous`'s turtles and
+    /// bests mapped across and projected into the new boundary. The carried scores belong to the
+    /// old problem; call `reevaluate_bests` to rescore them.
+    pub fn apply<'a, B: Boundary, P: Boundary>(
+        &self,
+        previous: &Optimizer<'_, P>,
+        boundaries: B,
+        objective_function: &'a (dyn Fn(&Array1<f64>) -> f64 + Sync),
+    ) -> Optimizer<'a, B> {
+        assert_eq!(
+            self.mappings.len(),
+            boundaries.dimensions(),
+            "a transfer needs one mapping per dimension of the new boundary"
+        );
+        let rng = ChaCha12Rng::from_rng(previous.rng.clone()).expect("chacha provides a seed");
+        let mut optimizer =
+            Optimizer::with_rng(0, boundaries, objective_function, previous.goal, rng);
+        let project = |position: &Array1<f64>| {
+            let mut position = self.map(position);
+            optimizer.boundaries.project(&mut position);
+            position
+        };
+        let turtles = previous
+            .turtles
+            .iter()
+            .map(|turtle| Turtle {
+                position: project(&turtle.position),
+                velocity: self.map_velocity(&turtle.velocity),
+                best_score: turtle.best_score,
+                best_position: project(&turtle.best_position),
+                stats: TurtleStats::default(),
+            })
+            .collect();
+        let best_position = project(&previous.best_position);
+        optimizer.turtles = turtles;
+        optimizer.best_position = best_position;
+        optimizer.best_score = previous.best_score;
+        optimizer.update_mode = previous.update_mode;
+        optimizer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+
+    #[test]
+    fn converged_swarms_move_into_an_extended_space() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let old = CubicBoundary::new(2, -1., 1.);
+        let new = CubicBoundary::new(3, 0., 10.);
+        let transfer = Transfer::between(&old, &new);
+        assert_eq!(transfer.map(&array![-1., 0.5]), array![0., 7.5, 5.]);
+
+        let mut previous = Optimizer::new_seeded(5, old, &parabola, -1., 3);
+        previous.iterate();
+        let shifted = |x: &Array1<f64>| x.mapv(|x| (x - 5.) * (x - 5.)).sum();
+        let mut optimizer = transfer.apply(&previous, new, &shifted);
+        optimizer.reevaluate_bests();
+
+        assert_eq!(optimizer.turtles.len(), 5);
+        for (old, turtle) in previous.turtles.iter().zip(optimizer.turtles.iter()) {
+            assert_eq!(turtle.position, transfer.map(&old.position));
+            assert!((turtle.best_score - 25. * parabola(&old.best_position)).abs() < 1e-9);
+        }
+        assert!((optimizer.best_score - 25. * previous.best_score).abs() < 1e-9);
+    }
+}
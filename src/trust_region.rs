@@ -0,0 +1,236 @@
+//! A trust-region phase for polishing the swarm's best position to high precision.
+//!
+//! Each round samples densely inside a box around the incumbent. The box grows when a good share
+//! of the samples improve on the incumbent and shrinks when none do, until it is smaller than
+//! `min_radius` or the rounds run out. `Optimizer::refine` runs the same search around any point,
+//! such as each of several candidate designs, inside a box of its own.
+
+use ndarray::Array1;
+use rand::Rng;
+
+use crate::Optimizer;
+
+#[derive(Clone, Copy, Debug)]
+pub struct TrustRegion {
+    /// The starting half-width of the box, as a fraction of the search space's width.
+    pub radius: f64,
+    /// The half-width below which refinement stops, also as a fraction of the width.
+    pub min_radius: f64,
+    /// The largest half-width the box may grow to, also as a fraction of the width.
+    pub max_radius: f64,
+    /// Positions evaluated per round.
+    pub samples: usize,
+    pub rounds: usize,
+    /// The share of improving samples at which the box grows.
+    pub expand_ratio: f64,
+    pub expansion: f64,
+    pub contraction: f64,
+}
+
+impl Default for TrustRegion {
+    fn default() -> Self {
+        Self {
+            radius: 0.1,
+            min_radius: 1e-9,
+            max_radius: 0.5,
+            samples: 16,
+            rounds: 100,
+            expand_ratio: 0.25,
+            expansion: 2.,
+            contraction: 0.5,
+        }
+    }
+}
+
+impl TrustRegion {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Refines `optimizer`'s best position in place and returns the number of evaluations spent,
+    /// which are added to `optimizer.evaluations` as well. An optimizer that has not scored
+    /// anything yet is left alone.
+    pub fn refine(&self, optimizer: &mut Optimizer) -> usize {
+        if !optimizer.best_score.is_finite() {
+            return 0;
+        }
+        let width = optimizer.boundaries.upper - optimizer.boundaries.lower;
+        let shape = optimizer.boundaries.shape;
+        let lower = Array1::from_elem(shape, optimizer.boundaries.lower);
+        let upper = Array1::from_elem(shape, optimizer.boundaries.upper);
+        let refinement = self.search(
+            optimizer,
+            (&lower, &upper),
+            width,
+            optimizer.best_position.clone(),
+            optimizer.best_score,
+            usize::MAX,
+        );
+        optimizer.best_score = refinement.score;
+        optimizer.best_position = refinement.position;
+        refinement.evaluations
+    }
+
+    // Trust-region sampling from `center` within the per-dimension `bounds`, with radii relative
+    // to `width`, stopping early once `budget` evaluations are spent.
+    fn search(
+        &self,
+        optimizer: &mut Optimizer,
+        (lower, upper): (&Array1<f64>, &Array1<f64>),
+        width: f64,
+        mut center: Array1<f64>,
+        mut score: f64,
+        budget: usize,
+    ) -> Refinement {
+        let mut radius = self.radius * width;
+        let mut evaluations = 0;
+
+        for _ in 0..self.rounds {
+            if radius < self.min_radius * width || self.samples == 0 || evaluations >= budget {
+                break;
+            }
+            let round = center.clone();
+            let mut improvements = 0;
+            for _ in 0..self.samples.min(budget - evaluations) {
+                let candidate: Array1<f64> = round
+                    .iter()
+                    .zip(lower.iter().zip(upper.iter()))
+                    .map(|(x, (lower, upper))| {
+                        let low = (x - radius).max(*lower);
+                        let high = (x + radius).min(*upper);
+                        if low < high {
+                            optimizer.rng.gen_range(low..high)
+                        } else {
+                            *x
+                        }
+                    })
+                    .collect();
+                let candidate_score = (optimizer.objective_function)(&candidate);
+                evaluations += 1;
+                optimizer.evaluations += 1;
+                if optimizer.direction().is_better(candidate_score, score) {
+                    score = candidate_score;
+                    center = candidate;
+                    improvements += 1;
+                }
+            }
+
+            let ratio = improvements as f64 / self.samples as f64;
+            if ratio >= self.expand_ratio {
+                radius = (radius * self.expansion).min(self.max_radius * width);
+            } else if improvements == 0 {
+                radius *= self.contraction;
+            }
+        }
+        Refinement {
+            position: center,
+            score,
+            evaluations,
+        }
+    }
+}
+
+/// The outcome of polishing a single point.
+#[derive(Clone, Debug)]
+pub struct Refinement {
+    pub position: Array1<f64>,
+    pub score: f64,
+    pub evaluations: usize,
+}
+
+impl Optimizer<'_> {
+    /// Polishes `point`, which need not be the best position, with a trust-region search confined
+    /// to the box `point ± radius` within the boundaries, spending at most `budget` evaluations.
+    /// The optimizer's bests are left alone; it only lends its random number generator and counts
+    /// the evaluations in `evaluations`.
+    pub fn refine(&mut self, point: &Array1<f64>, radius: f64, budget: usize) -> Refinement {
+        let radius = radius.abs();
+        let lower = point.mapv(|x| (x - radius).max(self.boundaries.lower));
+        let upper = point.mapv(|x| (x + radius).min(self.boundaries.upper));
+        let center = ndarray::Zip::from(point)
+            .and(&lower)
+            .and(&upper)
+            .map_collect(|x, low, high| x.max(*low).min(*high));
+        if budget == 0 {
+            return Refinement {
+                position: center,
+                score: self.direction().worst(),
+                evaluations: 0,
+            };
+        }
+        let score = (self.objective_function)(&center);
+        self.evaluations += 1;
+        // Radii are relative to the box, which the region may fill but never outgrow.
+        let region = TrustRegion {
+            radius: 0.5,
+            max_radius: 1.,
+            ..TrustRegion::default()
+        };
+        let mut refinement =
+            region.search(self, (&lower, &upper), radius, center, score, budget - 1);
+        refinement.evaluations += 1;
+        refinement
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CubicBoundary, Direction};
+
+    #[test]
+    fn refinement_reaches_high_precision() {
+        let shifted = |x: &Array1<f64>| x.mapv(|x| (x - 0.3).powi(2)).sum();
+        let boundaries = CubicBoundary::new(3, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &shifted, -1., 2);
+        optimizer.iterate();
+        let before = optimizer.best_score;
+
+        let evaluations = TrustRegion::new().refine(&mut optimizer);
+        assert!(evaluations > 0);
+        assert_eq!(optimizer.evaluations, 10 + evaluations);
+        assert!(optimizer.best_score < before);
+        assert!(optimizer.best_score < 1e-10);
+    }
+
+    #[test]
+    fn arbitrary_points_are_polished_within_their_box() {
+        // Two basins, at -0.5 and 0.5; the search around -0.4 must stay in the left one.
+        let wells = |x: &Array1<f64>| x.mapv(|x| (x * x - 0.25).powi(2)).sum();
+        let boundaries = CubicBoundary::new(2, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(4, boundaries, &wells, -1., 3);
+        let point = Array1::from_elem(2, -0.4);
+
+        let refinement = optimizer.refine(&point, 0.3, 200);
+        assert!(refinement.evaluations <= 200);
+        assert_eq!(optimizer.evaluations, refinement.evaluations);
+        assert!(refinement.score < 1e-6);
+        assert!(refinement
+            .position
+            .iter()
+            .all(|x| (-0.7..=-0.1).contains(x)));
+        assert_eq!(optimizer.best_score, f64::INFINITY);
+    }
+
+    #[test]
+    fn maximizers_are_refined_upwards() {
+        let peak = |x: &Array1<f64>| -x.mapv(|x| (x - 0.3).powi(2)).sum();
+        let boundaries = CubicBoundary::new(3, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(10, boundaries, &peak, 1., 2);
+        optimizer.set_direction(Direction::Maximize);
+        optimizer.iterate();
+        let before = optimizer.best_score;
+
+        TrustRegion::new().refine(&mut optimizer);
+        assert!(optimizer.best_score > before);
+        assert!(optimizer.best_score > -1e-10);
+        assert_eq!(peak(&optimizer.best_position), optimizer.best_score);
+
+        let refinement = optimizer.refine(&Array1::zeros(3), 0.5, 100);
+        assert!(refinement.score > peak(&Array1::zeros(3)));
+        assert_eq!(
+            optimizer.refine(&Array1::zeros(3), 0.5, 0).score,
+            f64::NEG_INFINITY
+        );
+    }
+}
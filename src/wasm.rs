@@ -0,0 +1,96 @@
+//! WebAssembly bindings, enabled with the `wasm` feature.
+//!
+//! The objective is a JavaScript function taking a `Float64Array` and returning a number, so
+//! turtles can be put to work in browser demos: `wasm-pack build bindings --features wasm`.
+
+use js_sys::{Float64Array, Function};
+use wasm_bindgen::prelude::*;
+
+use crate::{external_objective, CubicBoundary, Optimizer, OptimizerParams, Termination};
+
+/// The outcome of an optimization run in the browser.
+#[wasm_bindgen]
+pub struct WasmResult {
+    best_score: f64,
+    best_position: Vec<f64>,
+    iterations: usize,
+    evaluations: usize,
+    termination: Termination,
+}
+
+#[wasm_bindgen]
+impl WasmResult {
+    #[wasm_bindgen(getter, js_name = bestScore)]
+    pub fn best_score(&self) -> f64 {
+        self.best_score
+    }
+
+    #[wasm_bindgen(getter, js_name = bestPosition)]
+    pub fn best_position(&self) -> Vec<f64> {
+        self.best_position.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+
+    /// Why the run stopped, in words.
+    #[wasm_bindgen(getter)]
+    pub fn termination(&self) -> String {
+        self.termination.to_string()
+    }
+}
+
+/// Minimizes `objective` inside the cube `[lower, upper] ** dimensions` until the best score
+/// reaches `goal`, or until `max_iterations` have been spent when it is given. An exception
+/// thrown by the objective stops the run and is rethrown. `inertia`, `cognitive` and `social`
+/// replace the coefficients of the velocity update when they are given.
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn optimize(
+    objective: &Function,
+    dimensions: usize,
+    lower: f64,
+    upper: f64,
+    goal: f64,
+    turtles: usize,
+    max_iterations: Option<usize>,
+    seed: Option<u64>,
+    inertia: Option<f64>,
+    cognitive: Option<f64>,
+    social: Option<f64>,
+) -> Result<WasmResult, JsValue> {
+    let boundaries = CubicBoundary::new(dimensions, lower, upper);
+    let mut optimizer = match seed {
+        Some(seed) => Optimizer::new_seeded(turtles, boundaries, &external_objective, goal, seed),
+        None => Optimizer::new(turtles, boundaries, &external_objective, goal),
+    };
+    optimizer.max_iterations = max_iterations;
+    let defaults = OptimizerParams::default();
+    optimizer.params = OptimizerParams {
+        inertia: inertia.unwrap_or(defaults.inertia),
+        cognitive: cognitive.unwrap_or(defaults.cognitive),
+        social: social.unwrap_or(defaults.social),
+    };
+    // JavaScript values are tied to their thread, so the positions are scored through ask and tell.
+    let result = optimizer.optimize_with(|position| {
+        let array = Float64Array::from(&position.to_vec()[..]);
+        objective
+            .call1(&JsValue::NULL, &array)
+            .map(|score| score.as_f64().unwrap_or(f64::NAN))
+    })?;
+
+    Ok(WasmResult {
+        best_score: result.best_score,
+        best_position: result.best_position.to_vec(),
+        iterations: result.iterations,
+        evaluations: result.evaluations,
+        termination: result.termination,
+    })
+}
@@ -0,0 +1,116 @@
+//! Shared read access to the best result of a running optimizer.
+//!
+//! A `Watch` is a cheap handle that can be cloned and sent to other threads, so a UI or a
+//! controller can look at the live best while `optimize` keeps the turtles busy on a worker.
+
+use std::sync::{Arc, RwLock};
+
+use ndarray::Array1;
+
+use crate::Optimizer;
+
+/// The best result at the end of an iteration.
+#[derive(Clone, Debug)]
+pub struct Best {
+    pub score: f64,
+    pub position: Array1<f64>,
+    pub iterations: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Watch {
+    best: Arc<RwLock<Best>>,
+}
+
+impl Watch {
+    // A watch on nothing yet, for a run that has not started.
+    pub(crate) fn new() -> Self {
+        Self {
+            best: Arc::new(RwLock::new(Best {
+                score: f64::INFINITY,
+                position: Array1::zeros(0),
+                iterations: 0,
+            })),
+        }
+    }
+
+    /// A copy of the latest published best.
+    pub fn best(&self) -> Best {
+        // The lock is only ever held to copy a `Best`, so a poisoned lock still holds a whole one.
+        match self.best.read() {
+            Ok(best) => best.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// The latest published best score, without copying the position.
+    pub fn score(&self) -> f64 {
+        match self.best.read() {
+            Ok(best) => best.score,
+            Err(poisoned) => poisoned.into_inner().score,
+        }
+    }
+
+    pub(crate) fn publish<B>(&self, optimizer: &Optimizer<'_, B>) {
+        let mut best = match self.best.write() {
+            Ok(best) => best,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        best.score = optimizer.best_score;
+        best.position.clone_from(&optimizer.best_position);
+        best.iterations = optimizer.iterations;
+    }
+}
+
+impl<B> Optimizer<'_, B> {
+    /// A handle on the best result, updated after every iteration. All handles returned by one
+    /// optimizer share the same state.
+    pub fn watch(&mut self) -> Watch {
+        if let Some(watch) = &self.watch {
+            return watch.clone();
+        }
+        let watch = Watch::new();
+        watch.publish(self);
+        self.watch = Some(watch.clone());
+        watch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CubicBoundary;
+
+    #[test]
+    fn watchers_on_other_threads_see_the_live_best() {
+        let parabola = |x: &Array1<f64>| x.mapv(|x| x * x).sum();
+        let boundaries = CubicBoundary::new(3, -1., 1.);
+        let mut optimizer = Optimizer::new_seeded(8, boundaries, &parabola, -1., 4);
+        let watch = optimizer.watch();
+        assert_eq!(watch.score(), f64::INFINITY);
+
+        let seen = std::thread::scope(|scope| {
+            let reader = watch.clone();
+            let handle = scope.spawn(move || {
+                let mut scores = Vec::new();
+                while scores.len() < 3 {
+                    let best = reader.best();
+                    if best.iterations > scores.len() {
+                        scores.push(best.score);
+                    }
+                }
+                scores
+            });
+            for _ in 0..3 {
+                optimizer.iterate();
+            }
+            handle.join().unwrap()
+        });
+
+        assert!(seen.iter().all(|score| score.is_finite()));
+        let best = optimizer.watch().best();
+        assert_eq!(best.iterations, 3);
+        assert_eq!(best.score, optimizer.best_score);
+        assert_eq!(best.position, optimizer.best_position);
+    }
+}